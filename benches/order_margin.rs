@@ -18,6 +18,15 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     let position = Position::Neutral;
     let init_margin_req = Decimal::ONE;
+    let contract_spec = ContractSpecification::new(
+        leverage!(1),
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        Fee::from(Decimal::try_from_scaled(2, 4).unwrap()),
+        Fee::from(Decimal::try_from_scaled(6, 4).unwrap()),
+    )
+    .expect("works");
 
     for n in 1..20 {
         group.bench_with_input(BenchmarkId::new("insert", n), &n, |b, _n| {
@@ -39,7 +48,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(order.clone()),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .expect("Can insert")
                     }
@@ -56,7 +65,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     let mut om = OrderMargin::new(NonZeroUsize::new(n).unwrap());
                     let mut balances = Balances::new(QuoteCurrency::new(1_000_000, 0));
                     for order in orders.iter() {
-                        om.try_insert(order.clone(), &mut balances, &position, init_margin_req)
+                        om.try_insert(order.clone(), &mut balances, &position, &contract_spec)
                             .expect("Can insert");
                     }
                     (om, balances)
@@ -67,7 +76,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                             black_box(order.clone()),
                             &mut balances,
                             &position,
-                            init_margin_req,
+                            &contract_spec,
                         )
                     }
                 },
@@ -88,7 +97,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(order.clone()),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .unwrap()
                     }
@@ -101,7 +110,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(CancelBy::OrderId(order.id())),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .expect("Can insert");
                     }
@@ -114,7 +123,6 @@ fn criterion_benchmark(c: &mut Criterion) {
                 order.clone().into_pending(meta)
             }));
             let position = Position::Neutral;
-            let init_margin_req = Decimal::one();
             b.iter_with_setup(
                 || {
                     let mut order_margin = OrderMargin::new(NonZeroUsize::new(n).unwrap());
@@ -125,7 +133,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(order.clone()),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .unwrap()
                     }
@@ -145,7 +153,6 @@ fn criterion_benchmark(c: &mut Criterion) {
                 BaseCurrency::new(2, 0),
                 QuoteCurrency::new(100, 0),
             ));
-            let init_margin_req = Decimal::one();
             b.iter_with_setup(
                 || {
                     let mut order_margin = OrderMargin::new(NonZeroUsize::new(n).unwrap());
@@ -156,7 +163,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(order.clone()),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .unwrap()
                     }
@@ -176,7 +183,6 @@ fn criterion_benchmark(c: &mut Criterion) {
                 BaseCurrency::new(2, 0),
                 QuoteCurrency::new(100, 0),
             ));
-            let init_margin_req = Decimal::one();
             b.iter_with_setup(
                 || {
                     let mut order_margin = OrderMargin::new(NonZeroUsize::new(n).unwrap());
@@ -187,7 +193,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 black_box(order.clone()),
                                 &mut balances,
                                 &position,
-                                init_margin_req,
+                                &contract_spec,
                             )
                             .unwrap()
                     }
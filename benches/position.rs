@@ -2,7 +2,6 @@
 
 use std::hint::black_box;
 
-use const_decimal::Decimal;
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use lfest::prelude::*;
 use rand::{Rng, SeedableRng, rngs::SmallRng};
@@ -13,6 +12,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("Position");
     group.throughput(Throughput::Elements(N as u64));
 
+    let contract_spec = ContractSpecification::default();
     let mut rng = SmallRng::seed_from_u64(0);
     let starting_positions = [
         Position::Neutral,
@@ -44,7 +44,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                                 *fill_price,
                                 side,
                                 &mut balances,
-                                Decimal::ONE,
+                                &contract_spec,
                             ));
                         }
                     },
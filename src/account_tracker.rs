@@ -0,0 +1,443 @@
+use std::marker::PhantomData;
+
+use getset::{CopyGetters, Getters};
+
+use crate::types::{LnReturns, MarginCurrency, Mon, NANOS_PER_SECOND, TimestampNs};
+
+const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+/// Tracks the performance of the account by sampling its equity on a fixed time grid,
+/// along with the traded notional value, the fees and the realized profit and loss of the fills.
+/// It is enabled with `Config::with_account_tracker` and fed by `Exchange::update_state`.
+/// The returns and drawdowns are adjusted for the deposits and withdrawals of the account.
+///
+/// Generics:
+/// - `I`: The numeric data type of currencies.
+/// - `D`: The constant decimal precision of the currencies.
+/// - `BaseOrQuote`: The margin currency of the account.
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct AccountTracker<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: MarginCurrency<I, D>,
+{
+    /// The time between two consecutive equity samples.
+    #[getset(get_copy = "pub")]
+    sample_interval_ns: TimestampNs,
+
+    // The timestamp of the next equity sample, once the first market update was observed.
+    next_sample_ts_ns: Option<TimestampNs>,
+
+    /// The sampled equity, being the sum of the balances plus the unrealized profit and loss,
+    /// as of the first market update at or after each point of the time grid.
+    #[getset(get = "pub")]
+    equity_curve: Vec<(TimestampNs, BaseOrQuote)>,
+
+    // The net cash flow into the account since the previous sample, for each sample of the `equity_curve`.
+    sample_cash_flows: Vec<BaseOrQuote>,
+
+    // The net cash flow into the account since the latest sample.
+    pending_cash_flow: BaseOrQuote,
+
+    /// The deposits (positive) and withdrawals (negative) in the order they occurred.
+    #[getset(get = "pub")]
+    cash_flows: Vec<(TimestampNs, BaseOrQuote)>,
+
+    /// The total notional value of all fills.
+    #[getset(get_copy = "pub")]
+    traded_notional: BaseOrQuote,
+
+    /// The total fees paid, including liquidation fees.
+    #[getset(get_copy = "pub")]
+    fees_paid: BaseOrQuote,
+
+    /// The number of fills reducing the position with a realized profit.
+    #[getset(get_copy = "pub")]
+    num_winning_trades: usize,
+
+    /// The number of fills reducing the position with a realized loss.
+    #[getset(get_copy = "pub")]
+    num_losing_trades: usize,
+
+    _i: PhantomData<I>,
+}
+
+impl<I, const D: u8, BaseOrQuote> AccountTracker<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: MarginCurrency<I, D>,
+{
+    /// Create a new instance, which samples the equity every `sample_interval_ns`.
+    pub(crate) fn new(sample_interval_ns: TimestampNs) -> Self {
+        assert2::debug_assert!(sample_interval_ns > TimestampNs::from(0));
+        Self {
+            sample_interval_ns,
+            next_sample_ts_ns: None,
+            equity_curve: Vec::new(),
+            sample_cash_flows: Vec::new(),
+            pending_cash_flow: BaseOrQuote::zero(),
+            cash_flows: Vec::new(),
+            traded_notional: BaseOrQuote::zero(),
+            fees_paid: BaseOrQuote::zero(),
+            num_winning_trades: 0,
+            num_losing_trades: 0,
+            _i: PhantomData,
+        }
+    }
+
+    /// Sample the `equity` at every point of the time grid up to `ts_ns`, that has not been sampled yet.
+    /// The time grid starts at the first observed timestamp.
+    pub(crate) fn sample_equity(&mut self, ts_ns: TimestampNs, equity: BaseOrQuote) {
+        let mut next_sample_ts_ns = self.next_sample_ts_ns.unwrap_or(ts_ns);
+        while next_sample_ts_ns <= ts_ns {
+            self.equity_curve.push((next_sample_ts_ns, equity));
+            self.sample_cash_flows.push(self.pending_cash_flow);
+            self.pending_cash_flow = BaseOrQuote::zero();
+            next_sample_ts_ns += self.sample_interval_ns;
+        }
+        self.next_sample_ts_ns = Some(next_sample_ts_ns);
+    }
+
+    /// Record a fill with the `notional` value and the `fee`,
+    /// along with the `realized_pnl` if the fill reduced the position.
+    pub(crate) fn record_fill(
+        &mut self,
+        notional: BaseOrQuote,
+        fee: BaseOrQuote,
+        realized_pnl: Option<BaseOrQuote>,
+    ) {
+        self.traded_notional += notional;
+        self.fees_paid += fee;
+        match realized_pnl {
+            Some(pnl) if pnl > BaseOrQuote::zero() => self.num_winning_trades += 1,
+            Some(pnl) if pnl < BaseOrQuote::zero() => self.num_losing_trades += 1,
+            _ => {}
+        }
+    }
+
+    /// Record a `fee` which is not part of a fill, e.g. a liquidation fee.
+    pub(crate) fn record_fee(&mut self, fee: BaseOrQuote) {
+        self.fees_paid += fee;
+    }
+
+    /// Record a deposit (positive `amount`) or withdrawal (negative `amount`) at `ts_ns`,
+    /// which is excluded from the return of the sample period it falls into.
+    pub(crate) fn record_cash_flow(&mut self, ts_ns: TimestampNs, amount: BaseOrQuote) {
+        self.cash_flows.push((ts_ns, amount));
+        self.pending_cash_flow += amount;
+    }
+
+    /// The natural logarithmic returns between consecutive equity samples,
+    /// excluding the deposits and withdrawals in between.
+    /// Samples with a non-positive equity are skipped.
+    pub fn ln_returns(&self) -> Vec<f64> {
+        self.equity_curve
+            .windows(2)
+            .zip(self.sample_cash_flows.iter().skip(1))
+            .map(|(w, cash_flow)| -> (f64, f64) { (w[0].1.into(), (w[1].1 - *cash_flow).into()) })
+            .filter(|(prev, curr)| *prev > 0.0 && *curr > 0.0)
+            .map(|(prev, curr)| (curr / prev).ln())
+            .collect()
+    }
+
+    /// The annualized Sharpe ratio of the sampled returns, assuming a risk-free rate of zero.
+    /// Returns `None` if there are fewer than two returns or they don't vary.
+    pub fn sharpe_ratio(&self) -> Option<f64> {
+        let returns = self.ln_returns();
+        sharpe_ratio(LnReturns(&returns), self.periods_per_year())
+    }
+
+    /// The annualized Sortino ratio of the sampled returns, which only penalizes the downside deviation.
+    /// Returns `None` if there are fewer than two returns or none of them are negative.
+    pub fn sortino_ratio(&self) -> Option<f64> {
+        let returns = self.ln_returns();
+        sortino_ratio(LnReturns(&returns), self.periods_per_year())
+    }
+
+    /// The maximum drawdown of the equity curve as a fraction of its preceding peak, e.g. `0.2` for 20%.
+    pub fn max_drawdown(&self) -> f64 {
+        self.max_drawdown_with_duration().0
+    }
+
+    /// The duration of the maximum drawdown, from its preceding peak until the equity recovered to it,
+    /// or until the latest sample if it has not recovered yet.
+    pub fn max_drawdown_duration(&self) -> TimestampNs {
+        self.max_drawdown_with_duration().1
+    }
+
+    /// The annualized return relative to the maximum drawdown.
+    /// Returns `None` if there are no returns or no drawdown.
+    pub fn calmar_ratio(&self) -> Option<f64> {
+        let returns = self.ln_returns();
+        if returns.is_empty() {
+            return None;
+        }
+        let max_drawdown = self.max_drawdown();
+        if max_drawdown <= 0.0 {
+            return None;
+        }
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let annualized_return = (mean * self.periods_per_year()).exp() - 1.0;
+        Some(annualized_return / max_drawdown)
+    }
+
+    /// The traded notional value relative to the average sampled equity.
+    /// Returns `None` if there are no samples with a positive average.
+    pub fn turnover(&self) -> Option<f64> {
+        if self.equity_curve.is_empty() {
+            return None;
+        }
+        let avg_equity = self
+            .equity_curve
+            .iter()
+            .map(|(_, equity)| Into::<f64>::into(*equity))
+            .sum::<f64>()
+            / self.equity_curve.len() as f64;
+        if avg_equity <= 0.0 {
+            return None;
+        }
+        Some(Into::<f64>::into(self.traded_notional) / avg_equity)
+    }
+
+    /// The share of the gross profit, being the change in equity before fees and cash flows, that was paid as fees.
+    /// Returns `None` if there are fewer than two samples or the gross profit is not positive.
+    pub fn fee_share(&self) -> Option<f64> {
+        let (first, last) = match (self.equity_curve.first(), self.equity_curve.last()) {
+            (Some((_, first)), Some((_, last))) if self.equity_curve.len() > 1 => (*first, *last),
+            _ => return None,
+        };
+        let net_cash_flow = self
+            .sample_cash_flows
+            .iter()
+            .skip(1)
+            .fold(BaseOrQuote::zero(), |acc, cash_flow| acc + *cash_flow);
+        let gross_profit = last - first - net_cash_flow + self.fees_paid;
+        if gross_profit <= BaseOrQuote::zero() {
+            return None;
+        }
+        Some(Into::<f64>::into(self.fees_paid) / Into::<f64>::into(gross_profit))
+    }
+
+    /// The fraction of position reducing fills with a realized profit.
+    /// Returns `None` if the position has not been reduced with a profit or loss yet.
+    pub fn win_rate(&self) -> Option<f64> {
+        let num_trades = self.num_winning_trades + self.num_losing_trades;
+        if num_trades == 0 {
+            return None;
+        }
+        Some(self.num_winning_trades as f64 / num_trades as f64)
+    }
+
+    /// Write the equity curve as csv with the columns `timestamp_ns` and `equity`, e.g. for plotting.
+    pub fn write_equity_curve_csv<W: std::io::Write>(&self, writer: W) -> csv::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["timestamp_ns", "equity"])?;
+        for (ts_ns, equity) in self.equity_curve.iter() {
+            writer.write_record([ts_ns.to_string(), equity.as_ref().to_string()])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn periods_per_year(&self) -> f64 {
+        (SECONDS_PER_YEAR * NANOS_PER_SECOND) as f64 / self.sample_interval_ns.get() as f64
+    }
+
+    /// The equity curve scaled by the cash flows before each sample,
+    /// so that deposits and withdrawals don't show up as gains or losses.
+    /// Without cash flows, it equals the equity curve.
+    fn cash_flow_adjusted_equity(&self) -> Vec<(TimestampNs, f64)> {
+        let mut scale = 1.0;
+        self.equity_curve
+            .iter()
+            .zip(self.sample_cash_flows.iter())
+            .enumerate()
+            .map(|(i, ((ts_ns, equity), cash_flow))| {
+                let equity: f64 = (*equity).into();
+                let cash_flow: f64 = (*cash_flow).into();
+                if i > 0 && cash_flow != 0.0 && equity > 0.0 {
+                    scale *= (equity - cash_flow) / equity;
+                }
+                (*ts_ns, equity * scale)
+            })
+            .collect()
+    }
+
+    fn max_drawdown_with_duration(&self) -> (f64, TimestampNs) {
+        let equity_curve = self.cash_flow_adjusted_equity();
+        let Some((first_ts_ns, first_equity)) = equity_curve.first() else {
+            return (0.0, TimestampNs::from(0));
+        };
+        let mut peak: (TimestampNs, f64) = (*first_ts_ns, *first_equity);
+        let mut max_drawdown = 0.0;
+        // The peak of the maximum drawdown and when it recovered.
+        let mut max_drawdown_span = (*first_ts_ns, None);
+        for (ts_ns, equity) in equity_curve.iter() {
+            let equity = *equity;
+            if equity >= peak.1 {
+                if max_drawdown_span.0 == peak.0 && max_drawdown_span.1.is_none() {
+                    max_drawdown_span.1 = Some(*ts_ns);
+                }
+                peak = (*ts_ns, equity);
+                continue;
+            }
+            let drawdown = (peak.1 - equity) / peak.1;
+            if drawdown > max_drawdown {
+                max_drawdown = drawdown;
+                max_drawdown_span = (peak.0, None);
+            }
+        }
+        if max_drawdown == 0.0 {
+            return (0.0, TimestampNs::from(0));
+        }
+        let last_ts_ns = self.equity_curve.last().expect("Has samples").0;
+        let end_ts_ns = max_drawdown_span.1.unwrap_or(last_ts_ns);
+        (max_drawdown, end_ts_ns - max_drawdown_span.0)
+    }
+}
+
+/// The annualized Sharpe ratio of the `returns`.
+fn sharpe_ratio(returns: LnReturns<'_, f64>, periods_per_year: f64) -> Option<f64> {
+    if returns.0.len() < 2 {
+        return None;
+    }
+    let n = returns.0.len() as f64;
+    let mean = returns.0.iter().sum::<f64>() / n;
+    let variance = returns.0.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    if variance <= 0.0 {
+        return None;
+    }
+    Some(mean / variance.sqrt() * periods_per_year.sqrt())
+}
+
+/// The annualized Sortino ratio of the `returns`.
+fn sortino_ratio(returns: LnReturns<'_, f64>, periods_per_year: f64) -> Option<f64> {
+    if returns.0.len() < 2 {
+        return None;
+    }
+    let n = returns.0.len() as f64;
+    let mean = returns.0.iter().sum::<f64>() / n;
+    let downside_variance = returns.0.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n;
+    if downside_variance <= 0.0 {
+        return None;
+    }
+    Some(mean / downside_variance.sqrt() * periods_per_year.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::QuoteCurrency;
+
+    fn tracker(equity: &[i64]) -> AccountTracker<i64, 5, QuoteCurrency<i64, 5>> {
+        let mut tracker = AccountTracker::new(TimestampNs::from(10));
+        for (i, equity) in equity.iter().enumerate() {
+            tracker.sample_equity(
+                TimestampNs::from(i as i64 * 10),
+                QuoteCurrency::new(*equity, 0),
+            );
+        }
+        tracker
+    }
+
+    #[test]
+    fn account_tracker_sample_equity() {
+        let mut tracker = AccountTracker::<i64, 5, QuoteCurrency<i64, 5>>::new(10.into());
+        tracker.sample_equity(5.into(), QuoteCurrency::new(100, 0));
+        tracker.sample_equity(9.into(), QuoteCurrency::new(101, 0));
+        // The gap is filled with the latest equity.
+        tracker.sample_equity(36.into(), QuoteCurrency::new(102, 0));
+        assert_eq!(tracker.equity_curve(), &vec![
+            (5.into(), QuoteCurrency::new(100, 0)),
+            (15.into(), QuoteCurrency::new(102, 0)),
+            (25.into(), QuoteCurrency::new(102, 0)),
+            (35.into(), QuoteCurrency::new(102, 0)),
+        ]);
+    }
+
+    #[test]
+    fn account_tracker_max_drawdown() {
+        let tracker = tracker(&[100, 120, 90, 110, 130, 117]);
+        assert_eq!(tracker.max_drawdown(), 0.25);
+        // From the peak at 10 until the recovery at 40.
+        assert_eq!(tracker.max_drawdown_duration(), 30.into());
+
+        let tracker = tracker(&[100, 110, 120]);
+        assert_eq!(tracker.max_drawdown(), 0.0);
+        assert_eq!(tracker.max_drawdown_duration(), 0.into());
+        assert_eq!(tracker.calmar_ratio(), None);
+    }
+
+    #[test]
+    fn account_tracker_ratios() {
+        let tracker = tracker(&[100, 110, 99, 108, 120]);
+        let sharpe = tracker.sharpe_ratio().unwrap();
+        let sortino = tracker.sortino_ratio().unwrap();
+        assert!(sharpe > 0.0);
+        assert!(sortino > sharpe);
+        assert!(tracker.calmar_ratio().unwrap() > 0.0);
+
+        assert_eq!(tracker(&[100]).sharpe_ratio(), None);
+        assert_eq!(tracker(&[100, 110, 120]).sortino_ratio(), None);
+    }
+
+    #[test]
+    fn account_tracker_fills() {
+        let mut tracker = tracker(&[1000, 1010]);
+        assert_eq!(tracker.win_rate(), None);
+        tracker.record_fill(QuoteCurrency::new(500, 0), QuoteCurrency::new(1, 0), None);
+        tracker.record_fill(
+            QuoteCurrency::new(500, 0),
+            QuoteCurrency::new(1, 0),
+            Some(QuoteCurrency::new(12, 0)),
+        );
+        tracker.record_fill(
+            QuoteCurrency::new(1000, 0),
+            QuoteCurrency::new(2, 0),
+            Some(QuoteCurrency::new(-5, 0)),
+        );
+        assert_eq!(tracker.win_rate(), Some(0.5));
+        assert_eq!(tracker.traded_notional(), QuoteCurrency::new(2000, 0));
+        // 2000 traded with an average equity of 1005.
+        assert_eq!(tracker.turnover(), Some(2000.0 / 1005.0));
+        // The fees of 4 out of a gross profit of 14.
+        assert_eq!(tracker.fee_share(), Some(4.0 / 14.0));
+    }
+
+    #[test]
+    fn account_tracker_cash_flows() {
+        let mut tracker = AccountTracker::<i64, 5, QuoteCurrency<i64, 5>>::new(10.into());
+        tracker.sample_equity(0.into(), QuoteCurrency::new(1000, 0));
+        tracker.record_cash_flow(5.into(), QuoteCurrency::new(1000, 0));
+        tracker.sample_equity(10.into(), QuoteCurrency::new(2000, 0));
+        tracker.record_cash_flow(15.into(), QuoteCurrency::new(-500, 0));
+        tracker.sample_equity(20.into(), QuoteCurrency::new(1500, 0));
+        tracker.sample_equity(30.into(), QuoteCurrency::new(1350, 0));
+        assert_eq!(tracker.cash_flows(), &vec![
+            (5.into(), QuoteCurrency::new(1000, 0)),
+            (15.into(), QuoteCurrency::new(-500, 0)),
+        ]);
+
+        // The deposit and withdrawal are neither gains nor losses.
+        let returns = tracker.ln_returns();
+        assert_eq!(returns.len(), 3);
+        assert_eq!(returns[0], 0.0);
+        assert_eq!(returns[1], 0.0);
+        assert!((returns[2] - 0.9_f64.ln()).abs() < 1e-9);
+        assert!((tracker.max_drawdown() - 0.1).abs() < 1e-9);
+        // Excluding the net deposit of 500, the equity dropped by 150 before fees of 15.
+        tracker.record_fee(QuoteCurrency::new(15, 0));
+        assert_eq!(tracker.fee_share(), None);
+    }
+
+    #[test]
+    fn account_tracker_write_equity_curve_csv() {
+        let tracker = tracker(&[100, 110]);
+        let mut buf = Vec::new();
+        tracker.write_equity_curve_csv(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "timestamp_ns,equity\n0,100.00000\n10,110.00000\n"
+        );
+    }
+}
@@ -129,6 +129,15 @@ where
         opt_out
     }
 
+    /// Iterate mutably over all active orders, which must not change their price or time priority.
+    #[inline]
+    pub(crate) fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>>
+    {
+        self.bids.iter_mut().chain(self.asks.iter_mut())
+    }
+
     /// Try to insert a new `LimitOrder` into the order book.
     /// Returns an error if the maximum capacity is reached.
     pub fn try_insert(
@@ -1,11 +1,15 @@
 use std::num::NonZeroUsize;
 
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters};
 
 use crate::{
     contract_specification::ContractSpecification,
     prelude::{ConfigError, MarginCurrency, Mon},
-    types::OrderRateLimits,
+    trade_ledger::LedgerCapacity,
+    types::{
+        AdlModel, LatencyModel, LiquidationModel, MarginMode, OrderRateLimits, SlippageModel,
+        TimestampNs,
+    },
 };
 
 /// Define the Exchange configuration.
@@ -14,7 +18,7 @@ use crate::{
 /// - `I`: The numeric data type of currencies.
 /// - `D`: The constant decimal precision of the currencies.
 /// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
-#[derive(Debug, Clone, Getters, CopyGetters)]
+#[derive(Debug, Clone, Getters, CopyGetters, MutGetters)]
 pub struct Config<I, const D: u8, BaseOrQuote>
 where
     I: Mon<D>,
@@ -34,12 +38,59 @@ where
     max_num_open_orders: NonZeroUsize,
 
     /// The contract specification.
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)")]
     contract_spec: ContractSpecification<I, D, BaseOrQuote::PairedCurrency>,
 
     /// The submission rate limits for orders.
     #[getset(get = "pub")]
     order_rate_limits: OrderRateLimits,
+
+    /// How far market orders slip from the best bid or ask, when there is no order book depth to walk.
+    #[getset(get_copy = "pub")]
+    slippage_model: SlippageModel<I, D, BaseOrQuote::PairedCurrency>,
+
+    /// If `true`, the queue position of resting limit orders is tracked from the visible order book depth,
+    /// so they fill at the touch once the queue ahead of them is consumed.
+    /// Otherwise they assume the worst possible queue position and only fill when the price trades through them.
+    #[getset(get_copy = "pub")]
+    queue_position_tracking: bool,
+
+    /// Whether the position is backed by its own margin or by the equity of the whole account.
+    #[getset(get_copy = "pub")]
+    margin_mode: MarginMode,
+
+    /// How the position is liquidated once it no longer meets its maintenance margin.
+    #[getset(get_copy = "pub")]
+    liquidation_model: LiquidationModel<I, D>,
+
+    /// The starting balance of the insurance fund of the exchange (denoted in margin currency),
+    /// which collects the liquidation fees and covers liquidations filled beyond their bankruptcy price.
+    #[getset(get_copy = "pub")]
+    insurance_fund: BaseOrQuote,
+
+    /// How the profitable position is auto-deleveraged by `AutoDeleverage` market updates.
+    /// If `None`, these updates are ignored.
+    #[getset(get_copy = "pub")]
+    adl_model: Option<AdlModel<I, D>>,
+
+    /// The interval at which the `AccountTracker` samples the equity of the account.
+    /// If `None`, the performance of the account is not tracked.
+    #[getset(get_copy = "pub")]
+    account_tracker_interval_ns: Option<TimestampNs>,
+
+    /// How many fills and trades the `TradeLedger` retains.
+    /// If `None`, the fills are not recorded.
+    #[getset(get_copy = "pub")]
+    trade_ledger_capacity: Option<LedgerCapacity>,
+
+    /// How long requests sent with `Exchange::send_request` take to reach the exchange.
+    #[getset(get = "pub")]
+    order_entry_latency: LatencyModel,
+
+    /// How long market updates take to reach the strategy,
+    /// which delays the requests it sends in response to them.
+    #[getset(get = "pub")]
+    market_data_latency: LatencyModel,
 }
 
 impl<I, const D: u8, BaseOrQuote> Config<I, D, BaseOrQuote>
@@ -74,6 +125,118 @@ where
             max_num_open_orders,
             contract_spec: contract_specification,
             order_rate_limits,
+            slippage_model: SlippageModel::default(),
+            queue_position_tracking: false,
+            margin_mode: MarginMode::default(),
+            liquidation_model: LiquidationModel::default(),
+            insurance_fund: BaseOrQuote::zero(),
+            adl_model: None,
+            account_tracker_interval_ns: None,
+            trade_ledger_capacity: None,
+            order_entry_latency: LatencyModel::default(),
+            market_data_latency: LatencyModel::default(),
         })
     }
+
+    /// Use the `slippage_model` for market orders, which don't fill at the best bid or ask by default.
+    ///
+    /// # Returns:
+    /// Either the `Config` with the `slippage_model` or an Error if its parameters are invalid.
+    pub fn with_slippage_model(
+        mut self,
+        slippage_model: SlippageModel<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Result<Self, ConfigError> {
+        slippage_model.validate()?;
+        self.slippage_model = slippage_model;
+        Ok(self)
+    }
+
+    /// Track the queue position of resting limit orders, which requires order book depth with quantities,
+    /// e.g. from `L2Book`, `L2Delta` or `SizedBba` updates.
+    pub fn with_queue_position_tracking(mut self, queue_position_tracking: bool) -> Self {
+        self.queue_position_tracking = queue_position_tracking;
+        self
+    }
+
+    /// Use the `margin_mode` for the margin requirements and liquidations of the account.
+    pub fn with_margin_mode(mut self, margin_mode: MarginMode) -> Self {
+        self.margin_mode = margin_mode;
+        self
+    }
+
+    /// Use the `liquidation_model` once the position no longer meets its maintenance margin,
+    /// instead of closing it entirely with a market order.
+    pub fn with_liquidation_model(mut self, liquidation_model: LiquidationModel<I, D>) -> Self {
+        self.liquidation_model = liquidation_model;
+        self
+    }
+
+    /// Start the insurance fund of the exchange with a balance of `insurance_fund`, instead of an empty one.
+    ///
+    /// # Returns:
+    /// Either the `Config` with the `insurance_fund` or an Error if it is negative.
+    pub fn with_insurance_fund(mut self, insurance_fund: BaseOrQuote) -> Result<Self, ConfigError> {
+        if insurance_fund < BaseOrQuote::zero() {
+            return Err(ConfigError::InvalidInsuranceFund);
+        }
+        self.insurance_fund = insurance_fund;
+        Ok(self)
+    }
+
+    /// Auto-deleverage the profitable position according to the `adl_model` on `AutoDeleverage` market updates.
+    pub fn with_adl_model(mut self, adl_model: AdlModel<I, D>) -> Self {
+        self.adl_model = Some(adl_model);
+        self
+    }
+
+    /// Track the performance of the account with an `AccountTracker`,
+    /// which samples the equity every `sample_interval_ns`.
+    ///
+    /// # Returns:
+    /// Either the `Config` with the account tracker or an Error if the `sample_interval_ns` is not positive.
+    pub fn with_account_tracker(
+        mut self,
+        sample_interval_ns: TimestampNs,
+    ) -> Result<Self, ConfigError> {
+        if sample_interval_ns <= TimestampNs::from(0) {
+            return Err(ConfigError::InvalidSampleInterval);
+        }
+        self.account_tracker_interval_ns = Some(sample_interval_ns);
+        Ok(self)
+    }
+
+    /// Record every fill and the closed round trips of the position in a `TradeLedger`,
+    /// which retains them according to `capacity`.
+    pub fn with_trade_ledger(mut self, capacity: LedgerCapacity) -> Self {
+        self.trade_ledger_capacity = Some(capacity);
+        self
+    }
+
+    /// Delay requests sent with `Exchange::send_request` by the `order_entry_latency`,
+    /// until they reach the exchange.
+    ///
+    /// # Returns:
+    /// Either the `Config` with the `order_entry_latency` or an Error if its parameters are invalid.
+    pub fn with_order_entry_latency(
+        mut self,
+        order_entry_latency: LatencyModel,
+    ) -> Result<Self, ConfigError> {
+        order_entry_latency.validate()?;
+        self.order_entry_latency = order_entry_latency;
+        Ok(self)
+    }
+
+    /// Delay market updates by the `market_data_latency` until they reach the strategy,
+    /// so requests sent in response to them arrive later at the exchange.
+    ///
+    /// # Returns:
+    /// Either the `Config` with the `market_data_latency` or an Error if its parameters are invalid.
+    pub fn with_market_data_latency(
+        mut self,
+        market_data_latency: LatencyModel,
+    ) -> Result<Self, ConfigError> {
+        market_data_latency.validate()?;
+        self.market_data_latency = market_data_latency;
+        Ok(self)
+    }
 }
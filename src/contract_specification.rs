@@ -1,3 +1,5 @@
+use std::num::NonZeroU32;
+
 use const_decimal::Decimal;
 use getset::{CopyGetters, Getters, Setters};
 use num_traits::{One, Zero};
@@ -5,7 +7,8 @@ use num_traits::{One, Zero};
 use crate::{
     leverage,
     prelude::{ConfigError, Currency, Maker, Mon, PriceFilter, QuantityFilter, Taker},
-    types::{Fee, Leverage},
+    types::{Fee, FeeTier, Leverage, RiskLimitTier},
+    utils::max,
 };
 
 /// Specifies the details of the futures contract
@@ -34,6 +37,15 @@ where
     #[getset(get_copy = "pub")]
     maintenance_margin: Decimal<I, D>,
 
+    /// The fraction of the `init_margin_req` that makes up the `maintenance_margin`.
+    maintenance_margin_fraction: Decimal<I, D>,
+
+    /// The risk limit tiers in ascending order of their `max_notional`,
+    /// which scale the margin requirements with the notional value of the position.
+    /// If empty, the `init_margin_req` and `maintenance_margin` apply to positions of any size.
+    #[getset(get = "pub")]
+    risk_limit_tiers: Vec<RiskLimitTier<I, D, BaseOrQuote::PairedCurrency>>,
+
     /// The method for computing `mark-to-market`.
     #[getset(get_copy = "pub", set = "pub")]
     mark_method: MarkMethod,
@@ -53,6 +65,12 @@ where
     /// The taker fee as parts per 100_000
     #[getset(get_copy = "pub")]
     fee_taker: Fee<I, D, Taker>,
+
+    /// The fee tiers in ascending order of their `min_volume`,
+    /// which replace the `fee_maker` and `fee_taker` once the traded volume of the last 30 days reaches them.
+    /// If empty, the `fee_maker` and `fee_taker` apply regardless of the traded volume.
+    #[getset(get = "pub")]
+    fee_tiers: Vec<FeeTier<I, D, BaseOrQuote::PairedCurrency>>,
 }
 
 impl<I, const D: u8, BaseOrQuote> ContractSpecification<I, D, BaseOrQuote>
@@ -89,13 +107,129 @@ where
             ticker: String::new(),
             init_margin_req,
             maintenance_margin: init_margin_req * maintenance_margin,
+            maintenance_margin_fraction: maintenance_margin,
+            risk_limit_tiers: Vec::new(),
             mark_method: MarkMethod::default(),
             price_filter,
             quantity_filter,
             fee_maker,
             fee_taker,
+            fee_tiers: Vec::new(),
         })
     }
+
+    /// Change the `leverage`, which re-computes the `init_margin_req` and `maintenance_margin`.
+    pub(crate) fn set_leverage(&mut self, leverage: Leverage<I, D>) {
+        self.init_margin_req = leverage.init_margin_req();
+        self.maintenance_margin = self.init_margin_req * self.maintenance_margin_fraction;
+    }
+
+    /// Scale the margin requirements with the notional value of the position, according to the `risk_limit_tiers`.
+    /// Their `max_notional` must be strictly ascending, while their margin requirements must not decrease.
+    /// Positions can not grow beyond the `max_notional` of the last tier.
+    pub fn with_risk_limit_tiers(
+        mut self,
+        risk_limit_tiers: Vec<RiskLimitTier<I, D, BaseOrQuote::PairedCurrency>>,
+    ) -> Result<Self, ConfigError> {
+        if risk_limit_tiers.windows(2).any(|w| {
+            w[0].max_notional() >= w[1].max_notional()
+                || w[0].init_margin_req() > w[1].init_margin_req()
+                || w[0].maintenance_margin() > w[1].maintenance_margin()
+        }) {
+            return Err(ConfigError::InvalidRiskLimitTiers);
+        }
+        self.risk_limit_tiers = risk_limit_tiers;
+        Ok(self)
+    }
+
+    /// The risk limit tier of a position with the `notional` value.
+    /// Positions beyond the last tier are assigned to it.
+    fn risk_limit_tier(
+        &self,
+        notional: BaseOrQuote::PairedCurrency,
+    ) -> Option<&RiskLimitTier<I, D, BaseOrQuote::PairedCurrency>> {
+        self.risk_limit_tiers
+            .iter()
+            .find(|tier| notional <= tier.max_notional())
+            .or(self.risk_limit_tiers.last())
+    }
+
+    /// The initial margin requirement of a position with the `notional` value,
+    /// being the larger one of the leverage and the risk limit tier.
+    pub fn init_margin_req_for(&self, notional: BaseOrQuote::PairedCurrency) -> Decimal<I, D> {
+        match self.risk_limit_tier(notional) {
+            Some(tier) => max(self.init_margin_req, tier.init_margin_req()),
+            None => self.init_margin_req,
+        }
+    }
+
+    /// The maintenance margin requirement of a position with the `notional` value.
+    pub fn maintenance_margin_for(&self, notional: BaseOrQuote::PairedCurrency) -> Decimal<I, D> {
+        self.risk_limit_tier(notional)
+            .map_or(self.maintenance_margin, |tier| tier.maintenance_margin())
+    }
+
+    /// The initial margin of a position with the `notional` value.
+    pub fn init_margin(
+        &self,
+        notional: BaseOrQuote::PairedCurrency,
+    ) -> BaseOrQuote::PairedCurrency {
+        notional * self.init_margin_req_for(notional)
+    }
+
+    /// Scale the fees with the traded volume of the last 30 days, according to the `fee_tiers`.
+    /// Their `min_volume` must be strictly ascending.
+    /// Below the `min_volume` of the first tier, the `fee_maker` and `fee_taker` apply.
+    ///
+    /// The risk engines keep checking the margin of orders against the `fee_taker`,
+    /// so the taker fees of the tiers must not exceed it.
+    pub fn with_fee_tiers(
+        mut self,
+        fee_tiers: Vec<FeeTier<I, D, BaseOrQuote::PairedCurrency>>,
+    ) -> Result<Self, ConfigError> {
+        if fee_tiers
+            .windows(2)
+            .any(|w| w[0].min_volume() >= w[1].min_volume())
+            || fee_tiers
+                .iter()
+                .any(|tier| *tier.fee_taker().as_ref() > *self.fee_taker.as_ref())
+        {
+            return Err(ConfigError::InvalidFeeTiers);
+        }
+        self.fee_tiers = fee_tiers;
+        Ok(self)
+    }
+
+    /// The fee tier reached by the traded `volume` of the last 30 days,
+    /// or `None` if it falls below the first tier.
+    pub fn fee_tier(
+        &self,
+        volume: BaseOrQuote::PairedCurrency,
+    ) -> Option<&FeeTier<I, D, BaseOrQuote::PairedCurrency>> {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume())
+    }
+
+    /// The maker fee at the traded `volume` of the last 30 days.
+    pub fn fee_maker_for(&self, volume: BaseOrQuote::PairedCurrency) -> Fee<I, D, Maker> {
+        self.fee_tier(volume)
+            .map_or(self.fee_maker, |tier| tier.fee_maker())
+    }
+
+    /// The taker fee at the traded `volume` of the last 30 days.
+    pub fn fee_taker_for(&self, volume: BaseOrQuote::PairedCurrency) -> Fee<I, D, Taker> {
+        self.fee_tier(volume)
+            .map_or(self.fee_taker, |tier| tier.fee_taker())
+    }
+
+    /// Whether a position with the `notional` value exceeds the `max_notional` of the last risk limit tier.
+    pub fn exceeds_risk_limit(&self, notional: BaseOrQuote::PairedCurrency) -> bool {
+        self.risk_limit_tiers
+            .last()
+            .is_some_and(|tier| notional > tier.max_notional())
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote> Default for ContractSpecification<I, D, BaseOrQuote>
@@ -116,19 +250,27 @@ where
     }
 }
 
-// TODO: actually switch between the methods.
-/// Which price to use in `mark-to-market` calculations
-#[derive(Debug, Clone, Copy)]
+/// Which price to use in `mark-to-market` calculations,
+/// such as the unrealized profit and loss, the maintenance margin checks and funding payments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MarkMethod {
     /// Take the last mid price of the market.
     MidPrice,
     /// Use the best bid and ask to mark the position to market.
+    /// A long position is marked at the bid, a short position at the ask, while the mark price is the mid price.
     BidAsk,
     /// Use Fair Price Marking to avoid unnecessary liquidations in highly leveraged products.
     /// Without this system, unnecessary liquidations may occur if the market is being manipulated,
     /// is illiquid, or the Mark Price swings unnecessarily relative to its Index Price.
     /// The system is able to achieve this by setting the Mark Price of the contract to the `FairPrice` instead of the `LastPrice`.
-    FairPrice,
+    ///
+    /// The fair price is the index price, fed in with `IndexPrice` updates,
+    /// plus an exponential moving average of the basis between the mid price and the index price.
+    /// Until the first `IndexPrice` update arrives, the mid price is used.
+    FairPrice {
+        /// The number of `IndexPrice` updates the moving average of the basis spans.
+        basis_periods: NonZeroU32,
+    },
 }
 
 impl Default for MarkMethod {
@@ -1,29 +1,37 @@
-use std::cmp::Ordering;
+use std::collections::VecDeque;
 
 use assert2::assert;
-use getset::{Getters, MutGetters};
+use const_decimal::Decimal;
+use getset::{CopyGetters, Getters, MutGetters};
 use num_traits::Zero;
 use tracing::{debug, info, trace, warn};
 
 use crate::{
+    account_tracker::AccountTracker,
     config::Config,
     market_state::MarketState,
+    order_groups::{BracketExits, OrderGroup, OrderGroups, other_leg},
     order_margin::OrderMargin,
     order_rate_limiter::OrderRateLimiter,
     prelude::{
-        ActiveLimitOrders, Currency, MarketUpdate, Mon, OrderError, Position, QuoteCurrency,
-        RePricing,
+        ActiveLimitOrders, AutoDeleverage, Currency, Leverage, MarketUpdate, Mon, OrderError,
+        Position, QuoteCurrency, RePricing,
     },
-    risk_engine::{IsolatedMarginRiskEngine, RiskEngine},
+    risk_engine::{MarginModeRiskEngine, RiskEngine},
+    trade_ledger::{Fill, Liquidity, TradeLedger},
     types::{
-        Balances, Error, ExchangeOrderMeta, Filled, LimitOrder, LimitOrderFill, MarginCurrency,
-        MarketOrder, NewOrder, OrderId, Pending, Result, RiskError, Side, TimestampNs, UserOrderId,
+        Balances, CancelReason, Error, ExchangeOrderMeta, FeeTier, Filled, LatencySampler,
+        LimitOrder, LimitOrderFill, LinkedOrder, LiquidationSettlement, MarginCurrency,
+        MarketOrder, NewOrder, OrderBookDepth, OrderEvent, OrderId, OrderRequest, OrderResponse,
+        Pending, Result, RiskError, RollingVolume, Side, StopOrder, StopOrderType, TimeInForce,
+        TimestampNs, TriggeredOrder, UserOrderId,
     },
+    utils::min,
 };
 
 /// Whether to cancel a limit order by its `OrderId` or the `UserOrderId`.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum CancelBy<UserOrderIdT: UserOrderId> {
     OrderId(OrderId),
     UserOrderId(UserOrderIdT),
@@ -53,13 +61,27 @@ where
 }
 
 /// The main leveraged futures exchange for simulated trading
-#[derive(Debug, Clone, Getters, MutGetters)]
-pub struct Exchange<I, const D: u8, BaseOrQuote, UserOrderIdT>
-where
+///
+/// Generics:
+/// - `I`: The numeric data type of currencies.
+/// - `D`: The constant decimal precision of the currencies.
+/// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
+/// - `UserOrderId`: The type of user order id to use. Set to `()` if you don't need one.
+/// - `RiskEngineT`: The `RiskEngine` checking orders and the maintenance margin.
+///   Defaults to the `MarginModeRiskEngine` of the `MarginMode` in the `Config`.
+#[derive(Debug, Clone, Getters, CopyGetters, MutGetters)]
+pub struct Exchange<
+    I,
+    const D: u8,
+    BaseOrQuote,
+    UserOrderIdT,
+    RiskEngineT = MarginModeRiskEngine<I, D, BaseOrQuote>,
+> where
     I: Mon<D>,
     BaseOrQuote: Currency<I, D>,
     BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
     UserOrderIdT: UserOrderId,
+    RiskEngineT: RiskEngine<I, D, BaseOrQuote, UserOrderIdT>,
 {
     /// The exchange configuration.
     #[getset(get = "pub")]
@@ -69,7 +91,14 @@ where
     #[getset(get = "pub")]
     market_state: MarketState<I, D>,
 
-    risk_engine: IsolatedMarginRiskEngine<I, D, BaseOrQuote>,
+    /// The visible order book depth, maintained from `L2Book` and `L2Delta` updates,
+    /// which market orders and marketable limit orders walk.
+    #[getset(get = "pub")]
+    depth: OrderBookDepth<I, D, BaseOrQuote>,
+
+    /// The risk engine checking orders and the maintenance margin.
+    #[getset(get = "pub")]
+    risk_engine: RiskEngineT,
 
     next_order_id: OrderId,
 
@@ -83,6 +112,12 @@ where
     #[cfg_attr(test, getset(get_mut = "pub(crate)"))]
     position: Position<I, D, BaseOrQuote>,
 
+    /// The balance of the insurance fund of the exchange, which collects the liquidation fees
+    /// along with the difference between the bankruptcy price and the market price of liquidations,
+    /// and covers liquidations which could only be filled beyond their bankruptcy price.
+    #[getset(get_copy = "pub")]
+    insurance_fund: BaseOrQuote::PairedCurrency,
+
     /// The order margin state.
     #[getset(get = "pub")]
     order_margin: OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
@@ -90,7 +125,35 @@ where
     // To avoid allocations in hot-paths
     limit_order_updates: Vec<LimitOrderFill<I, D, BaseOrQuote, UserOrderIdT>>,
 
+    /// Order events which occurred during the last `update_state` call, e.g. triggered stop orders or liquidations.
+    #[getset(get = "pub")]
+    order_events: Vec<OrderEvent<I, D, BaseOrQuote, UserOrderIdT>>,
+
+    order_groups: OrderGroups<I, D, BaseOrQuote, UserOrderIdT>,
+
     order_rate_limiter: OrderRateLimiter,
+
+    order_entry_latency: LatencySampler,
+
+    market_data_latency: LatencySampler,
+
+    // How long the latest market update took to reach the strategy.
+    market_data_delay: TimestampNs,
+
+    /// Requests sent with `send_request` which have not reached the exchange yet, along with their arrival timestamp.
+    #[getset(get = "pub")]
+    pending_requests: VecDeque<(TimestampNs, OrderRequest<I, D, BaseOrQuote, UserOrderIdT>)>,
+
+    /// Responses to the requests which reached the exchange since the last `update_state` call.
+    #[getset(get = "pub")]
+    order_responses: Vec<OrderResponse<I, D, BaseOrQuote, UserOrderIdT>>,
+
+    account_tracker: Option<AccountTracker<I, D, BaseOrQuote::PairedCurrency>>,
+
+    trade_ledger: Option<TradeLedger<I, D, BaseOrQuote>>,
+
+    // The traded notional value of the last 30 days, which selects the fee tier.
+    traded_volume: RollingVolume<I, D, BaseOrQuote::PairedCurrency>,
 }
 
 impl<I, const D: u8, BaseOrQuote, UserOrderIdT> Exchange<I, D, BaseOrQuote, UserOrderIdT>
@@ -103,23 +166,62 @@ where
     /// Create a new Exchange with the desired config and whether to use candles
     /// as information source
     pub fn new(config: Config<I, D, BaseOrQuote::PairedCurrency>) -> Self {
-        let market_state = MarketState::default();
-        let risk_engine = IsolatedMarginRiskEngine::new(config.contract_spec().clone());
+        let risk_engine =
+            MarginModeRiskEngine::new(config.margin_mode(), config.contract_spec().clone());
+        Self::with_risk_engine(config, risk_engine)
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT, RiskEngineT>
+    Exchange<I, D, BaseOrQuote, UserOrderIdT, RiskEngineT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+    RiskEngineT: RiskEngine<I, D, BaseOrQuote, UserOrderIdT>,
+{
+    /// Create a new Exchange with the desired config, which checks orders and the maintenance margin with a custom `risk_engine`.
+    /// The `MarginMode` of the `config` is up to the `risk_engine` to honour.
+    pub fn with_risk_engine(
+        config: Config<I, D, BaseOrQuote::PairedCurrency>,
+        risk_engine: RiskEngineT,
+    ) -> Self {
+        let market_state = MarketState::new(config.contract_spec().mark_method());
 
         let max_active_orders = config.max_num_open_orders();
         let order_rate_limiter =
             OrderRateLimiter::new(config.order_rate_limits().orders_per_second());
         let balances = Balances::new(config.starting_wallet_balance());
+        let insurance_fund = config.insurance_fund();
+        let account_tracker = config
+            .account_tracker_interval_ns()
+            .map(AccountTracker::new);
+        let trade_ledger = config.trade_ledger_capacity().map(TradeLedger::new);
+        let order_entry_latency = LatencySampler::new(config.order_entry_latency().clone());
+        let market_data_latency = LatencySampler::new(config.market_data_latency().clone());
         Self {
             config,
             market_state,
+            depth: OrderBookDepth::default(),
             risk_engine,
             next_order_id: OrderId::default(),
             balances,
             position: Position::default(),
+            insurance_fund,
             order_margin: OrderMargin::new(max_active_orders),
             limit_order_updates: Vec::with_capacity(max_active_orders.get()),
+            order_events: Vec::with_capacity(max_active_orders.get()),
+            order_groups: OrderGroups::default(),
             order_rate_limiter,
+            order_entry_latency,
+            market_data_latency,
+            market_data_delay: TimestampNs::default(),
+            pending_requests: VecDeque::new(),
+            order_responses: Vec::new(),
+            account_tracker,
+            trade_ledger,
+            traded_volume: RollingVolume::default(),
         }
     }
 
@@ -129,6 +231,49 @@ where
         self.order_margin.active_limit_orders()
     }
 
+    /// The users currently active stop orders, which have not been triggered yet.
+    #[inline]
+    pub fn active_stop_orders(
+        &self,
+    ) -> &[StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>] {
+        self.order_margin.stop_orders()
+    }
+
+    /// The users `HideNotSlide` limit orders, which are hidden until the away market quotation is lifted.
+    #[inline]
+    pub fn hidden_limit_orders(
+        &self,
+    ) -> &[LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>] {
+        self.order_margin.hidden_orders()
+    }
+
+    /// The `AccountTracker` tracking the performance of the account, if enabled with `Config::with_account_tracker`.
+    #[inline]
+    pub fn account_tracker(&self) -> Option<&AccountTracker<I, D, BaseOrQuote::PairedCurrency>> {
+        self.account_tracker.as_ref()
+    }
+
+    /// The `TradeLedger` recording the fills and round trips of the position, if enabled with `Config::with_trade_ledger`.
+    #[inline]
+    pub fn trade_ledger(&self) -> Option<&TradeLedger<I, D, BaseOrQuote>> {
+        self.trade_ledger.as_ref()
+    }
+
+    /// The traded notional value of the last 30 days, which selects the current fee tier.
+    #[inline]
+    pub fn traded_volume(&self) -> BaseOrQuote::PairedCurrency {
+        self.traded_volume.volume()
+    }
+
+    /// The fee tier of the `ContractSpecification` reached by the `traded_volume`,
+    /// or `None` if the base `fee_maker` and `fee_taker` apply.
+    #[inline]
+    pub fn fee_tier(&self) -> Option<&FeeTier<I, D, BaseOrQuote::PairedCurrency>> {
+        self.config
+            .contract_spec()
+            .fee_tier(self.traded_volume.volume())
+    }
+
     /// Get information about the `Account`
     pub fn account(&self) -> Account<I, D, BaseOrQuote, UserOrderIdT> {
         Account {
@@ -138,16 +283,135 @@ where
         }
     }
 
+    /// The mark-to-market price at which the position is liquidated according to the risk engine,
+    /// see `RiskEngine::liquidation_price`.
+    /// Returns `None` if there is no position or it can not be liquidated at any positive price.
+    pub fn liquidation_price(&self) -> Option<QuoteCurrency<I, D>> {
+        self.risk_engine
+            .liquidation_price(&self.market_state, &self.position, &self.balances)
+    }
+
+    /// The price at which the losses of the position exhaust the margin backing it according to the risk engine,
+    /// see `RiskEngine::bankruptcy_price`.
+    /// Returns `None` if there is no position or the margin covers its losses at any positive price.
+    pub fn bankruptcy_price(&self) -> Option<QuoteCurrency<I, D>> {
+        self.risk_engine
+            .bankruptcy_price(&self.position, &self.balances)
+    }
+
+    /// The distance of the mark-to-market price to the `liquidation_price`, relative to the mark-to-market price,
+    /// see `Position::distance_to_liquidation`.
+    pub fn distance_to_liquidation(&self) -> Option<Decimal<I, D>> {
+        let liquidation_price = self.liquidation_price()?;
+        self.position
+            .distance_to(liquidation_price, &self.market_state)
+    }
+
+    /// Change the leverage of the account, which re-computes the position margin and order margin
+    /// and moves the liquidation price of the position going forward.
+    ///
+    /// # Returns:
+    /// If Err, the risk engine rejects the increased margin requirements, see `RiskEngine::check_leverage`,
+    /// and the leverage remains unchanged.
+    pub fn set_leverage(&mut self, leverage: Leverage<I, D>) -> std::result::Result<(), RiskError> {
+        let mut contract_spec = self.config.contract_spec().clone();
+        contract_spec.set_leverage(leverage);
+        self.risk_engine.check_leverage(
+            &self.market_state,
+            &self.position,
+            &self.balances,
+            &self.order_margin,
+            &contract_spec,
+        )?;
+        let new_position_margin = contract_spec.init_margin(self.position.total_cost());
+        debug!("set_leverage: {leverage}");
+        self.risk_engine.update_contract_spec(&contract_spec);
+        *self.config.contract_spec_mut() = contract_spec;
+
+        // Margin is freed before the increased requirements are reserved.
+        let position_margin = self.balances.position_margin();
+        if new_position_margin < position_margin {
+            self.balances
+                .free_position_margin(position_margin - new_position_margin);
+        }
+        self.rebalance_order_margin();
+        // Unrealized profits back the position margin the available balance can not cover with the cross margin mode.
+        let margin = min(
+            new_position_margin - position_margin,
+            self.balances.available(),
+        );
+        if margin > BaseOrQuote::PairedCurrency::zero() {
+            let success = self.balances.try_reserve_position_margin(margin);
+            debug_assert!(success, "Can reserve position margin");
+        }
+
+        Ok(())
+    }
+
+    /// Deposit `amount` into the available balance of the account.
+    /// It is recorded as a cash flow in the `AccountTracker` if enabled, so it does not count as a return.
+    ///
+    /// # Returns:
+    /// If Err, the `amount` is not positive.
+    pub fn deposit(&mut self, amount: BaseOrQuote::PairedCurrency) -> Result<()> {
+        if amount <= BaseOrQuote::PairedCurrency::zero() {
+            return Err(Error::InvalidTransferAmount);
+        }
+        debug!("deposit: {amount}");
+        self.balances.deposit(amount);
+        self.record_cash_flow(amount);
+        Ok(())
+    }
+
+    /// Withdraw `amount` from the available balance of the account, which can not touch the margin in use.
+    /// It is recorded as a cash flow in the `AccountTracker` if enabled, so it does not count as a loss.
+    ///
+    /// # Returns:
+    /// If Err, the `amount` is not positive, exceeds the available balance
+    /// or would leave the position below its maintenance margin, as is possible with cross margin.
+    /// The balances remain unchanged in that case.
+    pub fn withdraw(&mut self, amount: BaseOrQuote::PairedCurrency) -> Result<()> {
+        if amount <= BaseOrQuote::PairedCurrency::zero() {
+            return Err(Error::InvalidTransferAmount);
+        }
+        if amount > self.balances.available() {
+            return Err(RiskError::NotEnoughAvailableBalance.into());
+        }
+        let mut balances = self.balances.clone();
+        balances.withdraw(amount);
+        if self
+            .risk_engine
+            .check_maintenance_margin(&self.market_state, &self.position, &balances)
+            .is_err()
+        {
+            return Err(RiskError::WithdrawalBreachesMargin.into());
+        }
+        debug!("withdraw: {amount}");
+        self.balances = balances;
+        self.record_cash_flow(-amount);
+        Ok(())
+    }
+
+    /// Record a deposit or withdrawal (if negative) in the `AccountTracker`, if enabled.
+    fn record_cash_flow(&mut self, amount: BaseOrQuote::PairedCurrency) {
+        if let Some(account_tracker) = self.account_tracker.as_mut() {
+            account_tracker.record_cash_flow(self.market_state.current_ts_ns(), amount);
+        }
+    }
+
     /// Update the exchange state with new information
     /// Returns a reference to order updates vector for performance reasons.
     ///
     /// ### Parameters:
-    /// `timestamp_ns`: Is used in the AccountTracker `A`
-    ///     and if setting order timestamps is enabled in the config.
-    /// `market_update`: Newest market information
+    /// `market_update`: Newest market information,
+    ///     whose timestamp drives the equity samples of the `AccountTracker` if enabled.
     ///
     /// ### Returns:
     /// If Ok, returns updates regarding limit orders, wether partially filled or fully.
+    /// Other order events, such as triggered stop orders, are available through `order_events`.
+    /// If Err, the position was liquidated, with the liquidation orders available through `order_events`.
+    /// Requests which reached the exchange before the `market_update` are processed first,
+    /// with their outcomes available through `order_responses`.
     pub fn update_state<U>(
         &mut self,
         market_update: &U,
@@ -156,26 +420,55 @@ where
         U: MarketUpdate<I, D, BaseOrQuote>,
     {
         trace!("update_state: market_update: {market_update}");
+        self.order_events.clear();
+        self.order_responses.clear();
+        self.process_arrived_requests(market_update.timestamp_exchange_ns());
+        self.market_data_delay = self.market_data_latency.sample();
+
+        self.market_state.update_state(
+            market_update,
+            self.config.contract_spec().price_filter(),
+            &mut self.depth,
+        );
+        self.traded_volume.expire(self.market_state.current_ts_ns());
+
+        while let Some(funding_rate) = self.market_state.take_due_funding() {
+            self.settle_funding(funding_rate);
+        }
 
-        self.market_state
-            .update_state(market_update, self.config.contract_spec().price_filter());
+        if let Some(auto_deleverage) = market_update.auto_deleverage() {
+            self.deleverage(auto_deleverage);
+        }
 
-        if let Err(e) = <IsolatedMarginRiskEngine<I, D, BaseOrQuote> as RiskEngine<
-            I,
-            D,
-            BaseOrQuote,
-            UserOrderIdT,
-        >>::check_maintenance_margin(
-            &self.risk_engine, &self.market_state, &self.position
+        if let Err(e) = self.risk_engine.check_maintenance_margin(
+            &self.market_state,
+            &self.position,
+            &self.balances,
         ) {
             self.liquidate();
+            self.sample_equity();
             return Err(e);
         };
 
+        self.expire_orders();
         self.check_active_orders(market_update.clone());
+        self.advance_queue_positions(market_update);
+        self.reveal_hidden_orders();
+        self.update_trailing_stops(market_update.price_range());
+        self.check_stop_orders();
+        self.sample_equity();
         Ok(&self.limit_order_updates)
     }
 
+    /// Sample the equity of the account into the `AccountTracker`, if enabled.
+    fn sample_equity(&mut self) {
+        if let Some(account_tracker) = self.account_tracker.as_mut() {
+            let equity =
+                self.balances.sum() + self.position.marked_unrealized_pnl(&self.market_state);
+            account_tracker.sample_equity(self.market_state.current_timestamp_ns(), equity);
+        }
+    }
+
     /// Set the best bid and ask, alternatively a `Bba` `MarketUpdate` can be passed into `update_state`
     #[inline]
     pub fn set_best_bid_and_ask(&mut self, bid: QuoteCurrency<I, D>, ask: QuoteCurrency<I, D>) {
@@ -184,25 +477,243 @@ where
         self.market_state.set_ask(ask);
     }
 
-    // Liquidate the position by closing it with a market order.
+    /// Set the visible order book depth along with the best bid and ask of it,
+    /// alternatively an `L2Book` `MarketUpdate` can be passed into `update_state`.
+    /// Market orders walk the depth as long as its best price matches the best bid or ask,
+    /// otherwise the `SlippageModel` of the `Config` applies.
+    pub fn set_depth(&mut self, depth: OrderBookDepth<I, D, BaseOrQuote>) {
+        if let (Some(bid), Some(ask)) = (depth.best_bid(), depth.best_ask()) {
+            self.set_best_bid_and_ask(bid, ask);
+        }
+        self.depth = depth;
+    }
+
+    /// Send a request to the exchange, which arrives after the market data latency of the latest market update
+    /// plus the order entry latency of the `Config`, as the strategy responds to market data it received late.
+    /// Requests arrive in the order they were sent and are evaluated against the market state at their arrival,
+    /// with the outcome reported through `order_responses`.
+    /// Without any latency the request is processed immediately.
+    ///
+    /// # Returns:
+    /// The timestamp at which the request reaches the exchange.
+    pub fn send_request(
+        &mut self,
+        request: OrderRequest<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> TimestampNs {
+        let mut arrival_ts_ns = self.market_state.current_ts_ns()
+            + self.market_data_delay
+            + self.order_entry_latency.sample();
+        if let Some((last_arrival_ts_ns, _)) = self.pending_requests.back() {
+            arrival_ts_ns = arrival_ts_ns.max(*last_arrival_ts_ns);
+        }
+        trace!("send_request: {request:?}, arrival_ts_ns: {arrival_ts_ns}");
+        if arrival_ts_ns <= self.market_state.current_ts_ns() {
+            self.process_request(request);
+        } else {
+            self.pending_requests.push_back((arrival_ts_ns, request));
+        }
+        arrival_ts_ns
+    }
+
+    /// Process the pending requests which reach the exchange no later than `ts_ns`,
+    /// each at the timestamp of its arrival.
+    fn process_arrived_requests(&mut self, ts_ns: TimestampNs) {
+        while self
+            .pending_requests
+            .front()
+            .is_some_and(|(arrival_ts_ns, _)| *arrival_ts_ns <= ts_ns)
+        {
+            let (arrival_ts_ns, request) = self
+                .pending_requests
+                .pop_front()
+                .expect("Has a pending request");
+            self.market_state.set_current_ts_ns(arrival_ts_ns);
+            self.process_request(request);
+        }
+    }
+
+    /// Process a request which reached the exchange and record the response to it.
+    fn process_request(&mut self, request: OrderRequest<I, D, BaseOrQuote, UserOrderIdT>) {
+        let outcome = match request.clone() {
+            OrderRequest::SubmitMarketOrder(order) => self
+                .submit_market_order(order)
+                .map(OrderResponse::MarketOrderFilled),
+            OrderRequest::SubmitLimitOrder(order) => self
+                .submit_limit_order(order)
+                .map(OrderResponse::LimitOrderSubmitted),
+            OrderRequest::AmendLimitOrder {
+                existing_order_id,
+                new_order,
+            } => self
+                .amend_limit_order(existing_order_id, new_order)
+                .map(OrderResponse::LimitOrderAmended),
+            OrderRequest::CancelLimitOrder(cancel_by) => self
+                .cancel_limit_order(cancel_by)
+                .map(OrderResponse::LimitOrderCancelled),
+        };
+        let response = outcome.unwrap_or_else(|error| {
+            debug!("process_request: rejected {request:?}: {error}");
+            OrderResponse::Rejected { request, error }
+        });
+        self.order_responses.push(response);
+    }
+
+    /// Settle the funding payment of the current position at the mark price.
+    fn settle_funding(&mut self, funding_rate: Decimal<I, D>) {
+        let mark_price = self.market_state.mark_price();
+        let payment = self.position.funding_payment(funding_rate, mark_price);
+        debug!(
+            "settle_funding: funding_rate: {funding_rate}, mark_price: {mark_price}, payment: {payment}"
+        );
+        if payment.is_zero() {
+            return;
+        }
+        self.balances.apply_funding(payment);
+    }
+
+    /// Liquidate the position according to the `LiquidationModel` of the `Config`,
+    /// reporting each liquidation order through the `order_events`.
     fn liquidate(&mut self) {
         warn!("liquidating position {}", self.position);
         debug_assert!(self.market_state.ask() > QuoteCurrency::zero());
         debug_assert!(self.market_state.bid() > QuoteCurrency::zero());
-        let order = match &self.position {
-            Position::Long(pos) => {
-                MarketOrder::new(Side::Sell, pos.quantity()).expect("Can create market order.")
+        let liquidation_model = self.config.liquidation_model();
+        loop {
+            let (side, position_qty) = match &self.position {
+                Position::Long(pos) => (Side::Sell, pos.quantity()),
+                Position::Short(pos) => (Side::Buy, pos.quantity()),
+                Position::Neutral => panic!("A neutral position can not be liquidated"),
+            };
+            let quantity = liquidation_model
+                .step_quantity(position_qty, self.config.contract_spec().quantity_filter());
+            let market_price = self.market_order_fill_price(side, quantity);
+            let bankruptcy_price = self.bankruptcy_price().unwrap_or(market_price);
+            let (fill_price, fee) = match liquidation_model.settlement() {
+                LiquidationSettlement::MarketPrice => {
+                    // The insurance fund covers any fill beyond the bankruptcy price.
+                    let fill_price = match side {
+                        Side::Buy => market_price.min(bankruptcy_price),
+                        Side::Sell => market_price.max(bankruptcy_price),
+                    };
+                    (fill_price, self.taker_fee(quantity, fill_price))
+                }
+                LiquidationSettlement::BankruptcyPrice => {
+                    (bankruptcy_price, BaseOrQuote::PairedCurrency::zero())
+                }
+            };
+
+            let meta = ExchangeOrderMeta::new(
+                self.next_order_id(),
+                self.market_state.current_timestamp_ns(),
+            );
+            let order = MarketOrder::new(side, quantity)
+                .expect("Can create market order.")
+                .into_pending(meta)
+                .into_filled(fill_price, self.market_state.current_timestamp_ns());
+            self.settle_filled_market_order(order.clone(), fee);
+
+            let notional = BaseOrQuote::PairedCurrency::convert_from(quantity, fill_price);
+            let liquidation_fee =
+                (notional * liquidation_model.fee()).min(self.balances.available());
+            if liquidation_fee > BaseOrQuote::PairedCurrency::zero() {
+                self.balances.account_for_fee(liquidation_fee);
+                if let Some(account_tracker) = self.account_tracker.as_mut() {
+                    account_tracker.record_fee(liquidation_fee);
+                }
             }
-            Position::Short(pos) => {
-                MarketOrder::new(Side::Buy, pos.quantity()).expect("Can create market order.")
+            // The exchange takes over the liquidated quantity at the fill price and closes it at the market price.
+            let takeover_qty = match side {
+                Side::Buy => -quantity,
+                Side::Sell => quantity,
+            };
+            let takeover_pnl =
+                BaseOrQuote::PairedCurrency::pnl(fill_price, market_price, takeover_qty);
+            self.fund_insurance(takeover_pnl + liquidation_fee);
+            debug!(
+                "liquidate: {side} {quantity} @ {fill_price}, bankruptcy_price: {bankruptcy_price}, liquidation_fee: {liquidation_fee}, insurance_fund: {}",
+                self.insurance_fund
+            );
+            self.order_events.push(OrderEvent::Liquidated {
+                order,
+                bankruptcy_price,
+                liquidation_fee,
+            });
+
+            if self.position == Position::Neutral
+                || self
+                    .risk_engine
+                    .check_maintenance_margin(&self.market_state, &self.position, &self.balances)
+                    .is_ok()
+            {
+                break;
             }
-            Position::Neutral => panic!("A neutral position can not be liquidated"),
-        };
-        self.submit_market_order(order)
-            .expect("Must be able to submit liquidation order");
+        }
         info!("balances after liquidation: {:?}", self.balances());
     }
 
+    /// Pay `amount` into the insurance fund, or draw on it if negative.
+    /// Once the insurance fund is depleted, the remaining loss would be socialized by auto-deleveraging the counterparties.
+    fn fund_insurance(&mut self, amount: BaseOrQuote::PairedCurrency) {
+        let insurance_fund = self.insurance_fund + amount;
+        if insurance_fund < BaseOrQuote::PairedCurrency::zero() {
+            warn!(
+                "The insurance fund is depleted, leaving a shortfall of {}",
+                -insurance_fund
+            );
+        }
+        self.insurance_fund = insurance_fund.max(BaseOrQuote::PairedCurrency::zero());
+    }
+
+    /// Auto-deleverage the profitable position against the bankrupt counterparty of `auto_deleverage`
+    /// according to the `AdlModel` of the `Config`, reporting the deleveraging order through the `order_events`.
+    fn deleverage(&mut self, auto_deleverage: AutoDeleverage<I, D, BaseOrQuote>) {
+        let Some(adl_model) = self.config.adl_model() else {
+            return;
+        };
+        // The liquidation order of the counterparty is matched against positions on the opposite side.
+        let (side, position_qty) = match (&self.position, auto_deleverage.side) {
+            (Position::Long(pos), Side::Buy) => (Side::Sell, pos.quantity()),
+            (Position::Short(pos), Side::Sell) => (Side::Buy, pos.quantity()),
+            _ => return,
+        };
+        let unrealized_pnl = self.position.marked_unrealized_pnl(&self.market_state);
+        if unrealized_pnl <= BaseOrQuote::PairedCurrency::zero() {
+            return;
+        }
+        let ranking = adl_model.ranking(
+            *unrealized_pnl.as_ref(),
+            *self.position.total_cost().as_ref(),
+            *self.balances.position_margin().as_ref(),
+        );
+        if ranking < adl_model.min_ranking() {
+            trace!(
+                "deleverage: ranking {ranking} below {}",
+                adl_model.min_ranking()
+            );
+            return;
+        }
+
+        let quantity = auto_deleverage.quantity.min(position_qty);
+        let meta = ExchangeOrderMeta::new(
+            self.next_order_id(),
+            self.market_state.current_timestamp_ns(),
+        );
+        let order = MarketOrder::new(side, quantity)
+            .expect("Can create market order.")
+            .into_pending(meta)
+            .into_filled(
+                auto_deleverage.bankruptcy_price,
+                self.market_state.current_timestamp_ns(),
+            );
+        warn!(
+            "auto-deleveraging: {side} {quantity} @ {}, ranking: {ranking}",
+            auto_deleverage.bankruptcy_price
+        );
+        self.settle_filled_market_order(order.clone(), BaseOrQuote::PairedCurrency::zero());
+        self.order_events
+            .push(OrderEvent::AutoDeleveraged { order, ranking });
+    }
+
     /// Submit a new `MarketOrder` to the exchange.
     ///
     /// # Arguments:
@@ -227,43 +738,133 @@ where
             self.next_order_id(),
             self.market_state.current_timestamp_ns(),
         );
-        let order = order.into_pending(meta);
+        let mut order = order.into_pending(meta);
+        if order.reduce_only()
+            && let Some(reducible_qty) = self.position.reducible_quantity(order.side())
+            && order.quantity() > reducible_qty
+        {
+            order.set_quantity(reducible_qty);
+        }
+
+        self.execute_market_order(order)
+    }
 
+    /// Execute a market order against the order book depth or the best bid or ask.
+    fn execute_market_order(
+        &mut self,
+        order: MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Result<MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>> {
         assert2::debug_assert!(self.market_state.ask() > QuoteCurrency::zero());
         assert2::debug_assert!(self.market_state.bid() > QuoteCurrency::zero());
-        let fill_price = match order.side() {
+        let fill_price = self.market_order_fill_price(order.side(), order.quantity());
+        self.risk_engine.check_market_order(
+            &self.market_state,
+            &self.position,
+            &order,
+            fill_price,
+            &self.balances,
+            &self.order_margin,
+        )?;
+
+        let filled_order = order.into_filled(fill_price, self.market_state.current_timestamp_ns());
+        let fee = self.taker_fee(filled_order.quantity(), fill_price);
+        self.settle_filled_market_order(filled_order.clone(), fee);
+
+        Ok(filled_order)
+    }
+
+    /// The average price at which a market order of `quantity` on `side` fills.
+    /// It walks the order book depth if its best price matches the best bid or ask,
+    /// otherwise the `SlippageModel` is applied to the best bid or ask.
+    fn market_order_fill_price(&self, side: Side, quantity: BaseOrQuote) -> QuoteCurrency<I, D> {
+        if self.depth_is_current(side)
+            && let Some(fill_price) = self.depth.fill_price(side, quantity)
+        {
+            return fill_price;
+        }
+        let best_price = match side {
             Side::Buy => self.market_state.ask(),
             Side::Sell => self.market_state.bid(),
         };
-        self.risk_engine
-            .check_market_order(&self.position, &order, fill_price, &self.balances)?;
+        self.config.slippage_model().fill_price(
+            side,
+            best_price,
+            quantity,
+            self.config.contract_spec().price_filter().tick_size(),
+        )
+    }
 
-        let filled_order = order.into_filled(fill_price, self.market_state.current_timestamp_ns());
-        self.settle_filled_market_order(filled_order.clone());
+    /// Whether the best price on the away side of the depth for an order on `side` matches the best bid or ask,
+    /// as otherwise the depth is stale.
+    #[inline]
+    fn depth_is_current(&self, side: Side) -> bool {
+        match side {
+            Side::Buy => self.depth.best_ask() == Some(self.market_state.ask()),
+            Side::Sell => self.depth.best_bid() == Some(self.market_state.bid()),
+        }
+    }
 
-        Ok(filled_order)
+    /// The visible quantity resting at `price` on the side of the book of an order on `side`,
+    /// if the depth of that side is current.
+    fn visible_quantity(&self, side: Side, price: QuoteCurrency<I, D>) -> Option<BaseOrQuote> {
+        if !self.depth_is_current(side.inverted()) {
+            return None;
+        }
+        self.depth.visible_quantity(side, price)
+    }
+
+    /// The taker fee of filling `quantity` at `fill_price`, according to the current fee tier.
+    #[inline]
+    fn taker_fee(
+        &self,
+        quantity: BaseOrQuote,
+        fill_price: QuoteCurrency<I, D>,
+    ) -> BaseOrQuote::PairedCurrency {
+        let notional = BaseOrQuote::PairedCurrency::convert_from(quantity, fill_price);
+        let fee_taker = self
+            .config
+            .contract_spec()
+            .fee_taker_for(self.traded_volume.volume());
+        notional * *fee_taker.as_ref()
+    }
+
+    /// The maker fee of filling `quantity` at `limit_price` according to the current fee tier,
+    /// which is negative for a rebate.
+    #[inline]
+    fn maker_fee(
+        &self,
+        quantity: BaseOrQuote,
+        limit_price: QuoteCurrency<I, D>,
+    ) -> BaseOrQuote::PairedCurrency {
+        let notional = BaseOrQuote::PairedCurrency::convert_from(quantity, limit_price);
+        let fee_maker = self
+            .config
+            .contract_spec()
+            .fee_maker_for(self.traded_volume.volume());
+        notional * *fee_maker.as_ref()
     }
 
     fn settle_filled_market_order(
         &mut self,
         order: MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>,
+        fee: BaseOrQuote::PairedCurrency,
     ) {
         let filled_qty = order.quantity();
         assert2::debug_assert!(filled_qty > BaseOrQuote::zero());
         let fill_price = order.state().avg_fill_price();
         assert2::debug_assert!(fill_price > QuoteCurrency::zero());
 
-        let notional = BaseOrQuote::PairedCurrency::convert_from(filled_qty, fill_price);
-        let fee = notional * *self.config.contract_spec().fee_taker().as_ref();
-
-        self.position.change(
+        self.change_position(
+            order.state().meta().id(),
             filled_qty,
             fill_price,
             order.side(),
-            &mut self.balances,
-            self.config.contract_spec().init_margin_req(),
+            fee,
+            Liquidity::Taker,
         );
         self.balances.account_for_fee(fee);
+        self.rebalance_order_margin();
+        self.enforce_reduce_only_orders();
     }
 
     #[inline]
@@ -295,43 +896,195 @@ where
         self.config
             .contract_spec()
             .price_filter()
-            .validate_limit_price(order.limit_price(), self.market_state.mid_price())?;
+            .validate_limit_price(order.limit_price(), self.market_state.mark_price())?;
+        if order
+            .time_in_force()
+            .is_expired(self.market_state.current_ts_ns())
+        {
+            return Err(Error::OrderError(OrderError::GoodTilDateExpired));
+        }
 
         let meta = ExchangeOrderMeta::new(
             self.next_order_id(),
             self.market_state.current_timestamp_ns(),
         );
-        let order = order.into_pending(meta);
-
-        self.risk_engine.check_limit_order(
-            &self.position,
-            &order,
-            self.balances.available(),
-            &self.order_margin,
-        )?;
+        let mut order = order.into_pending(meta);
+        if order.reduce_only()
+            && let Some(reducible_qty) = self.position.reducible_quantity(order.side())
+            && order.remaining_quantity() > reducible_qty
+        {
+            order.set_remaining_quantity(reducible_qty);
+        }
 
-        // If a limit order is marketable, it will take liquidity from the book at the `limit_price` price level and pay the taker fee,
+        // A limit order is marketable if it locks or crosses the away market quotation.
+        let away_market_quotation_price = match order.side() {
+            Side::Buy => self.market_state.ask(),
+            Side::Sell => self.market_state.bid(),
+        };
         let marketable = match order.side() {
-            Side::Buy => order.limit_price() >= self.market_state.ask(),
-            Side::Sell => order.limit_price() <= self.market_state.bid(),
+            Side::Buy => order.limit_price() >= away_market_quotation_price,
+            Side::Sell => order.limit_price() <= away_market_quotation_price,
         };
-        match order.re_pricing() {
-            RePricing::GoodTilCrossing => {
-                if marketable {
+        if order.time_in_force().is_immediate() {
+            // A marketable order is filled entirely unless the order book depth limits the liquidity,
+            // which is when `FillOrKill` and `ImmediateOrCancel` differ.
+            // Without a current depth, the away market quotation fills both entirely, like a market order.
+            if marketable {
+                order = self.fill_marketable_limit_order(order)?;
+            }
+            if order.remaining_quantity() > BaseOrQuote::zero() {
+                debug!("cancelling unfilled remainder of order {}", order.id());
+                self.order_events.push(OrderEvent::Cancelled {
+                    order_id: order.id(),
+                    reason: CancelReason::UnfilledRemainder,
+                });
+            }
+            return Ok(order);
+        }
+        if marketable {
+            match order.re_pricing() {
+                RePricing::Marketable => {
+                    order = self.fill_marketable_limit_order(order)?;
+                    if order.remaining_quantity().is_zero() {
+                        return Ok(order);
+                    }
+                    // The remainder rests in the book at the `limit_price`.
+                }
+                RePricing::GoodTilCrossing => {
                     return Err(Error::OrderError(
                         OrderError::GoodTillCrossingRejectedOrder {
                             limit_price: order.limit_price().to_string(),
-                            away_market_quotation_price: match order.side() {
-                                Side::Buy => self.market_state.ask().to_string(),
-                                Side::Sell => self.market_state.bid().to_string(),
-                            },
+                            away_market_quotation_price: away_market_quotation_price.to_string(),
                         },
                     ));
                 }
+                RePricing::PriceAdjust => {
+                    let tick_size = self.config.contract_spec().price_filter().tick_size();
+                    let adjusted_price = match order.side() {
+                        Side::Buy => away_market_quotation_price - tick_size,
+                        Side::Sell => away_market_quotation_price + tick_size,
+                    };
+                    if adjusted_price <= QuoteCurrency::zero() {
+                        return Err(Error::OrderError(OrderError::LimitPriceLTEZero));
+                    }
+                    debug!(
+                        "adjusted limit price of order {} from {} to {adjusted_price}",
+                        order.id(),
+                        order.limit_price()
+                    );
+                    order.set_limit_price(adjusted_price);
+                }
+                RePricing::HideNotSlide => {
+                    self.risk_engine.check_limit_order(
+                        &self.market_state,
+                        &self.position,
+                        &order,
+                        &self.balances,
+                        &self.order_margin,
+                    )?;
+                    self.order_margin.try_insert_hidden(
+                        order.clone(),
+                        &mut self.balances,
+                        &self.position,
+                        self.config.contract_spec(),
+                    )?;
+                    return Ok(order);
+                }
             }
         }
 
-        self.append_limit_order(order.clone(), marketable)?;
+        self.risk_engine.check_limit_order(
+            &self.market_state,
+            &self.position,
+            &order,
+            &self.balances,
+            &self.order_margin,
+        )?;
+        if self.config.queue_position_tracking() {
+            order.set_queue_ahead(self.visible_quantity(order.side(), order.limit_price()));
+        }
+        self.append_limit_order(order.clone())?;
+
+        Ok(order)
+    }
+
+    /// Immediately fill a `Marketable` limit order which locks or crosses the away market quotation,
+    /// taking liquidity from the order book depth up to the `limit_price` and paying the taker fee.
+    /// Without current depth, it fills at the price of a market order, capped at the `limit_price`.
+    /// A `FillOrKill` order is not filled at all, if the depth can not fill it entirely.
+    /// The returned order reflects the filled quantity.
+    fn fill_marketable_limit_order(
+        &mut self,
+        mut order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Result<LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        let (filled_qty, fill_price) = self
+            .depth_is_current(order.side())
+            .then(|| {
+                self.depth.marketable_fill(
+                    order.side(),
+                    order.limit_price(),
+                    order.remaining_quantity(),
+                )
+            })
+            .flatten()
+            .unwrap_or_else(|| {
+                // Without current depth information, the entire quantity fills like a market order
+                // at the away market quotation, but never beyond the `limit_price`.
+                let market_price =
+                    self.market_order_fill_price(order.side(), order.remaining_quantity());
+                let fill_price = match order.side() {
+                    Side::Buy => market_price.min(order.limit_price()),
+                    Side::Sell => market_price.max(order.limit_price()),
+                };
+                (order.remaining_quantity(), fill_price)
+            });
+        if matches!(order.time_in_force(), TimeInForce::FillOrKill)
+            && filled_qty < order.remaining_quantity()
+        {
+            debug!(
+                "insufficient depth to fill order {}: {filled_qty}/{}",
+                order.id(),
+                order.remaining_quantity()
+            );
+            return Ok(order);
+        }
+        let mut taker_order =
+            MarketOrder::new_with_user_order_id(order.side(), filled_qty, order.user_order_id())
+                .expect("Limit order was validated upon creation");
+        taker_order.set_reduce_only(order.reduce_only());
+        let taker_order = taker_order.into_pending(order.state().meta().clone());
+        self.risk_engine.check_market_order(
+            &self.market_state,
+            &self.position,
+            &taker_order,
+            fill_price,
+            &self.balances,
+            &self.order_margin,
+        )?;
+
+        let fee = self.taker_fee(filled_qty, fill_price);
+        debug!(
+            "filled marketable limit {} order {}: {filled_qty} @ {fill_price}",
+            order.side(),
+            order.id(),
+        );
+        let _ = order.fill_at(
+            fill_price,
+            filled_qty,
+            fee,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.change_position(
+            order.id(),
+            filled_qty,
+            fill_price,
+            order.side(),
+            fee,
+            Liquidity::Taker,
+        );
+        self.balances.account_for_fee(fee);
+        self.rebalance_order_margin();
+        self.enforce_reduce_only_orders();
 
         Ok(order)
     }
@@ -352,6 +1105,11 @@ where
         let existing_order = self
             .active_limit_orders()
             .get_by_id(existing_order_id, new_order.side()) // Its assumed that `new_order` has the same side as existing order.
+            .or_else(|| {
+                self.hidden_limit_orders()
+                    .iter()
+                    .find(|order| order.id() == existing_order_id)
+            })
             .ok_or_else(|| {
                 if existing_order_id < self.next_order_id {
                     Error::OrderNoLongerActive
@@ -372,28 +1130,56 @@ where
         let qty_delta = new_order.total_quantity() - existing_order.total_quantity();
         trace!("qty_delta: {qty_delta}");
         let new_leaves_qty = existing_order.remaining_quantity() + qty_delta;
+        // Linked orders are not cancelled by the amend, instead they are re-linked to the new order.
+        let group = self.order_groups.take(existing_order_id);
         if new_leaves_qty <= BaseOrQuote::zero() {
             self.cancel_limit_order(CancelBy::OrderId(existing_order_id))
                 .expect("Can cancel this order");
+            self.relink_cancelled_order(group, existing_order_id);
             return Err(Error::AmendQtyAlreadyFilled);
         }
 
         new_order.set_remaining_quantity(new_leaves_qty);
 
-        self.cancel_limit_order(CancelBy::OrderId(existing_order_id))?;
-        self.submit_limit_order(new_order)
+        if let Err(e) = self.cancel_limit_order(CancelBy::OrderId(existing_order_id)) {
+            if let Some(group) = group {
+                self.order_groups.push(group);
+            }
+            return Err(e);
+        }
+        match self.submit_limit_order(new_order) {
+            Ok(order) => {
+                if let Some(mut group) = group {
+                    group.replace_id(existing_order_id, order.id());
+                    self.order_groups.push(group);
+                }
+                Ok(order)
+            }
+            Err(e) => {
+                self.relink_cancelled_order(group, existing_order_id);
+                Err(e)
+            }
+        }
     }
 
-    /// Append a new limit order as active order.
-    /// If limit order is `marketable`, the order will take liquidity from the book at the `limit_price` price level.
-    /// Then it pays the taker fee for the quantity that was taken from the book, the rest of the quantity (if any)
-    /// will be placed into the book as a passive order.
-    fn append_limit_order(
+    /// Put back the `group` of an order that was cancelled while amending it, cancelling the linked orders.
+    fn relink_cancelled_order(
         &mut self,
-        order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
-        marketable: bool,
-    ) -> Result<()> {
-        trace!("append_limit_order: order: {order}, marketable: {marketable}");
+        group: Option<OrderGroup<I, D, BaseOrQuote, UserOrderIdT>>,
+        order_id: OrderId,
+    ) {
+        if let Some(group) = group {
+            self.order_groups.push(group);
+            self.propagate_cancel(order_id);
+        }
+    }
+
+    /// Append a new limit order as a passive order into the book.
+    fn append_limit_order(
+        &mut self,
+        order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Result<()> {
+        trace!("append_limit_order: order: {order}");
         trace!(
             "active_limit_orders: {}, market_state: {}, position: {}",
             self.active_limit_orders(),
@@ -401,10 +1187,13 @@ where
             self.position,
         );
 
-        let init_margin_req = self.config().contract_spec().init_margin_req();
-        self.order_margin
-            .try_insert(order, &mut self.balances, &self.position, init_margin_req)?;
-        debug_assert!(if self.active_limit_orders().is_empty() {
+        self.order_margin.try_insert(
+            order,
+            &mut self.balances,
+            &self.position,
+            self.config.contract_spec(),
+        )?;
+        debug_assert!(if self.order_margin.is_empty() {
             self.balances.order_margin().is_zero()
         } else {
             true
@@ -423,23 +1212,22 @@ where
         trace!("cancel_order: by {:?}", cancel_by);
         self.order_rate_limiter
             .aquire(self.market_state.current_ts_ns())?;
-        debug_assert_eq!(
-            self.balances.order_margin(),
-            self.order_margin.order_margin(
-                self.config.contract_spec().init_margin_req(),
-                &self.position,
-            )
+        assert2::debug_assert!(
+            self.balances.order_margin()
+                <= self
+                    .order_margin
+                    .tiered_order_margin(self.config.contract_spec(), &self.position)
         );
 
-        let init_margin_req = self.config().contract_spec().init_margin_req();
         let removed_order = self.order_margin.remove(
             cancel_by,
             &mut self.balances,
             &self.position,
-            init_margin_req,
+            self.config.contract_spec(),
         )?;
+        self.propagate_cancel(removed_order.id());
 
-        assert!(if self.active_limit_orders().is_empty() {
+        assert!(if self.order_margin.is_empty() {
             self.balances.order_margin().is_zero()
         } else {
             true
@@ -448,6 +1236,699 @@ where
         Ok(removed_order)
     }
 
+    /// Submit a new `StopOrder` to the exchange.
+    /// It reserves order margin until it is either triggered or cancelled.
+    /// A stop order which would trigger immediately is rejected.
+    ///
+    /// # Arguments:
+    /// `order`: The order that is being submitted.
+    ///
+    /// # Returns:
+    /// If Ok, the order with timestamp and id filled in.
+    /// Else its an error.
+    pub fn submit_stop_order(
+        &mut self,
+        order: StopOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    ) -> Result<StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        trace!("submit_stop_order: {order}");
+
+        self.order_rate_limiter
+            .aquire(self.market_state.current_ts_ns())?;
+        // Basic checks
+        self.config
+            .contract_spec()
+            .quantity_filter()
+            .validate_order_quantity(order.quantity())?;
+        let price_filter = self.config.contract_spec().price_filter();
+        // The trigger price of a trailing stop is derived from the reference price below.
+        if order.trailing_offset().is_none() {
+            price_filter
+                .validate_limit_price(order.trigger_price(), self.market_state.mark_price())?;
+        }
+        if let StopOrderType::Limit { limit_price } = order.order_type() {
+            price_filter.validate_limit_price(limit_price, self.market_state.mark_price())?;
+        }
+
+        let meta = ExchangeOrderMeta::new(
+            self.next_order_id(),
+            self.market_state.current_timestamp_ns(),
+        );
+        let mut order = order.into_pending(meta);
+
+        if order.trailing_offset().is_some() {
+            let trigger_price = order
+                .trigger_by()
+                .reference_price(&self.market_state, order.side())
+                .and_then(|reference_price| order.trailed_trigger_price(reference_price))
+                .filter(|trigger_price| *trigger_price > QuoteCurrency::zero())
+                .ok_or(Error::OrderError(
+                    OrderError::TrailingStopWithoutReferencePrice,
+                ))?;
+            order.set_trigger_price(trigger_price);
+        }
+
+        if let Some(reference_price) = order
+            .trigger_by()
+            .reference_price(&self.market_state, order.side())
+            && order.is_triggered(reference_price)
+        {
+            return Err(Error::OrderError(
+                OrderError::StopOrderWouldTriggerImmediately {
+                    trigger_price: order.trigger_price().to_string(),
+                    reference_price: reference_price.to_string(),
+                },
+            ));
+        }
+
+        self.risk_engine.check_stop_order(
+            &self.market_state,
+            &self.position,
+            &order,
+            &self.balances,
+            &self.order_margin,
+        )?;
+
+        self.order_margin.try_insert_stop(
+            order.clone(),
+            &mut self.balances,
+            &self.position,
+            self.config.contract_spec(),
+        )?;
+        self.balances.debug_assert_state();
+
+        Ok(order)
+    }
+
+    /// Cancel an active stop order, releasing its order margin.
+    pub fn cancel_stop_order(
+        &mut self,
+        cancel_by: CancelBy<UserOrderIdT>,
+    ) -> Result<StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        trace!("cancel_stop_order: by {:?}", cancel_by);
+        self.order_rate_limiter
+            .aquire(self.market_state.current_ts_ns())?;
+
+        let removed_order = self.order_margin.remove_stop(
+            cancel_by,
+            &mut self.balances,
+            &self.position,
+            self.config.contract_spec(),
+        )?;
+        self.propagate_cancel(removed_order.id());
+
+        assert!(if self.order_margin.is_empty() {
+            self.balances.order_margin().is_zero()
+        } else {
+            true
+        });
+
+        Ok(removed_order)
+    }
+
+    /// Check the active stop orders against their reference prices and trigger them if needed.
+    fn check_stop_orders(&mut self) {
+        let mut i = 0;
+        while let Some(order) = self.order_margin.stop_orders().get(i) {
+            let Some(reference_price) = order
+                .trigger_by()
+                .reference_price(&self.market_state, order.side())
+            else {
+                i += 1;
+                continue;
+            };
+            if order.is_triggered(reference_price) {
+                let order_id = order.id();
+                // Triggering may also cancel linked stop orders, so start over.
+                self.trigger_stop_order(order_id, reference_price);
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Cancel the limit orders whose `GoodTilDate` has expired, releasing their order margin.
+    fn expire_orders(&mut self) {
+        let ts_ns = self.market_state.current_ts_ns();
+        while let Some(order_id) = self.order_margin.first_expired_order(ts_ns) {
+            debug!("limit order {order_id} expired at {ts_ns}");
+            self.order_margin
+                .remove(
+                    CancelBy::OrderId(order_id),
+                    &mut self.balances,
+                    &self.position,
+                    self.config.contract_spec(),
+                )
+                .expect("Can remove order as its an internal call");
+            self.order_events.push(OrderEvent::Cancelled {
+                order_id,
+                reason: CancelReason::Expired,
+            });
+            self.propagate_cancel(order_id);
+        }
+    }
+
+    /// Place hidden `HideNotSlide` limit orders passively into the book once the away market quotation is lifted.
+    fn reveal_hidden_orders(&mut self) {
+        let mut i = 0;
+        while let Some(order) = self.order_margin.hidden_orders().get(i) {
+            let still_marketable = match order.side() {
+                Side::Buy => order.limit_price() >= self.market_state.ask(),
+                Side::Sell => order.limit_price() <= self.market_state.bid(),
+            };
+            if still_marketable {
+                i += 1;
+                continue;
+            }
+            debug!("revealing hidden limit order {order}");
+            // The order is removed, so the next one moves into index `i`.
+            if let Err(e) = self.order_margin.reveal_hidden(i) {
+                debug!("could not reveal hidden limit order: {e}");
+                i += 1;
+            }
+        }
+    }
+
+    /// Advance the tracked queue position of resting limit orders by the quantity traded at their price.
+    /// Cancellations ahead of an order are accounted for by never letting its queue exceed the visible quantity
+    /// of its price level, which assumes the worst case of them happening in front of it.
+    fn advance_queue_positions<U>(&mut self, market_update: &U)
+    where
+        U: MarketUpdate<I, D, BaseOrQuote>,
+    {
+        if !self.config.queue_position_tracking() {
+            return;
+        }
+        let bids_current = self.depth_is_current(Side::Sell);
+        let asks_current = self.depth_is_current(Side::Buy);
+        for order in self.order_margin.active_limit_orders_mut().iter_mut() {
+            let Some(queue_ahead) = order.queue_ahead() else {
+                continue;
+            };
+            let traded_qty = market_update.traded_quantity(order.side(), order.limit_price());
+            let mut queue_ahead = (queue_ahead - traded_qty).max(BaseOrQuote::zero());
+            let depth_is_current = match order.side() {
+                Side::Buy => bids_current,
+                Side::Sell => asks_current,
+            };
+            if depth_is_current
+                && let Some(visible_qty) = self
+                    .depth
+                    .visible_quantity(order.side(), order.limit_price())
+            {
+                queue_ahead = queue_ahead.min(visible_qty);
+            }
+            order.set_queue_ahead(Some(queue_ahead));
+        }
+    }
+
+    /// Move the trigger price of trailing stop orders along with the best price seen.
+    /// As the order of prices within the `price_range` is unknown, a trailing stop which is reached by the
+    /// adverse end of the range is triggered before it trails the favourable end.
+    fn update_trailing_stops(
+        &mut self,
+        price_range: Option<(QuoteCurrency<I, D>, QuoteCurrency<I, D>)>,
+    ) {
+        let mut i = 0;
+        while let Some(order) = self.order_margin.stop_orders().get(i) {
+            if order.trailing_offset().is_none() {
+                i += 1;
+                continue;
+            }
+            let order_id = order.id();
+            let reference_price = order
+                .trigger_by()
+                .reference_price(&self.market_state, order.side());
+            let best_price = match price_range {
+                Some((low, high)) => {
+                    let (adverse, favourable) = match order.side() {
+                        Side::Buy => (high, low),
+                        Side::Sell => (low, high),
+                    };
+                    if order.is_triggered(adverse) {
+                        // Triggering may also cancel linked stop orders, so start over.
+                        self.trigger_stop_order(order_id, adverse);
+                        i = 0;
+                        continue;
+                    }
+                    Some(match (order.side(), reference_price) {
+                        (Side::Buy, Some(price)) => favourable.min(price),
+                        (Side::Sell, Some(price)) => favourable.max(price),
+                        (_, None) => favourable,
+                    })
+                }
+                None => reference_price,
+            };
+            let Some(trigger_price) =
+                best_price.and_then(|price| order.trailed_trigger_price(price))
+            else {
+                i += 1;
+                continue;
+            };
+            // The order is re-inserted at the back, so the next one moves into index `i`.
+            self.retrail_stop_order(order_id, trigger_price);
+        }
+    }
+
+    /// Move the `trigger_price` of a trailing stop order, which may change its order margin.
+    fn retrail_stop_order(&mut self, order_id: OrderId, trigger_price: QuoteCurrency<I, D>) {
+        trace!("retrail_stop_order: order_id: {order_id}, trigger_price: {trigger_price}");
+        let mut order = self
+            .order_margin
+            .remove_stop(
+                CancelBy::OrderId(order_id),
+                &mut self.balances,
+                &self.position,
+                self.config.contract_spec(),
+            )
+            .expect("Can remove stop order as its an internal call");
+        order.set_trigger_price(trigger_price);
+        if let Err(e) = self.insert_pending_order(LinkedOrder::Stop(order)) {
+            debug!("could not trail stop order {order_id}: {e}");
+            self.order_events.push(OrderEvent::Cancelled {
+                order_id,
+                reason: CancelReason::NotEnoughAvailableBalance,
+            });
+            self.propagate_cancel(order_id);
+        }
+    }
+
+    /// Convert a triggered `StopOrder` into either a `MarketOrder` or a `LimitOrder`.
+    /// A stop-limit order is placed into the book even if it locks or crosses the away market quotation.
+    fn trigger_stop_order(&mut self, order_id: OrderId, reference_price: QuoteCurrency<I, D>) {
+        let stop_order = self
+            .order_margin
+            .remove_stop(
+                CancelBy::OrderId(order_id),
+                &mut self.balances,
+                &self.position,
+                self.config.contract_spec(),
+            )
+            .expect("Can remove stop order as its an internal call");
+        debug!("triggered stop order {stop_order} @ {reference_price}");
+
+        // The resulting order keeps the `OrderId` of the stop order.
+        let meta = ExchangeOrderMeta::new(order_id, self.market_state.current_timestamp_ns());
+        let triggered_order = match stop_order.order_type() {
+            StopOrderType::Market => {
+                let order = stop_order
+                    .to_market_order(meta)
+                    .expect("Is a stop-market order");
+                match self.execute_market_order(order) {
+                    Ok(filled_order) => TriggeredOrder::Market(filled_order),
+                    Err(e) => TriggeredOrder::Rejected(e),
+                }
+            }
+            StopOrderType::Limit { .. } => {
+                let order = stop_order
+                    .to_limit_order(meta)
+                    .expect("Is a stop-limit order");
+                match self.insert_pending_order(LinkedOrder::Limit(order.clone())) {
+                    Ok(()) => TriggeredOrder::Limit(order),
+                    Err(e) => TriggeredOrder::Rejected(e),
+                }
+            }
+        };
+        self.order_events.push(OrderEvent::StopTriggered {
+            stop_order,
+            reference_price,
+            triggered_order,
+        });
+        self.propagate_trigger(order_id);
+    }
+
+    /// Insert an order which already has its `OrderId` assigned, after checking the margin requirements.
+    fn insert_pending_order(
+        &mut self,
+        order: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Result<()> {
+        match order {
+            LinkedOrder::Limit(order) => {
+                self.risk_engine.check_limit_order(
+                    &self.market_state,
+                    &self.position,
+                    &order,
+                    &self.balances,
+                    &self.order_margin,
+                )?;
+                self.append_limit_order(order)
+            }
+            LinkedOrder::Stop(order) => {
+                self.risk_engine.check_stop_order(
+                    &self.market_state,
+                    &self.position,
+                    &order,
+                    &self.balances,
+                    &self.order_margin,
+                )?;
+                self.order_margin.try_insert_stop(
+                    order,
+                    &mut self.balances,
+                    &self.position,
+                    self.config.contract_spec(),
+                )
+            }
+        }
+    }
+
+    /// Submit a one-cancels-other pair of orders.
+    /// A fill of one order reduces the quantity of the other one by the filled quantity,
+    /// while a triggered or cancelled order cancels the other one.
+    /// If the second order is rejected, the first one is cancelled as well.
+    ///
+    /// # Returns:
+    /// If Ok, both orders with timestamp and id filled in.
+    /// Else its an error.
+    pub fn submit_oco_order(
+        &mut self,
+        order_0: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+        order_1: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    ) -> Result<[LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>; 2]> {
+        let order_0 = self.submit_linked_order(order_0)?;
+        let order_1 = match self.submit_linked_order(order_1) {
+            Ok(order) => order,
+            Err(e) => {
+                self.remove_linked_order(order_0.id());
+                return Err(e);
+            }
+        };
+        self.order_groups
+            .push(OrderGroup::Oco([order_0.id(), order_1.id()]));
+
+        Ok([order_0, order_1])
+    }
+
+    /// Submit a bracket order, consisting of an `entry` limit order with a `take_profit` and `stop_loss` exit.
+    /// Both exit orders must be on the opposite side of the entry order, as they close the position it opens.
+    /// The exit orders are held back until the entry order is filled, which may already happen upon submission
+    /// if the entry order is marketable or has an immediate `TimeInForce`.
+    /// Their quantity is given by the filled quantity of the entry order and grows with each fill of it,
+    /// with each activation or adjustment reported through the `order_events`.
+    /// Once active, the exit orders behave like a one-cancels-other pair.
+    /// If an exit order is triggered, filled or cancelled, the remainder of the entry order is cancelled as well.
+    /// If the entry order is cancelled before any fill, including an unfilled `ImmediateOrCancel` or `FillOrKill` order,
+    /// the exit orders are dropped.
+    ///
+    /// # Arguments:
+    /// `entry`: The limit order opening the position.
+    /// `take_profit`: The exit order realizing the profit.
+    /// `stop_loss`: The exit order limiting the loss.
+    ///
+    /// # Returns:
+    /// If Ok, the entry order and the exit orders with timestamp and id filled in,
+    /// where the exit orders carry the quantity of the entry order and may not be active yet.
+    /// Else its an error, e.g. `OrderError::InvalidBracketExitSide` if an exit order is on the side of the entry order,
+    /// a price filter error of an exit order or any error of `submit_limit_order` for the entry order.
+    #[allow(clippy::type_complexity)]
+    pub fn submit_bracket_order(
+        &mut self,
+        entry: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+        take_profit: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+        stop_loss: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    ) -> Result<(
+        LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        [LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>; 2],
+    )> {
+        let exit_side = entry.side().inverted();
+        if take_profit.side() != exit_side || stop_loss.side() != exit_side {
+            return Err(Error::OrderError(OrderError::InvalidBracketExitSide));
+        }
+        self.validate_linked_order_prices(&take_profit)?;
+        self.validate_linked_order_prices(&stop_loss)?;
+
+        let entry = self.submit_limit_order(entry)?;
+        let exits = [take_profit, stop_loss].map(|order| {
+            let meta = ExchangeOrderMeta::new(
+                self.next_order_id(),
+                self.market_state.current_timestamp_ns(),
+            );
+            order.into_pending(meta)
+        });
+        self.order_groups.push(OrderGroup::Bracket {
+            entry: entry.id(),
+            exits: BracketExits::Inactive(Box::new(exits.clone())),
+        });
+
+        // The entry order may have been filled upon submission, before the group existed.
+        let filled_qty = entry.filled_quantity();
+        let remaining_qty = entry.remaining_quantity();
+        if filled_qty > BaseOrQuote::zero() {
+            self.propagate_fill(entry.id(), filled_qty, remaining_qty.is_zero());
+        }
+        // The unfilled remainder of an immediate order is cancelled instead of resting in the book.
+        if remaining_qty > BaseOrQuote::zero() && entry.time_in_force().is_immediate() {
+            self.propagate_cancel(entry.id());
+        }
+
+        Ok((entry, exits))
+    }
+
+    fn submit_linked_order(
+        &mut self,
+        order: LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    ) -> Result<LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        match order {
+            LinkedOrder::Limit(order) => self.submit_limit_order(order).map(LinkedOrder::Limit),
+            LinkedOrder::Stop(order) => self.submit_stop_order(order).map(LinkedOrder::Stop),
+        }
+    }
+
+    fn validate_linked_order_prices(
+        &self,
+        order: &LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    ) -> Result<()> {
+        let price_filter = self.config.contract_spec().price_filter();
+        let mark_price = self.market_state.mark_price();
+        match order {
+            LinkedOrder::Limit(order) => {
+                price_filter.validate_limit_price(order.limit_price(), mark_price)?
+            }
+            LinkedOrder::Stop(order) => {
+                price_filter.validate_limit_price(order.trigger_price(), mark_price)?;
+                if let StopOrderType::Limit { limit_price } = order.order_type() {
+                    price_filter.validate_limit_price(limit_price, mark_price)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove an active limit or stop order without any further checks.
+    fn remove_linked_order(
+        &mut self,
+        order_id: OrderId,
+    ) -> Option<LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        if let Ok(order) = self.order_margin.remove(
+            CancelBy::OrderId(order_id),
+            &mut self.balances,
+            &self.position,
+            self.config.contract_spec(),
+        ) {
+            return Some(LinkedOrder::Limit(order));
+        }
+        self.order_margin
+            .remove_stop(
+                CancelBy::OrderId(order_id),
+                &mut self.balances,
+                &self.position,
+                self.config.contract_spec(),
+            )
+            .ok()
+            .map(LinkedOrder::Stop)
+    }
+
+    /// Cancel a linked order if its still active.
+    fn cancel_linked_order(&mut self, order_id: OrderId, reason: CancelReason) {
+        if self.remove_linked_order(order_id).is_some() {
+            debug!("cancelled linked order {order_id}, reason: {reason:?}");
+            self.order_events
+                .push(OrderEvent::Cancelled { order_id, reason });
+        }
+    }
+
+    /// Change the quantity of a linked order by `qty_delta`, cancelling it if nothing remains.
+    /// Returns `true` if the order is still active afterwards.
+    fn adjust_linked_order(&mut self, order_id: OrderId, qty_delta: BaseOrQuote) -> bool {
+        let Some(mut order) = self.remove_linked_order(order_id) else {
+            return false;
+        };
+        let quantity = order.quantity() + qty_delta;
+        if quantity <= BaseOrQuote::zero() {
+            self.order_events.push(OrderEvent::Cancelled {
+                order_id,
+                reason: CancelReason::LinkedOrder,
+            });
+            return false;
+        }
+        order.set_quantity(quantity);
+        if let Err(e) = self.insert_pending_order(order) {
+            debug!("could not adjust linked order {order_id}: {e}");
+            self.order_events.push(OrderEvent::Cancelled {
+                order_id,
+                reason: CancelReason::NotEnoughAvailableBalance,
+            });
+            return false;
+        }
+        self.order_events
+            .push(OrderEvent::QuantityAdjusted { order_id, quantity });
+
+        true
+    }
+
+    /// Activate or grow the exit orders of a bracket after its entry order got `filled_qty` filled.
+    /// Returns the ids of the active exit orders, or `None` if they could not be placed.
+    fn grow_bracket_exits(
+        &mut self,
+        exits: BracketExits<I, D, BaseOrQuote, UserOrderIdT>,
+        filled_qty: BaseOrQuote,
+    ) -> Option<[OrderId; 2]> {
+        match exits {
+            BracketExits::Inactive(orders) => {
+                let ids = [orders[0].id(), orders[1].id()];
+                for mut order in *orders {
+                    let order_id = order.id();
+                    order.set_quantity(filled_qty);
+                    if let Err(e) = self.insert_pending_order(order) {
+                        debug!("could not activate bracket exit {order_id}: {e}");
+                        self.cancel_linked_order(
+                            other_leg(ids, order_id),
+                            CancelReason::LinkedOrder,
+                        );
+                        self.order_events.push(OrderEvent::Cancelled {
+                            order_id,
+                            reason: CancelReason::NotEnoughAvailableBalance,
+                        });
+                        return None;
+                    }
+                    self.order_events.push(OrderEvent::Activated {
+                        order_id,
+                        quantity: filled_qty,
+                    });
+                }
+                Some(ids)
+            }
+            BracketExits::Active(ids) => {
+                for order_id in ids {
+                    if !self.adjust_linked_order(order_id, filled_qty) {
+                        self.cancel_linked_order(
+                            other_leg(ids, order_id),
+                            CancelReason::LinkedOrder,
+                        );
+                        return None;
+                    }
+                }
+                Some(ids)
+            }
+        }
+    }
+
+    /// Propagate a fill of `filled_qty` of the order with `order_id` to its linked orders.
+    fn propagate_fill(&mut self, order_id: OrderId, filled_qty: BaseOrQuote, fully_filled: bool) {
+        let Some(group) = self.order_groups.take(order_id) else {
+            return;
+        };
+        trace!("propagate_fill: order_id: {order_id}, filled_qty: {filled_qty}, group: {group:?}");
+        match group {
+            OrderGroup::Oco(legs) => {
+                let other = other_leg(legs, order_id);
+                if fully_filled {
+                    self.cancel_linked_order(other, CancelReason::LinkedOrder);
+                } else if self.adjust_linked_order(other, -filled_qty) {
+                    self.order_groups.push(OrderGroup::Oco(legs));
+                }
+            }
+            OrderGroup::Bracket { entry, exits } if entry == order_id => {
+                match self.grow_bracket_exits(exits, filled_qty) {
+                    Some(exits) if fully_filled => self.order_groups.push(OrderGroup::Oco(exits)),
+                    Some(exits) => self.order_groups.push(OrderGroup::Bracket {
+                        entry,
+                        exits: BracketExits::Active(exits),
+                    }),
+                    // Without active exits the remaining entry is cancelled.
+                    None => self.cancel_linked_order(entry, CancelReason::LinkedOrder),
+                }
+            }
+            OrderGroup::Bracket {
+                entry,
+                exits: BracketExits::Active(legs),
+            } => {
+                let other = other_leg(legs, order_id);
+                let other_active = if fully_filled {
+                    self.cancel_linked_order(other, CancelReason::LinkedOrder);
+                    false
+                } else {
+                    self.adjust_linked_order(other, -filled_qty)
+                };
+                if other_active {
+                    self.order_groups.push(OrderGroup::Bracket {
+                        entry,
+                        exits: BracketExits::Active(legs),
+                    });
+                } else {
+                    self.cancel_linked_order(entry, CancelReason::LinkedOrder);
+                }
+            }
+            OrderGroup::Bracket {
+                exits: BracketExits::Inactive(_),
+                ..
+            } => unreachable!("Only the entry order of an inactive bracket can be filled"),
+        }
+    }
+
+    /// Propagate a triggered stop order with `order_id` to its linked orders.
+    fn propagate_trigger(&mut self, order_id: OrderId) {
+        let Some(group) = self.order_groups.take(order_id) else {
+            return;
+        };
+        trace!("propagate_trigger: order_id: {order_id}, group: {group:?}");
+        match group {
+            OrderGroup::Oco(legs) => {
+                self.cancel_linked_order(other_leg(legs, order_id), CancelReason::LinkedOrder)
+            }
+            OrderGroup::Bracket {
+                entry,
+                exits: BracketExits::Active(legs),
+            } => {
+                self.cancel_linked_order(other_leg(legs, order_id), CancelReason::LinkedOrder);
+                self.cancel_linked_order(entry, CancelReason::LinkedOrder);
+            }
+            OrderGroup::Bracket {
+                exits: BracketExits::Inactive(_),
+                ..
+            } => unreachable!("The entry order of a bracket is a limit order"),
+        }
+    }
+
+    /// Propagate the cancellation of the order with `order_id` to its linked orders.
+    fn propagate_cancel(&mut self, order_id: OrderId) {
+        let Some(group) = self.order_groups.take(order_id) else {
+            return;
+        };
+        trace!("propagate_cancel: order_id: {order_id}, group: {group:?}");
+        match group {
+            OrderGroup::Oco(legs) => {
+                self.cancel_linked_order(other_leg(legs, order_id), CancelReason::LinkedOrder)
+            }
+            OrderGroup::Bracket { entry, exits } if entry == order_id => {
+                // Already active exits keep protecting the filled part of the entry.
+                if let BracketExits::Active(legs) = exits {
+                    self.order_groups.push(OrderGroup::Oco(legs));
+                }
+            }
+            OrderGroup::Bracket {
+                entry,
+                exits: BracketExits::Active(legs),
+            } => {
+                self.cancel_linked_order(other_leg(legs, order_id), CancelReason::LinkedOrder);
+                self.cancel_linked_order(entry, CancelReason::LinkedOrder);
+            }
+            OrderGroup::Bracket {
+                exits: BracketExits::Inactive(_),
+                ..
+            } => unreachable!("Only the entry order of an inactive bracket can be cancelled"),
+        }
+    }
+
     /// Checks for the execution of active limit orders in the account.
     /// NOTE: only public for benchmarking purposes.
     pub fn check_active_orders<U>(&mut self, mut market_update: U)
@@ -461,10 +1942,12 @@ where
             return;
         }
 
+        // Each fill consumes the liquidity of `market_update`,
+        // so the combined fills of all bids and asks never exceed its volume.
+
         if market_update.can_fill_bids() {
             // peek at the best bid order.
             while let Some(order) = self.active_limit_orders().peek_best_bid() {
-                // TODO: if some quantity was filled, mutate `market_update` to reflect the reduced liquidity so it does not fill more orders than possible.
                 if let Some((filled_qty, exhausted)) = market_update.limit_order_filled(order) {
                     self.fill_limit_order(
                         order.clone(),
@@ -483,7 +1966,6 @@ where
 
         if market_update.can_fill_asks() {
             while let Some(order) = self.active_limit_orders().peek_best_ask() {
-                // TODO: if some quantity was filled, mutate `market_update` to reflect the reduced liquidity so it does not fill more orders than possible.
                 if let Some((filled_qty, exhausted)) = market_update.limit_order_filled(order) {
                     self.fill_limit_order(
                         order.clone(),
@@ -500,17 +1982,16 @@ where
             }
         }
 
-        assert2::debug_assert!(if self.active_limit_orders().is_empty() {
+        assert2::debug_assert!(if self.order_margin.is_empty() {
             self.balances.order_margin().is_zero()
         } else {
             true
         });
-        debug_assert_eq!(
-            self.balances.order_margin(),
-            self.order_margin.order_margin(
-                self.config.contract_spec().init_margin_req(),
-                &self.position
-            )
+        assert2::debug_assert!(
+            self.balances.order_margin()
+                <= self
+                    .order_margin
+                    .tiered_order_margin(self.config.contract_spec(), &self.position)
         );
         self.balances.debug_assert_state();
     }
@@ -532,63 +2013,136 @@ where
             filled_qty > BaseOrQuote::zero(),
             "The filled_qty must be greater than zero"
         );
-        debug_assert_eq!(
-            self.balances.order_margin(),
-            self.order_margin.order_margin(
-                self.config.contract_spec().init_margin_req(),
-                &self.position
-            )
+        assert2::debug_assert!(
+            self.balances.order_margin()
+                <= self
+                    .order_margin
+                    .tiered_order_margin(self.config.contract_spec(), &self.position)
         );
 
         let side = order.side();
         let limit_price = order.limit_price();
-        let notional = BaseOrQuote::PairedCurrency::convert_from(filled_qty, limit_price);
-        let fee = notional * *self.config.contract_spec().fee_maker().as_ref();
+        let fee = self.maker_fee(filled_qty, limit_price);
         self.balances.account_for_fee(fee);
 
+        let order_id = order.id();
         let limit_order_update = order.fill(filled_qty, fee, ts_ns);
-        let init_margin_req = self.config().contract_spec().init_margin_req();
-        if let LimitOrderFill::FullyFilled { .. } = limit_order_update {
+        let fully_filled = matches!(limit_order_update, LimitOrderFill::FullyFilled { .. });
+        if fully_filled {
             self.order_margin
                 .remove(
                     CancelBy::OrderId(order.id()),
                     &mut self.balances,
                     &self.position,
-                    init_margin_req,
+                    self.config.contract_spec(),
                 )
                 .expect("Can remove order as its an internal call");
         } else {
             assert2::debug_assert!(order.remaining_quantity() > BaseOrQuote::zero());
-            self.order_margin
-                .fill_order(order, &mut self.balances, &self.position, init_margin_req)
+            self.order_margin.fill_order(
+                order,
+                &mut self.balances,
+                &self.position,
+                self.config.contract_spec(),
+            )
         }
         self.limit_order_updates.push(limit_order_update);
 
-        self.position.change(
+        self.change_position(
+            order_id,
             filled_qty,
             limit_price,
             side,
+            fee,
+            Liquidity::Maker,
+        );
+        self.rebalance_order_margin();
+        self.propagate_fill(order_id, filled_qty, fully_filled);
+        self.enforce_reduce_only_orders();
+    }
+
+    /// Clamp resting reduce-only orders to the position quantity after it shrunk,
+    /// or cancel them once the position is closed or flipped, so they can never increase the position.
+    fn enforce_reduce_only_orders(&mut self) {
+        while let Some((order_id, quantity)) = self
+            .order_margin
+            .first_excessive_reduce_only_order(&self.position)
+        {
+            match quantity {
+                Some(quantity) => {
+                    debug!("clamping reduce-only order {order_id} to {quantity}");
+                    self.order_margin.clamp_reduce_only(order_id, quantity);
+                    self.order_events
+                        .push(OrderEvent::QuantityAdjusted { order_id, quantity });
+                }
+                None => {
+                    debug!("cancelling reduce-only order {order_id}");
+                    self.order_margin
+                        .remove(
+                            CancelBy::OrderId(order_id),
+                            &mut self.balances,
+                            &self.position,
+                            self.config.contract_spec(),
+                        )
+                        .expect("Can remove order as its an internal call");
+                    self.order_events.push(OrderEvent::Cancelled {
+                        order_id,
+                        reason: CancelReason::ReduceOnly,
+                    });
+                    self.propagate_cancel(order_id);
+                }
+            }
+        }
+    }
+
+    /// Change the position by a fill of order `order_id` for `filled_qty` at `fill_price` on `side`,
+    /// which adds to the `traded_volume` and is recorded along with its `fee` in the `AccountTracker` and `TradeLedger` if enabled.
+    fn change_position(
+        &mut self,
+        order_id: OrderId,
+        filled_qty: BaseOrQuote,
+        fill_price: QuoteCurrency<I, D>,
+        side: Side,
+        fee: BaseOrQuote::PairedCurrency,
+        liquidity: Liquidity,
+    ) {
+        let reduces_position = self.position.reducible_quantity(side).is_some();
+        let balance_before = self.balances.sum();
+        self.position.change(
+            filled_qty,
+            fill_price,
+            side,
             &mut self.balances,
-            self.config.contract_spec().init_margin_req(),
+            self.config.contract_spec(),
         );
+        // Moving margin between the balances leaves their sum unchanged, so the difference is the realized profit and loss.
+        let realized_pnl = reduces_position.then(|| self.balances.sum() - balance_before);
+        let notional = BaseOrQuote::PairedCurrency::convert_from(filled_qty, fill_price);
+        self.traded_volume
+            .record(self.market_state.current_ts_ns(), notional);
+        if let Some(account_tracker) = self.account_tracker.as_mut() {
+            account_tracker.record_fill(notional, fee, realized_pnl);
+        }
+        if let Some(trade_ledger) = self.trade_ledger.as_mut() {
+            trade_ledger.record_fill(Fill {
+                order_id,
+                side,
+                quantity: filled_qty,
+                price: fill_price,
+                fee,
+                liquidity,
+                realized_pnl: realized_pnl.unwrap_or_else(BaseOrQuote::PairedCurrency::zero),
+                timestamp_ns: self.market_state.current_ts_ns(),
+            });
+        }
+    }
 
-        let new_order_margin = self.order_margin.order_margin(
-            self.config.contract_spec().init_margin_req(),
+    /// The order margin depends on the position, so it must be updated after the position changes.
+    fn rebalance_order_margin(&mut self) {
+        self.order_margin.update_balances(
+            &mut self.balances,
             &self.position,
+            self.config.contract_spec(),
         );
-        match new_order_margin.cmp(&self.balances.order_margin()) {
-            Ordering::Less => {
-                let margin_delta = self.balances.order_margin() - new_order_margin;
-                assert2::debug_assert!(margin_delta > Zero::zero());
-                self.balances.free_order_margin(margin_delta);
-            }
-            Ordering::Equal => {}
-            Ordering::Greater => {
-                let margin_delta = new_order_margin - self.balances.order_margin();
-                assert2::debug_assert!(margin_delta > Zero::zero());
-                let success = self.balances.try_reserve_order_margin(margin_delta);
-                assert!(success, "Can reserve order margin");
-            }
-        }
     }
 }
@@ -5,6 +5,7 @@
 #[macro_use]
 extern crate serde;
 
+mod account_tracker;
 mod active_limit_orders;
 mod config;
 mod contract_specification;
@@ -14,6 +15,7 @@ mod market_state;
 mod market_update;
 mod mock_exchange;
 mod order_filters;
+mod order_groups;
 mod order_margin;
 mod order_rate_limiter;
 mod position;
@@ -23,6 +25,7 @@ mod risk_engine;
 mod tests;
 #[cfg(feature = "trade_aggregation")]
 mod trade_aggregation;
+mod trade_ledger;
 mod types;
 mod utils;
 
@@ -36,6 +39,7 @@ pub mod prelude {
     pub use num_traits::{One, Zero};
 
     pub use crate::{
+        account_tracker::AccountTracker,
         active_limit_orders::ActiveLimitOrders,
         config::Config,
         contract_specification::*,
@@ -47,6 +51,10 @@ pub mod prelude {
         order_margin::OrderMargin,
         position::Position,
         position_inner::PositionInner,
+        risk_engine::{
+            CrossMarginRiskEngine, IsolatedMarginRiskEngine, MarginModeRiskEngine, RiskEngine,
+        },
+        trade_ledger::{Fill, LedgerCapacity, Liquidity, RoundTrip, TradeLedger},
         types::*,
         utils::{NoUserOrderId, decimal_from_f64, scale},
     };
@@ -1,9 +1,15 @@
+use std::collections::VecDeque;
+
 use const_decimal::Decimal;
 use getset::{CopyGetters, Getters, Setters};
+use num_traits::{One, Zero};
 
 use crate::{
-    prelude::{Currency, MarketUpdate, Mon, PriceFilter, QuoteCurrency},
-    types::TimestampNs,
+    prelude::{
+        Currency, MarkMethod, MarketUpdate, Mon, OrderBookDepth, PriceFilter, QuoteCurrency,
+    },
+    types::{Side, TimestampNs},
+    utils::max,
 };
 
 /// Some information regarding the state of the market.
@@ -28,12 +34,32 @@ where
     last_trade_price: QuoteCurrency<I, D>,
 
     /// The current timestamp in nanoseconds
-    #[getset(get_copy = "pub")]
+    #[getset(get_copy = "pub", set = "pub(crate)")]
     current_ts_ns: TimestampNs,
 
     /// Used for synchronizing orders.
     #[getset(get_copy = "pub")]
     step: u64,
+
+    /// The scheduled funding settlements as `(settlement_ts_ns, funding_rate)`, ordered by their settlement timestamp.
+    scheduled_funding: VecDeque<(TimestampNs, Decimal<I, D>)>,
+
+    /// The method for computing `mark-to-market`, taken from the `ContractSpecification`.
+    #[getset(get_copy = "pub")]
+    mark_method: MarkMethod,
+
+    /// The latest index price of the underlying, as provided by `IndexPrice` updates,
+    /// which are usually composed from the spot prices of multiple venues.
+    #[getset(get_copy = "pub")]
+    index_price: QuoteCurrency<I, D>,
+
+    /// The exponential moving average of the basis between the mid price and the `index_price`,
+    /// which is sampled on every `IndexPrice` update.
+    #[getset(get_copy = "pub")]
+    basis_moving_average: QuoteCurrency<I, D>,
+
+    // Whether the basis has been sampled at least once.
+    basis_sampled: bool,
 }
 
 impl<I: Mon<D>, const D: u8> std::fmt::Display for MarketState<I, D> {
@@ -50,16 +76,26 @@ impl<I, const D: u8> MarketState<I, D>
 where
     I: Mon<D>,
 {
+    /// Create a new instance, which marks positions to market with the `mark_method`.
+    pub(crate) fn new(mark_method: MarkMethod) -> Self {
+        Self {
+            mark_method,
+            ..Default::default()
+        }
+    }
+
     /// Update the exchange state with new information
     ///
     /// ### Parameters:
     /// `market_update`: Newest market information
     /// `price_filter`: The pricing rules.
+    /// `depth`: The visible order book depth, which is updated if the `market_update` carries any.
     ///
     pub(crate) fn update_state<U, BaseOrQuote>(
         &mut self,
         market_update: &U,
         price_filter: &PriceFilter<I, D>,
+        depth: &mut OrderBookDepth<I, D, BaseOrQuote>,
     ) where
         U: MarketUpdate<I, D, BaseOrQuote>,
         BaseOrQuote: Currency<I, D>,
@@ -67,6 +103,13 @@ where
         // Only in debug mode do we care to validate the market update, because usually the update comes from an exchange source.
         debug_assert!(market_update.validate_market_update(price_filter).is_ok());
         market_update.update_market_state(self);
+        if market_update.update_depth(depth)
+            && let (Some(bid), Some(ask)) = (depth.best_bid(), depth.best_ask())
+        {
+            debug_assert!(bid < ask, "The order book depth must not be crossed");
+            self.bid = bid;
+            self.ask = ask;
+        }
 
         self.current_ts_ns = market_update.timestamp_exchange_ns();
         self.step += 1;
@@ -78,6 +121,137 @@ where
         (self.bid + self.ask) / Decimal::TWO
     }
 
+    /// Set the `index_price` and sample the basis between the mid price and it into the `basis_moving_average`.
+    /// The basis is only sampled once the best bid and ask are known.
+    pub(crate) fn set_index_price(&mut self, index_price: QuoteCurrency<I, D>) {
+        assert2::debug_assert!(index_price > QuoteCurrency::zero());
+        self.index_price = index_price;
+        if self.bid.is_zero() || self.ask.is_zero() {
+            return;
+        }
+
+        let basis = self.mid_price() - index_price;
+        if !self.basis_sampled {
+            self.basis_moving_average = basis;
+            self.basis_sampled = true;
+            return;
+        }
+        let smoothing = match self.mark_method {
+            MarkMethod::FairPrice { basis_periods } => {
+                let periods = Decimal::try_from_scaled(
+                    I::from(u64::from(basis_periods.get()) + 1)
+                        .expect("Can convert the number of basis periods"),
+                    0,
+                )
+                .expect("Can create `Decimal`");
+                Decimal::TWO / periods
+            }
+            MarkMethod::MidPrice | MarkMethod::BidAsk => Decimal::one(),
+        };
+        self.basis_moving_average += (basis - self.basis_moving_average) * smoothing;
+    }
+
+    /// The fair price, which is the `index_price` plus the `basis_moving_average`.
+    /// Before the basis has been sampled, it is the mid price.
+    pub fn fair_price(&self) -> QuoteCurrency<I, D> {
+        if !self.basis_sampled {
+            return self.mid_price();
+        }
+        self.index_price + self.basis_moving_average
+    }
+
+    /// The premium index of the contract over the `index_price`, as a fraction of it.
+    /// It is the amount by which the best bid exceeds the index price,
+    /// less the amount by which the best ask falls short of it,
+    /// so it is positive when the contract trades at a premium and negative at a discount.
+    /// Zero until the index price and the best bid and ask are known.
+    pub fn premium_index(&self) -> Decimal<I, D> {
+        if self.index_price.is_zero() || self.bid.is_zero() || self.ask.is_zero() {
+            return Decimal::zero();
+        }
+        let premium = max(self.bid - self.index_price, QuoteCurrency::zero());
+        let discount = max(self.index_price - self.ask, QuoteCurrency::zero());
+        *(premium - discount).as_ref() / *self.index_price.as_ref()
+    }
+
+    /// The mark price of the contract according to the `mark_method`,
+    /// which values funding payments and triggers `TriggerBy::MarkPrice` stop orders.
+    /// It is the fair price with `MarkMethod::FairPrice` and the mid price otherwise.
+    #[inline]
+    pub fn mark_price(&self) -> QuoteCurrency<I, D> {
+        match self.mark_method {
+            MarkMethod::FairPrice { .. } => self.fair_price(),
+            MarkMethod::MidPrice | MarkMethod::BidAsk => self.mid_price(),
+        }
+    }
+
+    /// The price at which a position, that an order on `side` would close, is marked to market according to the `mark_method`.
+    /// With `MarkMethod::BidAsk` a long position is marked at the bid and a short position at the ask,
+    /// otherwise both are marked at the `mark_price`.
+    #[inline]
+    pub fn mark_to_market_price(&self, side: Side) -> QuoteCurrency<I, D> {
+        match self.mark_method {
+            MarkMethod::BidAsk => match side {
+                Side::Buy => self.ask,
+                Side::Sell => self.bid,
+            },
+            MarkMethod::MidPrice | MarkMethod::FairPrice { .. } => self.mark_price(),
+        }
+    }
+
+    /// The funding rate that will be settled at `next_funding_ts_ns`, or zero if no settlement is scheduled.
+    #[inline]
+    pub fn funding_rate(&self) -> Decimal<I, D> {
+        self.scheduled_funding
+            .front()
+            .map_or_else(Decimal::zero, |(_, funding_rate)| *funding_rate)
+    }
+
+    /// The timestamp of the next funding settlement, if one is scheduled.
+    #[inline]
+    pub fn next_funding_ts_ns(&self) -> Option<TimestampNs> {
+        self.scheduled_funding
+            .front()
+            .map(|(settlement_ts_ns, _)| *settlement_ts_ns)
+    }
+
+    /// Schedule a funding settlement with `funding_rate` at `settlement_ts_ns`.
+    /// It supersedes the scheduled settlements at or after `settlement_ts_ns`, e.g. when the rate of the next interval is revised,
+    /// while earlier settlements stay scheduled until they are due, even if the announcement arrives after them.
+    #[inline]
+    pub(crate) fn set_funding(
+        &mut self,
+        funding_rate: Decimal<I, D>,
+        settlement_ts_ns: TimestampNs,
+    ) {
+        while self
+            .scheduled_funding
+            .back()
+            .is_some_and(|(ts_ns, _)| *ts_ns >= settlement_ts_ns)
+        {
+            self.scheduled_funding.pop_back();
+        }
+        self.scheduled_funding
+            .push_back((settlement_ts_ns, funding_rate));
+    }
+
+    /// Returns the funding rate of the earliest scheduled funding settlement if it is due at the current timestamp.
+    /// Each scheduled settlement is only returned once, so this is called until it returns `None`.
+    #[inline]
+    pub(crate) fn take_due_funding(&mut self) -> Option<Decimal<I, D>> {
+        if self
+            .scheduled_funding
+            .front()
+            .is_some_and(|(settlement_ts_ns, _)| *settlement_ts_ns <= self.current_ts_ns)
+        {
+            return self
+                .scheduled_funding
+                .pop_front()
+                .map(|(_, funding_rate)| funding_rate);
+        }
+        None
+    }
+
     /// Get the last observed timestamp in nanoseconts
     #[inline(always)]
     pub fn current_timestamp_ns(&self) -> TimestampNs {
@@ -99,6 +273,7 @@ where
             last_trade_price,
             current_ts_ns,
             step,
+            ..Default::default()
         }
     }
 }
@@ -128,7 +303,108 @@ mod test {
                 timestamp_exchange_ns: 1.into(),
             },
             &pf,
+            &mut OrderBookDepth::default(),
         );
         assert_eq!(state.mid_price(), QuoteCurrency::new(1005, 1));
     }
+
+    #[test]
+    fn market_state_take_due_funding() {
+        let mut state = MarketState::<i64, 5>::default();
+        let rate = Decimal::try_from_scaled(1, 4).unwrap();
+        state.set_funding(rate, 10.into());
+        assert_eq!(state.take_due_funding(), None);
+
+        state.current_ts_ns = 10.into();
+        assert_eq!(state.take_due_funding(), Some(rate));
+        assert_eq!(state.next_funding_ts_ns(), None);
+        assert_eq!(state.take_due_funding(), None);
+    }
+
+    #[test]
+    fn market_state_set_funding_keeps_due_settlement() {
+        let mut state = MarketState::<i64, 5>::default();
+        let rate_0 = Decimal::try_from_scaled(1, 4).unwrap();
+        let rate_1 = Decimal::try_from_scaled(2, 4).unwrap();
+        let rate_2 = Decimal::try_from_scaled(3, 4).unwrap();
+        state.set_funding(rate_0, 10.into());
+        // The next interval is announced after the prior settlement time, but before it was settled.
+        state.set_funding(rate_1, 20.into());
+        assert_eq!(state.funding_rate(), rate_0);
+        assert_eq!(state.next_funding_ts_ns(), Some(10.into()));
+        // A revised rate for the next interval supersedes the announced one.
+        state.set_funding(rate_2, 20.into());
+
+        state.current_ts_ns = 20.into();
+        assert_eq!(state.take_due_funding(), Some(rate_0));
+        assert_eq!(state.take_due_funding(), Some(rate_2));
+        assert_eq!(state.take_due_funding(), None);
+        assert_eq!(state.funding_rate(), Decimal::zero());
+    }
+
+    #[test]
+    fn market_state_mark_price() {
+        let bid = QuoteCurrency::<i64, 5>::new(100, 0);
+        let ask = QuoteCurrency::new(102, 0);
+        let mid = QuoteCurrency::new(101, 0);
+
+        let mut state = MarketState::new(MarkMethod::BidAsk);
+        state.set_bid(bid);
+        state.set_ask(ask);
+        assert_eq!(state.mark_price(), mid);
+        assert_eq!(state.mark_to_market_price(Side::Sell), bid);
+        assert_eq!(state.mark_to_market_price(Side::Buy), ask);
+
+        state.mark_method = MarkMethod::MidPrice;
+        assert_eq!(state.mark_to_market_price(Side::Sell), mid);
+        assert_eq!(state.mark_to_market_price(Side::Buy), mid);
+    }
+
+    #[test]
+    fn market_state_premium_index() {
+        let mut state = MarketState::<i64, 5>::default();
+        state.set_bid(QuoteCurrency::new(101, 0));
+        state.set_ask(QuoteCurrency::new(102, 0));
+        assert_eq!(state.premium_index(), Decimal::zero());
+
+        state.set_index_price(QuoteCurrency::new(100, 0));
+        assert_eq!(
+            state.premium_index(),
+            Decimal::try_from_scaled(1, 2).unwrap()
+        );
+        state.set_index_price(QuoteCurrency::new(104, 0));
+        assert_eq!(
+            state.premium_index(),
+            Decimal::try_from_scaled(-2, 0).unwrap() / Decimal::try_from_scaled(104, 0).unwrap()
+        );
+        // Within the spread there is neither a premium nor a discount.
+        state.set_index_price(QuoteCurrency::new(1015, 1));
+        assert_eq!(state.premium_index(), Decimal::zero());
+    }
+
+    #[test]
+    fn market_state_fair_price() {
+        let mut state = MarketState::<i64, 5>::new(MarkMethod::FairPrice {
+            basis_periods: std::num::NonZeroU32::new(3).unwrap(),
+        });
+        state.set_bid(QuoteCurrency::new(100, 0));
+        state.set_ask(QuoteCurrency::new(102, 0));
+        // Falls back to the mid price without an index price.
+        assert_eq!(state.mark_price(), QuoteCurrency::new(101, 0));
+
+        state.set_index_price(QuoteCurrency::new(99, 0));
+        assert_eq!(state.basis_moving_average(), QuoteCurrency::new(2, 0));
+        assert_eq!(state.mark_price(), QuoteCurrency::new(101, 0));
+
+        // A wick of the bid and ask only moves the basis by half, as it spans 3 periods.
+        state.set_bid(QuoteCurrency::new(90, 0));
+        state.set_ask(QuoteCurrency::new(92, 0));
+        state.set_index_price(QuoteCurrency::new(99, 0));
+        assert_eq!(state.basis_moving_average(), QuoteCurrency::new(-3, 0));
+        assert_eq!(state.mark_price(), QuoteCurrency::new(96, 0));
+        assert_eq!(
+            state.mark_to_market_price(Side::Sell),
+            QuoteCurrency::new(96, 0)
+        );
+    }
 }
@@ -0,0 +1,121 @@
+use super::MarketUpdate;
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    order_filters::enforce_min_price,
+    prelude::{Currency, LimitOrder, MarketState, Mon, Pending, PriceFilter, QuoteCurrency, Side},
+    types::{TimestampNs, UserOrderId},
+};
+
+/// The liquidation of a bankrupt counterparty, which could not be filled above its bankruptcy price
+/// and whose loss the insurance fund could not absorb.
+/// The remaining quantity is matched against profitable positions on the opposite side,
+/// which the `AdlModel` of the `Config` deleverages at the `bankruptcy_price`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AutoDeleverage<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The side of the liquidation order of the bankrupt counterparty.
+    /// A `Side::Sell` liquidates a bankrupt long, which deleverages profitable shorts and vice versa.
+    pub side: Side,
+    /// The quantity which could not be liquidated in the market.
+    pub quantity: BaseOrQuote,
+    /// The bankruptcy price of the counterparty, at which the deleveraged positions are closed.
+    pub bankruptcy_price: QuoteCurrency<I, D>,
+    /// The nanosecond timestamp at which this event occurred at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+impl<I, const D: u8, BaseOrQuote> std::fmt::Display for AutoDeleverage<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "auto-deleverage: {} {} @ {}, ts: {}",
+            self.side, self.quantity, self.bankruptcy_price, self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote>
+    for AutoDeleverage<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = false;
+
+    #[inline(always)]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        _limit_order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        unreachable!(
+            "This should never be called, because an auto-deleverage update can never fill a limit order."
+        );
+    }
+
+    #[inline(always)]
+    fn validate_market_update(&self, _price_filter: &PriceFilter<I, D>) -> Result<()> {
+        debug_assert!(self.quantity > BaseOrQuote::zero());
+        // The bankruptcy price does not need to conform to the `PriceFilter`.
+        enforce_min_price(None, self.bankruptcy_price)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn update_market_state(&self, _market_state: &mut MarketState<I, D>) {
+        // The bankruptcy price of a counterparty does not move the market.
+    }
+
+    #[inline(always)]
+    fn auto_deleverage(&self) -> Option<AutoDeleverage<I, D, BaseOrQuote>> {
+        Some(*self)
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    #[test]
+    fn auto_deleverage_update() {
+        let update = AutoDeleverage {
+            side: Side::Sell,
+            quantity: BaseCurrency::<i64, 5>::new(5, 0),
+            bankruptcy_price: QuoteCurrency::new(80, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        assert!(
+            update
+                .validate_market_update(&PriceFilter::default())
+                .is_ok()
+        );
+        assert_eq!(update.auto_deleverage(), Some(update));
+        assert_eq!(
+            &update.to_string(),
+            "auto-deleverage: Sell 5.00000 Base @ 80.00000 Quote, ts: 1"
+        );
+    }
+}
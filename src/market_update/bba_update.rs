@@ -12,8 +12,7 @@ use crate::{
 };
 
 /// An update to the best bid and ask has occured.
-/// For now we don't handle the quantity a these price levels.
-/// This will change in future versions.
+/// It does not carry the quantity at these price levels, use `SizedBba` for that.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Bba<I, const D: u8>
 where
@@ -15,6 +15,10 @@ use crate::{
 /// A new candle has been created.
 /// Here we can use the `high` and `low` prices to see if our simulated resting orders
 /// have been executed over the last period as a proxy in absence of actual `Trade` flow.
+/// As a candle carries no volume, its liquidity is limited to a single price level per side:
+/// only the resting orders at the best price level its `low` or `high` trades through are filled (entirely),
+/// the deeper levels remain untouched until a later update.
+/// Use `SmartCandle` or `Trade` updates for volume limited fills.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TypedBuilder, CopyGetters)]
 pub struct Candle<I, const D: u8>
 where
@@ -39,6 +43,14 @@ where
     /// The nanosecond timestamp at which this event occurred at the exchange.
     #[getset(get_copy = "pub")]
     timestamp_exchange_ns: TimestampNs,
+
+    /// The price level of the bids filled by this candle, if any.
+    #[builder(default, setter(skip))]
+    filled_bid_level: Option<QuoteCurrency<I, D>>,
+
+    /// The price level of the asks filled by this candle, if any.
+    #[builder(default, setter(skip))]
+    filled_ask_level: Option<QuoteCurrency<I, D>>,
 }
 
 impl<I, const D: u8> Candle<I, D>
@@ -75,6 +87,8 @@ where
             low,
             high,
             timestamp_exchange_ns,
+            filled_bid_level: None,
+            filled_ask_level: None,
         })
     }
 
@@ -114,8 +128,8 @@ where
 {
     const CAN_FILL_LIMIT_ORDERS: bool = true;
 
-    // In this simplified model, Candles have infinite volume to fill orders.
-    // Candles are not realistic when it comes to fills.
+    // In this simplified model, Candles have infinite volume at the first price level they fill on each side,
+    // but no liquidity beyond it. Candles are not realistic when it comes to fills.
     #[inline]
     fn limit_order_filled<UserOrderIdT: UserOrderId>(
         &mut self,
@@ -123,18 +137,21 @@ where
     ) -> Option<(BaseOrQuote, Exhausted)> {
         debug_assert!(order.remaining_quantity() > BaseOrQuote::zero());
 
-        // As a simplifying assumption, the order always get executed fully when using candles if the price is right.
-        if self.fills_limit_order(order) {
-            // Order is executed fully with candles.
-            Some((
-                match order.side() {
-                    Side::Buy => order.remaining_quantity(),
-                    Side::Sell => order.remaining_quantity(),
-                },
-                false,
-            ))
-        } else {
-            None
+        if !self.fills_limit_order(order) {
+            return None;
+        }
+        // Orders are offered best price first, so the first filled level is the best one traded through.
+        let filled_level = match order.side() {
+            Side::Buy => &mut self.filled_bid_level,
+            Side::Sell => &mut self.filled_ask_level,
+        };
+        match filled_level {
+            Some(level) if *level != order.limit_price() => None,
+            _ => {
+                *filled_level = Some(order.limit_price());
+                // As a simplifying assumption, the orders at the filled level always get executed fully.
+                Some((order.remaining_quantity(), false))
+            }
         }
     }
 
@@ -162,6 +179,11 @@ where
         market_state.set_ask(self.ask);
     }
 
+    #[inline(always)]
+    fn price_range(&self) -> Option<(QuoteCurrency<I, D>, QuoteCurrency<I, D>)> {
+        Some((self.low, self.high))
+    }
+
     #[inline(always)]
     fn timestamp_exchange_ns(&self) -> TimestampNs {
         self.timestamp_exchange_ns
@@ -200,13 +222,14 @@ mod test {
         96, 99, 100, 1000
     ])]
     fn candle_update_fills_buy_limit_order(limit_price: i64) {
-        let candle = Candle {
-            bid: QuoteCurrency::<i64, 5>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
-        };
+        let candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         let new_order = LimitOrder::new(
             Side::Buy,
             QuoteCurrency::new(limit_price, 0),
@@ -222,13 +245,14 @@ mod test {
         1, 10, 50, 90, 95
     ])]
     fn candle_update_fills_buy_limit_order_not(limit_price: i64) {
-        let candle = Candle {
-            bid: QuoteCurrency::<i64, 5>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
-        };
+        let candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         let new_order = LimitOrder::new(
             Side::Buy,
             QuoteCurrency::new(limit_price, 0),
@@ -244,13 +268,14 @@ mod test {
         1, 10, 94, 95, 96, 104
     ])]
     fn candle_update_fills_sell_limit_order(limit_price: i64) {
-        let candle = Candle {
-            bid: QuoteCurrency::<i64, 5>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
-        };
+        let candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         let new_order = LimitOrder::new(
             Side::Sell,
             QuoteCurrency::new(limit_price, 0),
@@ -266,13 +291,14 @@ mod test {
         105, 110, 1000
     ])]
     fn candle_update_fills_sell_limit_order_not(limit_price: i64) {
-        let candle = Candle {
-            bid: QuoteCurrency::<i64, 5>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
-        };
+        let candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         let new_order = LimitOrder::new(
             Side::Sell,
             QuoteCurrency::new(limit_price, 0),
@@ -286,13 +312,14 @@ mod test {
 
     #[test]
     fn candle_update() {
-        let mut candle = Candle {
-            bid: QuoteCurrency::<i64, 5>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
-        };
+        let mut candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         let new_order = LimitOrder::new(
             Side::Buy,
             QuoteCurrency::new(94, 0),
@@ -326,14 +353,42 @@ mod test {
     }
 
     #[test]
-    fn candle_update_display() {
-        let candle = Candle {
-            bid: QuoteCurrency::<i64, 1>::new(100, 0),
-            ask: QuoteCurrency::new(101, 0),
-            low: QuoteCurrency::new(95, 0),
-            high: QuoteCurrency::new(105, 0),
-            timestamp_exchange_ns: 1.into(),
+    fn candle_update_fills_single_price_level() {
+        let mut candle = Candle::new(
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
+        let order = |side, limit_price| {
+            LimitOrder::new(
+                side,
+                QuoteCurrency::new(limit_price, 0),
+                BaseCurrency::<i64, 5>::new(5, 0),
+            )
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(0.into(), 1.into()))
         };
+        let filled = Some((BaseCurrency::new(5, 0), false));
+        assert_eq!(candle.limit_order_filled(&order(Side::Buy, 99)), filled);
+        assert_eq!(candle.limit_order_filled(&order(Side::Buy, 99)), filled);
+        assert_eq!(candle.limit_order_filled(&order(Side::Buy, 98)), None);
+        assert_eq!(candle.limit_order_filled(&order(Side::Sell, 102)), filled);
+        assert_eq!(candle.limit_order_filled(&order(Side::Sell, 103)), None);
+    }
+
+    #[test]
+    fn candle_update_display() {
+        let candle = Candle::new(
+            QuoteCurrency::<i64, 1>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            QuoteCurrency::new(95, 0),
+            QuoteCurrency::new(105, 0),
+            1.into(),
+        )
+        .unwrap();
         assert_eq!(
             &candle.to_string(),
             "bid: 100.0 Quote, ask: 101.0 Quote, high: 105.0 Quote, low: 95.0 Quote",
@@ -0,0 +1,116 @@
+use const_decimal::Decimal;
+
+use super::MarketUpdate;
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    prelude::{Currency, LimitOrder, MarketState, Mon, Pending, PriceFilter},
+    types::{TimestampNs, UserOrderId},
+};
+
+/// A funding rate announcement of a perpetual futures contract.
+/// The `rate` is settled against the open position once `settlement_ts_ns` is reached.
+/// A positive rate means longs pay shorts, a negative rate means shorts pay longs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Funding<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// The funding rate applied to the position notional at the mark price.
+    pub rate: Decimal<I, D>,
+    /// The nanosecond timestamp at which the funding rate will be settled.
+    pub settlement_ts_ns: TimestampNs,
+    /// The nanosecond timestamp at which this event occurred at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+impl<I, const D: u8> std::fmt::Display for Funding<I, D>
+where
+    I: Mon<D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "funding rate: {}, settlement_ts: {}, ts: {}",
+            self.rate, self.settlement_ts_ns, self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote> for Funding<I, D>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = false;
+
+    #[inline(always)]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        _limit_order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        unreachable!(
+            "This should never be called, because a funding update can never fill a limit order."
+        );
+    }
+
+    #[inline(always)]
+    fn validate_market_update(&self, _price_filter: &PriceFilter<I, D>) -> Result<()> {
+        // Funding updates don't carry any prices.
+        Ok(())
+    }
+
+    #[inline]
+    fn update_market_state(&self, market_state: &mut MarketState<I, D>) {
+        market_state.set_funding(self.rate, self.settlement_ts_ns);
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    #[test]
+    fn funding_update_market_state() {
+        let funding = Funding {
+            rate: Decimal::<i64, 5>::try_from_scaled(1, 4).unwrap(),
+            settlement_ts_ns: 10.into(),
+            timestamp_exchange_ns: 1.into(),
+        };
+        let mut state = MarketState::default();
+        <Funding<i64, 5> as MarketUpdate<i64, 5, BaseCurrency<i64, 5>>>::update_market_state(
+            &funding, &mut state,
+        );
+        assert_eq!(state.funding_rate(), funding.rate);
+        assert_eq!(state.next_funding_ts_ns(), Some(10.into()));
+    }
+
+    #[test]
+    fn funding_update_display() {
+        let funding = Funding {
+            rate: Decimal::<i64, 5>::try_from_scaled(1, 4).unwrap(),
+            settlement_ts_ns: 10.into(),
+            timestamp_exchange_ns: 1.into(),
+        };
+        assert_eq!(
+            &funding.to_string(),
+            "funding rate: 0.00010, settlement_ts: 10, ts: 1"
+        );
+    }
+}
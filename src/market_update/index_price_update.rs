@@ -0,0 +1,185 @@
+use const_decimal::Decimal;
+use num_traits::Zero;
+
+use super::MarketUpdate;
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    order_filters::enforce_min_price,
+    prelude::{Currency, LimitOrder, MarketState, Mon, Pending, PriceFilter, QuoteCurrency},
+    types::{Error, TimestampNs, UserOrderId},
+};
+
+/// The spot price of the underlying at one venue, which makes up a part of the `IndexPrice`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IndexConstituent<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// The spot price at the venue.
+    pub price: QuoteCurrency<I, D>,
+    /// The weight of the venue in the index, relative to the other constituents.
+    pub weight: Decimal<I, D>,
+}
+
+/// An update to the index price of the underlying, which backs the fair price of `MarkMethod::FairPrice`.
+/// The index price does not need to conform to the `PriceFilter` of the contract.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IndexPrice<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// The new index price.
+    pub price: QuoteCurrency<I, D>,
+    /// The nanosecond timestamp at which this event occurred at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+impl<I, const D: u8> IndexPrice<I, D>
+where
+    I: Mon<D>,
+{
+    /// Compose the index price from the spot prices of its `constituents`, weighted relative to each other.
+    ///
+    /// # Returns:
+    /// Either the `IndexPrice` or an Error if there are no constituents,
+    /// or any of them has a non-positive price or weight.
+    pub fn from_constituents(
+        constituents: &[IndexConstituent<I, D>],
+        timestamp_exchange_ns: TimestampNs,
+    ) -> Result<Self> {
+        if constituents.is_empty()
+            || constituents.iter().any(|constituent| {
+                constituent.price <= QuoteCurrency::zero() || constituent.weight <= Decimal::zero()
+            })
+        {
+            return Err(Error::InvalidIndexConstituents);
+        }
+
+        let total_weight = constituents
+            .iter()
+            .fold(Decimal::zero(), |acc, constituent| acc + constituent.weight);
+        let weighted_price: QuoteCurrency<I, D> = constituents
+            .iter()
+            .map(|constituent| constituent.price * constituent.weight)
+            .sum();
+
+        Ok(Self {
+            price: weighted_price / total_weight,
+            timestamp_exchange_ns,
+        })
+    }
+}
+
+impl<I, const D: u8> std::fmt::Display for IndexPrice<I, D>
+where
+    I: Mon<D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "index price: {}, ts: {}",
+            self.price, self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote> for IndexPrice<I, D>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = false;
+
+    #[inline(always)]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        _limit_order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        unreachable!(
+            "This should never be called, because an index price update can never fill a limit order."
+        );
+    }
+
+    #[inline(always)]
+    fn validate_market_update(&self, _price_filter: &PriceFilter<I, D>) -> Result<()> {
+        enforce_min_price(None, self.price)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn update_market_state(&self, market_state: &mut MarketState<I, D>) {
+        market_state.set_index_price(self.price);
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    #[test]
+    fn index_price_update_market_state() {
+        let update = IndexPrice {
+            price: QuoteCurrency::<i64, 5>::new(100, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        let mut state = MarketState::default();
+        <IndexPrice<i64, 5> as MarketUpdate<i64, 5, BaseCurrency<i64, 5>>>::update_market_state(
+            &update, &mut state,
+        );
+        assert_eq!(state.index_price(), QuoteCurrency::new(100, 0));
+    }
+
+    #[test]
+    fn index_price_from_constituents() {
+        let constituent = |price, weight| IndexConstituent {
+            price: QuoteCurrency::<i64, 5>::new(price, 0),
+            weight: Decimal::try_from_scaled(weight, 1).unwrap(),
+        };
+        let index = IndexPrice::from_constituents(
+            &[
+                constituent(100, 5),
+                constituent(104, 3),
+                constituent(110, 2),
+            ],
+            1.into(),
+        )
+        .unwrap();
+        assert_eq!(index.price, QuoteCurrency::new(1032, 1));
+        assert_eq!(index.timestamp_exchange_ns, 1.into());
+
+        assert_eq!(
+            IndexPrice::<i64, 5>::from_constituents(&[], 1.into()),
+            Err(Error::InvalidIndexConstituents)
+        );
+        assert_eq!(
+            IndexPrice::from_constituents(&[constituent(100, 5), constituent(104, 0)], 1.into()),
+            Err(Error::InvalidIndexConstituents)
+        );
+    }
+
+    #[test]
+    fn index_price_update_display() {
+        let update = IndexPrice {
+            price: QuoteCurrency::<i64, 5>::new(100, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        assert_eq!(&update.to_string(), "index price: 100.00000 Quote, ts: 1");
+    }
+}
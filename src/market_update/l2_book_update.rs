@@ -0,0 +1,214 @@
+use num_traits::Zero;
+
+use super::MarketUpdate;
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    order_filters::{
+        enforce_bid_ask_spread, enforce_max_price, enforce_min_price, enforce_step_size,
+    },
+    prelude::{
+        Currency, LimitOrder, MarketState, Mon, OrderBookDepth, Pending, PriceFilter, PriceLevel,
+    },
+    types::{TimestampNs, UserOrderId},
+};
+
+/// A full snapshot of the visible order book depth, which replaces the depth known to the exchange.
+/// Market orders walk the levels and resting limit orders are filled by the liquidity priced through them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct L2Book<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The price levels of both sides of the book.
+    pub depth: OrderBookDepth<I, D, BaseOrQuote>,
+    /// The nanosecond timestamp at which this snapshot was taken at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+/// Checks that every price level satisfies the `PriceFilter`.
+pub(super) fn validate_price_levels<I, const D: u8, BaseOrQuote>(
+    levels: &[PriceLevel<I, D, BaseOrQuote>],
+    price_filter: &PriceFilter<I, D>,
+) -> Result<()>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    for level in levels {
+        enforce_min_price(price_filter.min_price(), level.price)?;
+        enforce_max_price(price_filter.max_price(), level.price)?;
+        enforce_step_size(price_filter.tick_size(), level.price)?;
+    }
+    Ok(())
+}
+
+impl<I, const D: u8, BaseOrQuote> std::fmt::Display for L2Book<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bid levels: {}, ask levels: {}, ts: {}",
+            self.depth.bids().len(),
+            self.depth.asks().len(),
+            self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote> for L2Book<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = true;
+
+    #[inline]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        let filled_qty = self.depth.take_liquidity(
+            order.side(),
+            order.limit_price(),
+            order.remaining_quantity(),
+        );
+        if filled_qty <= BaseOrQuote::zero() {
+            return None;
+        }
+        let exhausted = self.depth.bids().is_empty() && self.depth.asks().is_empty();
+        Some((filled_qty, exhausted))
+    }
+
+    fn validate_market_update(&self, price_filter: &PriceFilter<I, D>) -> Result<()> {
+        validate_price_levels(self.depth.bids(), price_filter)?;
+        validate_price_levels(self.depth.asks(), price_filter)?;
+        if let (Some(bid), Some(ask)) = (self.depth.best_bid(), self.depth.best_ask()) {
+            enforce_bid_ask_spread(bid, ask)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn update_market_state(&self, market_state: &mut MarketState<I, D>) {
+        if let (Some(bid), Some(ask)) = (self.depth.best_bid(), self.depth.best_ask()) {
+            market_state.set_bid(bid);
+            market_state.set_ask(ask);
+        }
+    }
+
+    #[inline]
+    fn update_depth(&self, depth: &mut OrderBookDepth<I, D, BaseOrQuote>) -> bool {
+        depth.clone_from(&self.depth);
+        true
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        !self.depth.asks().is_empty()
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        !self.depth.bids().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+        PriceLevel {
+            price: QuoteCurrency::new(price, 0),
+            quantity: BaseCurrency::new(quantity, 0),
+        }
+    }
+
+    fn l2_book() -> L2Book<i64, 5, BaseCurrency<i64, 5>> {
+        L2Book {
+            depth: OrderBookDepth::new(vec![level(100, 2), level(99, 3)], vec![
+                level(101, 2),
+                level(102, 3),
+            ]),
+            timestamp_exchange_ns: 1.into(),
+        }
+    }
+
+    #[test]
+    fn l2_book_update_market_state() {
+        let book = l2_book();
+        let mut state = MarketState::default();
+        let mut depth = OrderBookDepth::default();
+        state.update_state(&book, &PriceFilter::default(), &mut depth);
+        assert_eq!(state.bid(), QuoteCurrency::new(100, 0));
+        assert_eq!(state.ask(), QuoteCurrency::new(101, 0));
+        assert_eq!(state.current_ts_ns(), 1.into());
+        assert_eq!(depth, book.depth);
+    }
+
+    #[test]
+    fn l2_book_validate_market_update() {
+        let price_filter = PriceFilter::default();
+        l2_book().validate_market_update(&price_filter).unwrap();
+
+        let mut book = l2_book();
+        book.depth = OrderBookDepth::new(vec![level(100, 2)], vec![PriceLevel {
+            price: QuoteCurrency::new(1015, 1),
+            quantity: BaseCurrency::new(1, 0),
+        }]);
+        assert!(book.validate_market_update(&price_filter).is_err());
+
+        book.depth = OrderBookDepth::new(vec![level(101, 2)], vec![level(101, 1)]);
+        assert!(book.validate_market_update(&price_filter).is_err());
+    }
+
+    #[test]
+    fn l2_book_limit_order_filled() {
+        let mut book = l2_book();
+        assert!(book.can_fill_bids());
+        assert!(book.can_fill_asks());
+
+        let order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(102, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        // Only the liquidity priced strictly below the limit price fills the order.
+        assert_eq!(
+            book.limit_order_filled(&order),
+            Some((BaseCurrency::new(2, 0), false))
+        );
+        assert_eq!(book.limit_order_filled(&order), None);
+        assert!(book.can_fill_bids());
+
+        let order = LimitOrder::new(
+            Side::Sell,
+            QuoteCurrency::new(100, 0),
+            BaseCurrency::new(1, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(1.into(), 0.into()));
+        assert_eq!(book.limit_order_filled(&order), None);
+    }
+
+    #[test]
+    fn l2_book_update_display() {
+        assert_eq!(
+            &l2_book().to_string(),
+            "bid levels: 2, ask levels: 2, ts: 1"
+        );
+    }
+}
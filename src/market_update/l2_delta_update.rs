@@ -0,0 +1,191 @@
+use num_traits::Zero;
+
+use super::{MarketUpdate, l2_book_update::validate_price_levels};
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    prelude::{
+        Currency, LimitOrder, MarketState, Mon, OrderBookDepth, Pending, PriceFilter, PriceLevel,
+        Side,
+    },
+    types::{TimestampNs, UserOrderId, take_liquidity},
+};
+
+/// An incremental update to the visible order book depth, applied on top of the latest `L2Book` snapshot.
+/// Each level carries the new total quantity resting at its price, where a zero quantity removes the level.
+/// Levels priced through a resting limit order are treated as liquidity crossing it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct L2Delta<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The changed bid levels, in any order.
+    pub bids: Vec<PriceLevel<I, D, BaseOrQuote>>,
+    /// The changed ask levels, in any order.
+    pub asks: Vec<PriceLevel<I, D, BaseOrQuote>>,
+    /// The nanosecond timestamp at which this event occurred at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+impl<I, const D: u8, BaseOrQuote> std::fmt::Display for L2Delta<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bid updates: {}, ask updates: {}, ts: {}",
+            self.bids.len(),
+            self.asks.len(),
+            self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote> for L2Delta<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = true;
+
+    #[inline]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        let levels = match order.side() {
+            Side::Buy => &mut self.asks,
+            Side::Sell => &mut self.bids,
+        };
+        let filled_qty = take_liquidity(
+            levels,
+            order.side(),
+            order.limit_price(),
+            order.remaining_quantity(),
+        );
+        if filled_qty <= BaseOrQuote::zero() {
+            return None;
+        }
+        let exhausted = self
+            .bids
+            .iter()
+            .chain(self.asks.iter())
+            .all(|level| level.quantity.is_zero());
+        Some((filled_qty, exhausted))
+    }
+
+    fn validate_market_update(&self, price_filter: &PriceFilter<I, D>) -> Result<()> {
+        validate_price_levels(&self.bids, price_filter)?;
+        validate_price_levels(&self.asks, price_filter)
+    }
+
+    #[inline(always)]
+    fn update_market_state(&self, _market_state: &mut MarketState<I, D>) {
+        // The best bid and ask follow from the depth once the delta is applied.
+    }
+
+    #[inline]
+    fn update_depth(&self, depth: &mut OrderBookDepth<I, D, BaseOrQuote>) -> bool {
+        for level in self.bids.iter() {
+            depth.update_level(Side::Buy, *level);
+        }
+        for level in self.asks.iter() {
+            depth.update_level(Side::Sell, *level);
+        }
+        true
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        self.asks
+            .iter()
+            .any(|level| level.quantity > BaseOrQuote::zero())
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        self.bids
+            .iter()
+            .any(|level| level.quantity > BaseOrQuote::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+        PriceLevel {
+            price: QuoteCurrency::new(price, 0),
+            quantity: BaseCurrency::new(quantity, 0),
+        }
+    }
+
+    #[test]
+    fn l2_delta_update_market_state() {
+        let mut depth = OrderBookDepth::new(vec![level(100, 2), level(99, 3)], vec![
+            level(101, 2),
+            level(102, 3),
+        ]);
+        let delta = L2Delta {
+            bids: vec![level(100, 0), level(98, 1)],
+            asks: vec![level(101, 1)],
+            timestamp_exchange_ns: 2.into(),
+        };
+        let mut state = MarketState::default();
+        state.update_state(&delta, &PriceFilter::default(), &mut depth);
+        assert_eq!(
+            depth,
+            OrderBookDepth::new(vec![level(99, 3), level(98, 1)], vec![
+                level(101, 1),
+                level(102, 3)
+            ])
+        );
+        assert_eq!(state.bid(), QuoteCurrency::new(99, 0));
+        assert_eq!(state.ask(), QuoteCurrency::new(101, 0));
+        assert_eq!(state.current_ts_ns(), 2.into());
+    }
+
+    #[test]
+    fn l2_delta_limit_order_filled() {
+        let mut delta = L2Delta {
+            bids: vec![level(99, 0)],
+            asks: vec![level(98, 1), level(100, 2), level(97, 1)],
+            timestamp_exchange_ns: 2.into(),
+        };
+        assert!(delta.can_fill_bids());
+        assert!(!delta.can_fill_asks());
+
+        let order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(99, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        assert_eq!(
+            delta.limit_order_filled(&order),
+            Some((BaseCurrency::new(2, 0), false))
+        );
+        assert_eq!(delta.limit_order_filled(&order), None);
+    }
+
+    #[test]
+    fn l2_delta_update_display() {
+        let delta = L2Delta::<i64, 5, BaseCurrency<i64, 5>> {
+            bids: vec![level(99, 0)],
+            asks: Vec::new(),
+            timestamp_exchange_ns: 2.into(),
+        };
+        assert_eq!(&delta.to_string(), "bid updates: 1, ask updates: 0, ts: 2");
+    }
+}
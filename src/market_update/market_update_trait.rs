@@ -1,6 +1,12 @@
+use num_traits::Zero;
+
+use super::AutoDeleverage;
 use crate::{
     Result,
-    prelude::{Currency, LimitOrder, MarketState, Mon, Pending, PriceFilter},
+    prelude::{
+        Currency, LimitOrder, MarketState, Mon, OrderBookDepth, Pending, PriceFilter,
+        QuoteCurrency, Side,
+    },
     types::{TimestampNs, UserOrderId},
 };
 
@@ -36,6 +42,34 @@ where
     /// Update the `MarketState` with new information.
     fn update_market_state(&self, market_state: &mut MarketState<I, D>);
 
+    /// Update the visible order book depth, if the market update carries any.
+    /// Returns `true` if it did, in which case the best bid and ask are taken from the updated depth.
+    #[inline(always)]
+    fn update_depth(&self, _depth: &mut OrderBookDepth<I, D, BaseOrQuote>) -> bool {
+        false
+    }
+
+    /// The quantity traded at exactly `price` against resting orders on `side`,
+    /// which consumes the queue ahead of a resting limit order at that price.
+    #[inline(always)]
+    fn traded_quantity(&self, _side: Side, _price: QuoteCurrency<I, D>) -> BaseOrQuote {
+        BaseOrQuote::zero()
+    }
+
+    /// The liquidation of a bankrupt counterparty, if the market update carries one,
+    /// which may auto-deleverage the position according to the `AdlModel` of the `Config`.
+    #[inline(always)]
+    fn auto_deleverage(&self) -> Option<AutoDeleverage<I, D, BaseOrQuote>> {
+        None
+    }
+
+    /// The lowest and highest traded price covered by the market update, if it carries any.
+    /// Used for tracking trailing stop orders in between consecutive market states.
+    #[inline(always)]
+    fn price_range(&self) -> Option<(QuoteCurrency<I, D>, QuoteCurrency<I, D>)> {
+        None
+    }
+
     /// The nanosecond timestamp when the market update occurred at the exchange.
     fn timestamp_exchange_ns(&self) -> TimestampNs;
 }
@@ -1,11 +1,23 @@
+mod auto_deleverage_update;
 mod bba_update;
 mod candle_update;
+mod funding_update;
+mod index_price_update;
+mod l2_book_update;
+mod l2_delta_update;
 mod market_update_trait;
+mod sized_bba_update;
 mod smart_candle;
 mod trade_update;
 
+pub use auto_deleverage_update::AutoDeleverage;
 pub use bba_update::Bba;
 pub use candle_update::Candle;
+pub use funding_update::Funding;
+pub use index_price_update::{IndexConstituent, IndexPrice};
+pub use l2_book_update::L2Book;
+pub use l2_delta_update::L2Delta;
 pub use market_update_trait::MarketUpdate;
+pub use sized_bba_update::SizedBba;
 pub use smart_candle::SmartCandle;
 pub use trade_update::Trade;
@@ -0,0 +1,160 @@
+use super::MarketUpdate;
+use crate::{
+    Result,
+    market_update::market_update_trait::Exhausted,
+    order_filters::{
+        enforce_bid_ask_spread, enforce_max_price, enforce_min_price, enforce_step_size,
+    },
+    prelude::{
+        Currency, LimitOrder, MarketState, Mon, OrderBookDepth, Pending, PriceFilter, PriceLevel,
+        QuoteCurrency, Side,
+    },
+    types::{TimestampNs, UserOrderId},
+};
+
+/// An update to the best bid and ask along with the quantities resting at them.
+/// It replaces the best levels of the visible order book depth, while keeping the levels behind them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SizedBba<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The new best bid
+    pub bid: QuoteCurrency<I, D>,
+    /// The quantity resting at the best bid.
+    pub bid_quantity: BaseOrQuote,
+    /// The new best ask
+    pub ask: QuoteCurrency<I, D>,
+    /// The quantity resting at the best ask.
+    pub ask_quantity: BaseOrQuote,
+    /// The nanosecond timestamp at which this event occurred at the exchange.
+    pub timestamp_exchange_ns: TimestampNs,
+}
+
+impl<I, const D: u8, BaseOrQuote> std::fmt::Display for SizedBba<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bid: {} @ {}, ask: {} @ {}, ts: {}",
+            self.bid_quantity, self.bid, self.ask_quantity, self.ask, self.timestamp_exchange_ns
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> MarketUpdate<I, D, BaseOrQuote> for SizedBba<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    const CAN_FILL_LIMIT_ORDERS: bool = false;
+
+    #[inline(always)]
+    fn limit_order_filled<UserOrderIdT: UserOrderId>(
+        &mut self,
+        _limit_order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<(BaseOrQuote, Exhausted)> {
+        unreachable!(
+            "This should never be called, because a best bid and ask update can never fill a limit order."
+        );
+    }
+
+    fn validate_market_update(&self, price_filter: &PriceFilter<I, D>) -> Result<()> {
+        enforce_min_price(price_filter.min_price(), self.bid)?;
+        enforce_min_price(price_filter.min_price(), self.ask)?;
+        enforce_max_price(price_filter.max_price(), self.bid)?;
+        enforce_max_price(price_filter.max_price(), self.ask)?;
+        enforce_step_size(price_filter.tick_size(), self.bid)?;
+        enforce_step_size(price_filter.tick_size(), self.ask)?;
+        enforce_bid_ask_spread(self.bid, self.ask)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn update_market_state(&self, market_state: &mut MarketState<I, D>) {
+        market_state.set_bid(self.bid);
+        market_state.set_ask(self.ask);
+    }
+
+    #[inline]
+    fn update_depth(&self, depth: &mut OrderBookDepth<I, D, BaseOrQuote>) -> bool {
+        depth.update_best_level(Side::Buy, PriceLevel {
+            price: self.bid,
+            quantity: self.bid_quantity,
+        });
+        depth.update_best_level(Side::Sell, PriceLevel {
+            price: self.ask,
+            quantity: self.ask_quantity,
+        });
+        true
+    }
+
+    #[inline(always)]
+    fn timestamp_exchange_ns(&self) -> TimestampNs {
+        self.timestamp_exchange_ns
+    }
+
+    #[inline(always)]
+    fn can_fill_bids(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn can_fill_asks(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+        PriceLevel {
+            price: QuoteCurrency::new(price, 0),
+            quantity: BaseCurrency::new(quantity, 0),
+        }
+    }
+
+    #[test]
+    fn sized_bba_update_market_state() {
+        let update = SizedBba {
+            bid: QuoteCurrency::new(100, 0),
+            bid_quantity: BaseCurrency::new(3, 0),
+            ask: QuoteCurrency::new(102, 0),
+            ask_quantity: BaseCurrency::new(1, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        let mut depth =
+            OrderBookDepth::new(vec![level(101, 2), level(100, 1), level(99, 5)], vec![
+                level(102, 4),
+                level(103, 2),
+            ]);
+        let mut state = MarketState::default();
+        state.update_state(&update, &PriceFilter::default(), &mut depth);
+        assert_eq!(state.bid(), QuoteCurrency::new(100, 0));
+        assert_eq!(state.ask(), QuoteCurrency::new(102, 0));
+        assert_eq!(depth.bids(), &vec![level(100, 3), level(99, 5)]);
+        assert_eq!(depth.asks(), &vec![level(102, 1), level(103, 2)]);
+    }
+
+    #[test]
+    fn sized_bba_update_display() {
+        let update = SizedBba {
+            bid: QuoteCurrency::<i64, 1>::new(100, 0),
+            bid_quantity: BaseCurrency::new(3, 0),
+            ask: QuoteCurrency::new(101, 0),
+            ask_quantity: BaseCurrency::new(1, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        assert_eq!(
+            &update.to_string(),
+            "bid: 3.0 Base @ 100.0 Quote, ask: 1.0 Base @ 101.0 Quote, ts: 1"
+        );
+    }
+}
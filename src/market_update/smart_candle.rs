@@ -125,6 +125,20 @@ where
             bba,
         }
     }
+
+    /// The buy and sell volume which has not been consumed by filling limit orders yet.
+    #[inline]
+    fn total_volume(&self) -> BaseOrQuote {
+        let buy_volume = self
+            .aggregate_buy_volume
+            .last()
+            .map_or(BaseOrQuote::zero(), |v| v.1);
+        let sell_volume = self
+            .aggregate_sell_volume
+            .last()
+            .map_or(BaseOrQuote::zero(), |v| v.1);
+        buy_volume + sell_volume
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote> std::fmt::Display for SmartCandle<I, D, BaseOrQuote>
@@ -151,7 +165,8 @@ where
     const CAN_FILL_LIMIT_ORDERS: bool = true;
 
     // TODO: benchmark and optimize this.
-    // TODO: reduce the quantity if a limit order is filled.
+    /// The volume that fills the order is consumed, starting with the trades that went furthest through the book,
+    /// so the combined fills of all orders never exceed the traded volume.
     #[inline]
     fn limit_order_filled<UserOrderIdT: UserOrderId>(
         &mut self,
@@ -163,28 +178,39 @@ where
             crate::prelude::Pending<I, D, BaseOrQuote>,
         >,
     ) -> Option<(BaseOrQuote, Exhausted)> {
-        match limit_order.side() {
+        let aggregate_volume = match limit_order.side() {
             Side::Buy => {
                 if self.low >= limit_order.limit_price() {
                     return None;
                 }
-                self.aggregate_sell_volume
-                    .iter()
-                    .rev()
-                    .find(|v| v.0 < limit_order.limit_price())
-                    .map(|v| (min(v.1, limit_order.remaining_quantity()), false))
+                &mut self.aggregate_sell_volume
             }
             Side::Sell => {
                 if self.high <= limit_order.limit_price() {
                     return None;
                 }
-                self.aggregate_buy_volume
-                    .iter()
-                    .rev()
-                    .find(|v| v.0 > limit_order.limit_price())
-                    .map(|v| (min(v.1, limit_order.remaining_quantity()), false))
+                &mut self.aggregate_buy_volume
             }
+        };
+        let available_qty = aggregate_volume
+            .iter()
+            .rev()
+            .find(|v| match limit_order.side() {
+                Side::Buy => v.0 < limit_order.limit_price(),
+                Side::Sell => v.0 > limit_order.limit_price(),
+            })?
+            .1;
+        let filled_qty = min(available_qty, limit_order.remaining_quantity());
+        if filled_qty <= BaseOrQuote::zero() {
+            return None;
+        }
+        // Each level holds the cumulative volume of all levels further through the book,
+        // which shrinks by the filled quantity until it is used up.
+        for (_, volume) in aggregate_volume.iter_mut() {
+            *volume = (*volume - filled_qty).max(BaseOrQuote::zero());
         }
+
+        Some((filled_qty, self.total_volume().is_zero()))
     }
 
     #[inline(always)]
@@ -203,6 +229,11 @@ where
         market_state.set_ask(self.bba.ask);
     }
 
+    #[inline(always)]
+    fn price_range(&self) -> Option<(QuoteCurrency<I, D>, QuoteCurrency<I, D>)> {
+        Some((self.low, self.high))
+    }
+
     #[inline(always)]
     fn timestamp_exchange_ns(&self) -> crate::prelude::TimestampNs {
         self.last_timestamp_exchange_ns
@@ -610,6 +641,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn smart_candle_consumes_volume() {
+        let trades = &[
+            Trade {
+                timestamp_exchange_ns: 0.into(),
+                price: QuoteCurrency::<i64, 5>::new(99, 0),
+                quantity: BaseCurrency::new(3, 0),
+                side: Side::Sell,
+            },
+            Trade {
+                timestamp_exchange_ns: 1.into(),
+                price: QuoteCurrency::<i64, 5>::new(98, 0),
+                quantity: BaseCurrency::new(2, 0),
+                side: Side::Sell,
+            },
+        ];
+        let bba = Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 1.into(),
+        };
+        let pf = PriceFilter::new(
+            None,
+            None,
+            QuoteCurrency::new(1, 0),
+            Decimal::TWO,
+            Decimal::try_from_scaled(5, 1).unwrap(),
+        )
+        .unwrap();
+        let mut smart_candle = SmartCandle::new(trades, bba, &pf);
+        let limit_buy = |price: i64, qty: i64| {
+            LimitOrder::<i64, 5, _, NoUserOrderId, _>::new(
+                Side::Buy,
+                QuoteCurrency::<i64, 5>::new(price, 0),
+                BaseCurrency::new(qty, 0),
+            )
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()))
+        };
+
+        assert_eq!(
+            smart_candle.limit_order_filled(&limit_buy(100, 4)),
+            Some((BaseCurrency::new(4, 0), false))
+        );
+        assert_eq!(smart_candle.aggregate_sell_volume, vec![
+            (QuoteCurrency::new(98, 0), BaseCurrency::new(0, 0)),
+            (QuoteCurrency::new(99, 0), BaseCurrency::new(1, 0)),
+        ]);
+        // The sells below 99 were consumed by the first order.
+        assert_eq!(smart_candle.limit_order_filled(&limit_buy(99, 2)), None);
+        assert_eq!(
+            smart_candle.limit_order_filled(&limit_buy(100, 2)),
+            Some((BaseCurrency::new(1, 0), true))
+        );
+        assert_eq!(smart_candle.limit_order_filled(&limit_buy(100, 2)), None);
+    }
+
     #[test]
     fn size_of_smart_candle() {
         assert_eq!(
@@ -46,6 +46,20 @@ where
             Side::Sell => self.price > order.limit_price() && matches!(self.side, Side::Buy),
         }
     }
+
+    /// The quantity of the `Trade` left for the `order` at the touch,
+    /// once the queue ahead of it is consumed. Only if its queue position is tracked.
+    #[inline(always)]
+    fn touch_quantity<UserOrderIdT: UserOrderId>(
+        &self,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> Option<BaseOrQuote> {
+        let queue_ahead = order.queue_ahead()?;
+        (self.side == order.side().inverted()
+            && self.price == order.limit_price()
+            && self.quantity > queue_ahead)
+            .then(|| self.quantity - queue_ahead)
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote> std::fmt::Display for Trade<I, D, BaseOrQuote>
@@ -81,16 +95,18 @@ where
         debug_assert!(order.remaining_quantity() > BaseOrQuote::zero());
 
         // Notice that the limit order price must be strictly lower or higher than the limit order price,
-        // because we assume the limit order has the worst possible queue position in the book.
-        if self.fills_order(order) {
-            // Execute up to the quantity of the incoming `Trade`.
-            let filled_qty = min(self.quantity, order.remaining_quantity());
-            self.quantity -= filled_qty;
-            debug_assert!(self.quantity >= Zero::zero());
-            Some((filled_qty, self.quantity <= Zero::zero()))
+        // because we assume the limit order has the worst possible queue position in the book,
+        // unless its queue position is tracked.
+        let available_qty = if self.fills_order(order) {
+            self.quantity
         } else {
-            None
-        }
+            self.touch_quantity(order)?
+        };
+        // Execute up to the available quantity of the incoming `Trade`.
+        let filled_qty = min(available_qty, order.remaining_quantity());
+        self.quantity -= filled_qty;
+        debug_assert!(self.quantity >= Zero::zero());
+        Some((filled_qty, self.quantity <= Zero::zero()))
     }
 
     fn validate_market_update(&self, price_filter: &PriceFilter<I, D>) -> Result<()> {
@@ -106,6 +122,20 @@ where
         market_state.set_last_trade_price(self.price);
     }
 
+    #[inline(always)]
+    fn traded_quantity(&self, side: Side, price: QuoteCurrency<I, D>) -> BaseOrQuote {
+        if self.side == side.inverted() && self.price == price {
+            self.quantity
+        } else {
+            BaseOrQuote::zero()
+        }
+    }
+
+    #[inline(always)]
+    fn price_range(&self) -> Option<(QuoteCurrency<I, D>, QuoteCurrency<I, D>)> {
+        Some((self.price, self.price))
+    }
+
     #[inline(always)]
     fn timestamp_exchange_ns(&self) -> TimestampNs {
         self.timestamp_exchange_ns
@@ -368,6 +398,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trade_update_fills_at_touch() {
+        let mut order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        let mut trade = Trade {
+            price: QuoteCurrency::new(100, 0),
+            quantity: BaseCurrency::new(4, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 0.into(),
+        };
+        // Without a tracked queue position, the worst one is assumed.
+        assert_eq!(trade.limit_order_filled(&order), None);
+        assert_eq!(
+            trade.traded_quantity(Side::Buy, QuoteCurrency::new(100, 0)),
+            BaseCurrency::new(4, 0)
+        );
+        assert_eq!(
+            trade.traded_quantity(Side::Sell, QuoteCurrency::new(100, 0)),
+            BaseCurrency::zero()
+        );
+
+        order.set_queue_ahead(Some(BaseCurrency::new(4, 0)));
+        assert_eq!(trade.limit_order_filled(&order), None);
+
+        order.set_queue_ahead(Some(BaseCurrency::new(1, 0)));
+        assert_eq!(
+            trade.limit_order_filled(&order),
+            Some((BaseCurrency::new(3, 0), false))
+        );
+        assert_eq!(trade.quantity, BaseCurrency::new(1, 0));
+    }
+
     #[test]
     fn size_of_trade() {
         assert_eq!(
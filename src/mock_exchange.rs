@@ -4,7 +4,7 @@ use std::num::NonZeroUsize;
 
 use const_decimal::Decimal;
 
-use crate::{prelude::*, utils::NoUserOrderId};
+use crate::{prelude::*, types::NANOS_PER_SECOND, utils::NoUserOrderId};
 
 /// The constant decimal precision.
 pub const DECIMALS: u8 = 5;
@@ -45,7 +45,8 @@ pub fn mock_exchange_linear() -> Exchange<i64, DECIMALS, BaseCurrency<i64, DECIM
 
 /// Constructs a mock exchange (for linear futures) for testing.
 /// The size is denoted in `BaseCurrency`
-/// and the margin currency is `QuoteCurency`
+/// and the margin currency is `QuoteCurency`.
+/// The `AccountTracker` samples the equity every second.
 pub fn mock_exchange_linear_with_account_tracker(
     starting_balance: QuoteCurrency<i64, DECIMALS>,
 ) -> Exchange<i64, DECIMALS, BaseCurrency<i64, DECIMALS>, NoUserOrderId> {
@@ -64,6 +65,8 @@ pub fn mock_exchange_linear_with_account_tracker(
         contract_spec,
         OrderRateLimits::default(),
     )
+    .unwrap()
+    .with_account_tracker(TimestampNs::from(NANOS_PER_SECOND))
     .unwrap();
     Exchange::new(config)
 }
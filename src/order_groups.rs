@@ -0,0 +1,195 @@
+use crate::{
+    prelude::{Currency, Mon},
+    types::{LinkedOrder, OrderId, Pending, UserOrderId},
+};
+
+/// The exit orders of a bracket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BracketExits<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// The entry order has not been filled yet, so the exit orders are held back.
+    Inactive(Box<[LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>; 2]>),
+    /// The exit orders are active and cancel each other.
+    Active([OrderId; 2]),
+}
+
+/// A group of orders which are linked to each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum OrderGroup<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// One-cancels-other pair of orders.
+    /// A fill of one order reduces the quantity of the other one,
+    /// a trigger or cancellation of one order cancels the other one.
+    Oco([OrderId; 2]),
+    /// An entry `LimitOrder` with a take-profit and stop-loss exit,
+    /// which become active once the entry order is filled and grow with each fill of the entry.
+    Bracket {
+        /// The id of the entry order.
+        entry: OrderId,
+        /// The exit orders.
+        exits: BracketExits<I, D, BaseOrQuote, UserOrderIdT>,
+    },
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> OrderGroup<I, D, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    fn contains(&self, order_id: OrderId) -> bool {
+        match self {
+            OrderGroup::Oco(legs) => legs.contains(&order_id),
+            OrderGroup::Bracket { entry, exits } => {
+                *entry == order_id
+                    || matches!(exits, BracketExits::Active(legs) if legs.contains(&order_id))
+            }
+        }
+    }
+
+    /// Replace the `old` order id with the `new` one, e.g. after an order was amended.
+    pub(crate) fn replace_id(&mut self, old: OrderId, new: OrderId) {
+        let ids = match self {
+            OrderGroup::Oco(legs) => legs.as_mut_slice(),
+            OrderGroup::Bracket { entry, exits } => {
+                if *entry == old {
+                    *entry = new;
+                    return;
+                }
+                match exits {
+                    BracketExits::Inactive(_) => return,
+                    BracketExits::Active(legs) => legs.as_mut_slice(),
+                }
+            }
+        };
+        if let Some(id) = ids.iter_mut().find(|id| **id == old) {
+            *id = new;
+        }
+    }
+}
+
+/// Get the other order id of a linked pair.
+#[inline]
+pub(crate) fn other_leg(legs: [OrderId; 2], order_id: OrderId) -> OrderId {
+    debug_assert!(legs.contains(&order_id));
+    if legs[0] == order_id {
+        legs[1]
+    } else {
+        legs[0]
+    }
+}
+
+/// Keeps track of the linked orders.
+#[derive(Debug, Clone)]
+pub(crate) struct OrderGroups<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    groups: Vec<OrderGroup<I, D, BaseOrQuote, UserOrderIdT>>,
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> Default
+    for OrderGroups<I, D, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    fn default() -> Self {
+        Self { groups: Vec::new() }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> OrderGroups<I, D, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Track a new group of linked orders.
+    #[inline]
+    pub(crate) fn push(&mut self, group: OrderGroup<I, D, BaseOrQuote, UserOrderIdT>) {
+        self.groups.push(group);
+    }
+
+    /// Remove and return the group which contains the `order_id`, if any.
+    #[inline]
+    pub(crate) fn take(
+        &mut self,
+        order_id: OrderId,
+    ) -> Option<OrderGroup<I, D, BaseOrQuote, UserOrderIdT>> {
+        let idx = self
+            .groups
+            .iter()
+            .position(|group| group.contains(order_id))?;
+        Some(self.groups.swap_remove(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::BaseCurrency, utils::NoUserOrderId};
+
+    type Groups = OrderGroups<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>;
+
+    #[test]
+    fn order_groups_take() {
+        let mut groups = Groups::default();
+        groups.push(OrderGroup::Oco([0.into(), 1.into()]));
+        groups.push(OrderGroup::Bracket {
+            entry: 2.into(),
+            exits: BracketExits::Active([3.into(), 4.into()]),
+        });
+
+        assert_eq!(groups.take(5.into()), None);
+        assert_eq!(
+            groups.take(4.into()),
+            Some(OrderGroup::Bracket {
+                entry: 2.into(),
+                exits: BracketExits::Active([3.into(), 4.into()]),
+            })
+        );
+        assert_eq!(groups.take(2.into()), None);
+        assert_eq!(
+            groups.take(1.into()),
+            Some(OrderGroup::Oco([0.into(), 1.into()]))
+        );
+        assert_eq!(groups.take(0.into()), None);
+    }
+
+    #[test]
+    fn order_group_replace_id() {
+        let mut group =
+            OrderGroup::<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>::Oco([0.into(), 1.into()]);
+        group.replace_id(1.into(), 5.into());
+        assert_eq!(group, OrderGroup::Oco([0.into(), 5.into()]));
+
+        let mut group = OrderGroup::<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>::Bracket {
+            entry: 0.into(),
+            exits: BracketExits::Active([1.into(), 2.into()]),
+        };
+        group.replace_id(0.into(), 3.into());
+        group.replace_id(2.into(), 4.into());
+        assert_eq!(group, OrderGroup::Bracket {
+            entry: 3.into(),
+            exits: BracketExits::Active([1.into(), 4.into()]),
+        });
+    }
+
+    #[test]
+    fn order_groups_other_leg() {
+        assert_eq!(other_leg([0.into(), 1.into()], 0.into()), 1.into());
+        assert_eq!(other_leg([0.into(), 1.into()], 1.into()), 0.into());
+    }
+}
@@ -1,4 +1,4 @@
-use std::num::NonZeroUsize;
+use std::{cmp::Ordering, num::NonZeroUsize};
 
 use const_decimal::Decimal;
 use getset::{CopyGetters, Getters, MutGetters};
@@ -7,10 +7,14 @@ use tracing::{debug, trace};
 
 use crate::{
     Result,
+    contract_specification::ContractSpecification,
     exchange::CancelBy,
     prelude::{ActiveLimitOrders, Currency, Mon, Position},
-    types::{Balances, Error, LimitOrder, MarginCurrency, Pending, Side, UserOrderId},
-    utils::max,
+    types::{
+        Balances, Error, LimitOrder, MarginCurrency, OrderId, Pending, Side, StopOrder,
+        TimestampNs, UserOrderId,
+    },
+    utils::{max, min},
 };
 
 /// An implementation for computing the order margin online, aka with every change to the active orders.
@@ -23,8 +27,15 @@ where
 {
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
     active_limit_orders: ActiveLimitOrders<I, D, BaseOrQuote, UserOrderIdT>,
+    #[getset(get = "pub(crate)")]
+    stop_orders: Vec<StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>>,
+    /// Limit orders which are hidden until the away market quotation is lifted.
+    #[getset(get = "pub(crate)")]
+    hidden_orders: Vec<LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>>,
     bids_notional: BaseOrQuote::PairedCurrency,
     asks_notional: BaseOrQuote::PairedCurrency,
+    /// The maximum number of limit, stop and hidden orders combined.
+    max_active_orders: NonZeroUsize,
 }
 
 impl<I, const D: u8, BaseOrQuote, UserOrderIdT> OrderMargin<I, D, BaseOrQuote, UserOrderIdT>
@@ -34,15 +45,32 @@ where
     BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
     UserOrderIdT: UserOrderId,
 {
-    /// Create a new instance with a maximum capacity of `max_active_orders`.
+    /// Create a new instance with a maximum capacity of `max_active_orders`,
+    /// which the limit, stop and hidden orders share.
     pub fn new(max_active_orders: NonZeroUsize) -> Self {
         Self {
             active_limit_orders: ActiveLimitOrders::with_capacity(max_active_orders),
+            stop_orders: Vec::with_capacity(max_active_orders.get()),
+            hidden_orders: Vec::with_capacity(max_active_orders.get()),
             bids_notional: Zero::zero(),
             asks_notional: Zero::zero(),
+            max_active_orders,
         }
     }
 
+    /// The number of active limit orders, stop orders and hidden limit orders.
+    pub fn num_open_orders(&self) -> usize {
+        self.active_limit_orders.num_active() + self.stop_orders.len() + self.hidden_orders.len()
+    }
+
+    /// Ensure another order fits into the `max_active_orders`.
+    fn check_capacity(&self) -> Result<()> {
+        if self.num_open_orders() >= self.max_active_orders.get() {
+            return Err(Error::MaxNumberOfActiveOrders);
+        }
+        Ok(())
+    }
+
     /// Insert a new limit order.
     #[inline(always)]
     pub fn try_insert(
@@ -50,24 +78,17 @@ where
         order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
         position: &Position<I, D, BaseOrQuote>,
-        init_margin_req: Decimal<I, D>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
     ) -> Result<()> {
         trace!("OrderMargin.try_insert {order:?}");
+        self.check_capacity()?;
         self.active_limit_orders.try_insert(order.clone())?;
         match order.side() {
-            Side::Buy => self.bids_notional += order.notional(),
-            Side::Sell => self.asks_notional += order.notional(),
+            Side::Buy => self.bids_notional += Self::margin_notional(&order),
+            Side::Sell => self.asks_notional += Self::margin_notional(&order),
         }
 
-        // Update balances
-        let new_order_margin = self.order_margin(init_margin_req, position);
-        assert2::debug_assert!(new_order_margin >= balances.order_margin());
-        if new_order_margin > balances.order_margin() {
-            let margin = new_order_margin - balances.order_margin();
-            assert2::debug_assert!(margin >= BaseOrQuote::PairedCurrency::zero());
-            let success = balances.try_reserve_order_margin(margin);
-            debug_assert!(success, "Can place order");
-        }
+        self.update_balances(balances, position, contract_spec);
 
         Ok(())
     }
@@ -80,13 +101,13 @@ where
         order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
         position: &Position<I, D, BaseOrQuote>,
-        init_margin_req: Decimal<I, D>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
     ) {
         trace!("OrderMargin.update {order:?}");
-        let notional = order.notional();
-        assert2::debug_assert!(notional > Zero::zero());
+        let notional = Self::margin_notional(&order);
+        assert2::debug_assert!(order.notional() > Zero::zero());
         let old_order = self.active_limit_orders.update(order);
-        let notional_delta = notional - old_order.notional();
+        let notional_delta = notional - Self::margin_notional(&old_order);
         match old_order.side() {
             Side::Buy => {
                 self.bids_notional += notional_delta;
@@ -98,17 +119,7 @@ where
             }
         }
 
-        // Update balances
-        let new_order_margin = self.order_margin(init_margin_req, position);
-        assert2::debug_assert!(
-            new_order_margin <= balances.order_margin(),
-            "The order margin does not increase with a filled limit order event."
-        );
-        if new_order_margin < balances.order_margin() {
-            let margin_delta = balances.order_margin() - new_order_margin;
-            assert2::debug_assert!(margin_delta > Zero::zero());
-            balances.free_order_margin(margin_delta);
-        }
+        self.update_balances(balances, position, contract_spec);
     }
 
     /// Remove an order from being tracked for margin purposes.
@@ -117,20 +128,191 @@ where
         by: CancelBy<UserOrderIdT>,
         balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
         position: &Position<I, D, BaseOrQuote>,
-        init_margin_req: Decimal<I, D>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
     ) -> Result<LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
         debug!("OrderMargin.remove {by:?}");
         let removed_order = match by {
             CancelBy::OrderId(order_id) => self
                 .active_limit_orders
                 .remove(order_id)
+                .or_else(|| self.remove_hidden(|order| order.id() == order_id))
                 .ok_or(Error::OrderIdNotFound { order_id })?,
             CancelBy::UserOrderId(user_order_id) => self
                 .active_limit_orders
                 .remove_by_user_order_id(user_order_id)
+                .or_else(|| self.remove_hidden(|order| order.user_order_id() == user_order_id))
                 .ok_or(Error::UserOrderIdNotFound)?,
         };
 
+        match removed_order.side() {
+            Side::Buy => {
+                self.bids_notional -= Self::margin_notional(&removed_order);
+                assert2::debug_assert!(self.bids_notional >= Zero::zero());
+            }
+            Side::Sell => {
+                self.asks_notional -= Self::margin_notional(&removed_order);
+                assert2::debug_assert!(self.asks_notional >= Zero::zero());
+            }
+        }
+
+        self.update_balances(balances, position, contract_spec);
+
+        Ok(removed_order)
+    }
+
+    /// `true` if there are neither active limit orders, hidden limit orders nor stop orders.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.active_limit_orders.is_empty()
+            && self.stop_orders.is_empty()
+            && self.hidden_orders.is_empty()
+    }
+
+    /// Insert a new limit order which is hidden from the book, but requires order margin just like an active one.
+    pub fn try_insert_hidden(
+        &mut self,
+        order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
+        position: &Position<I, D, BaseOrQuote>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<()> {
+        trace!("OrderMargin.try_insert_hidden {order:?}");
+        self.check_capacity()?;
+        match order.side() {
+            Side::Buy => self.bids_notional += Self::margin_notional(&order),
+            Side::Sell => self.asks_notional += Self::margin_notional(&order),
+        }
+        self.hidden_orders.push(order);
+
+        self.update_balances(balances, position, contract_spec);
+
+        Ok(())
+    }
+
+    /// Move the hidden order at `idx` into the book. Its notional value and thus the order margin do not change.
+    pub(crate) fn reveal_hidden(&mut self, idx: usize) -> Result<()> {
+        let order = self.hidden_orders.remove(idx);
+        debug!("OrderMargin.reveal_hidden {order:?}");
+        if let Err(e) = self.active_limit_orders.try_insert(order.clone()) {
+            self.hidden_orders.insert(idx, order);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// The id of the first active or hidden limit order whose `TimeInForce` has expired at `ts_ns`, if any.
+    pub(crate) fn first_expired_order(&self, ts_ns: TimestampNs) -> Option<OrderId> {
+        self.active_limit_orders
+            .bids()
+            .iter()
+            .chain(self.active_limit_orders.asks())
+            .chain(self.hidden_orders.iter())
+            .find(|order| order.time_in_force().is_expired(ts_ns))
+            .map(|order| order.id())
+    }
+
+    /// The first active or hidden reduce-only order which no longer reduces the `position`, if any.
+    /// Returns its id and the quantity it must be clamped to, or `None` if it must be cancelled.
+    pub(crate) fn first_excessive_reduce_only_order(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+    ) -> Option<(OrderId, Option<BaseOrQuote>)> {
+        self.active_limit_orders
+            .bids()
+            .iter()
+            .chain(self.active_limit_orders.asks())
+            .chain(self.hidden_orders.iter())
+            .filter(|order| order.reduce_only())
+            .find_map(|order| match position.reducible_quantity(order.side()) {
+                None => Some((order.id(), None)),
+                Some(qty) if order.remaining_quantity() > qty => Some((order.id(), Some(qty))),
+                Some(_) => None,
+            })
+    }
+
+    /// Reduce the `remaining_quantity` of an active or hidden reduce-only order.
+    /// As reduce-only orders don't require order margin, the balances are not affected.
+    /// # Panics:
+    /// panics if the order id was not found.
+    pub(crate) fn clamp_reduce_only(&mut self, order_id: OrderId, quantity: BaseOrQuote) {
+        trace!("OrderMargin.clamp_reduce_only {order_id}: {quantity}");
+        if let Some(order) = self
+            .hidden_orders
+            .iter_mut()
+            .find(|order| order.id() == order_id)
+        {
+            debug_assert!(order.reduce_only());
+            order.set_remaining_quantity(quantity);
+            return;
+        }
+        let mut order = self
+            .active_limit_orders
+            .bids()
+            .iter()
+            .chain(self.active_limit_orders.asks())
+            .find(|order| order.id() == order_id)
+            .expect("Order must be active")
+            .clone();
+        debug_assert!(order.reduce_only());
+        order.set_remaining_quantity(quantity);
+        let _ = self.active_limit_orders.update(order);
+    }
+
+    fn remove_hidden<F>(
+        &mut self,
+        predicate: F,
+    ) -> Option<LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>>
+    where
+        F: Fn(&LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>) -> bool,
+    {
+        let idx = self.hidden_orders.iter().position(predicate)?;
+        Some(self.hidden_orders.remove(idx))
+    }
+
+    /// Insert a new stop order, which requires order margin just like a limit order.
+    pub fn try_insert_stop(
+        &mut self,
+        order: StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
+        position: &Position<I, D, BaseOrQuote>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<()> {
+        trace!("OrderMargin.try_insert_stop {order:?}");
+        self.check_capacity()?;
+        match order.side() {
+            Side::Buy => self.bids_notional += order.notional(),
+            Side::Sell => self.asks_notional += order.notional(),
+        }
+        self.stop_orders.push(order);
+
+        self.update_balances(balances, position, contract_spec);
+
+        Ok(())
+    }
+
+    /// Remove a stop order from being tracked for margin purposes.
+    pub fn remove_stop(
+        &mut self,
+        by: CancelBy<UserOrderIdT>,
+        balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
+        position: &Position<I, D, BaseOrQuote>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        debug!("OrderMargin.remove_stop {by:?}");
+        let idx = match by {
+            CancelBy::OrderId(order_id) => self
+                .stop_orders
+                .iter()
+                .position(|order| order.id() == order_id)
+                .ok_or(Error::OrderIdNotFound { order_id })?,
+            CancelBy::UserOrderId(user_order_id) => self
+                .stop_orders
+                .iter()
+                .position(|order| order.user_order_id() == user_order_id)
+                .ok_or(Error::UserOrderIdNotFound)?,
+        };
+        let removed_order = self.stop_orders.remove(idx);
+
         match removed_order.side() {
             Side::Buy => {
                 self.bids_notional -= removed_order.notional();
@@ -142,21 +324,39 @@ where
             }
         }
 
-        // Update balances
-        let new_order_margin = self.order_margin(init_margin_req, position);
-        assert2::debug_assert!(
-            new_order_margin <= balances.order_margin(),
-            "When removing a limit order, the new order margin is smaller or equal the old order margin"
-        );
-        if new_order_margin < balances.order_margin() {
-            let margin = balances.order_margin() - new_order_margin;
-            assert2::debug_assert!(margin >= Zero::zero());
-            balances.free_order_margin(margin);
-        }
+        self.update_balances(balances, position, contract_spec);
 
         Ok(removed_order)
     }
 
+    /// Reserve or free order margin, so the `balances` match the `tiered_order_margin`.
+    /// Order margin exceeding the available balance remains unreserved,
+    /// e.g. when unrealized profits back the orders with the cross margin mode.
+    pub(crate) fn update_balances(
+        &self,
+        balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
+        position: &Position<I, D, BaseOrQuote>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) {
+        let new_order_margin = self.tiered_order_margin(contract_spec, position);
+        match new_order_margin.cmp(&balances.order_margin()) {
+            Ordering::Less => {
+                balances.free_order_margin(balances.order_margin() - new_order_margin)
+            }
+            Ordering::Equal => {}
+            Ordering::Greater => {
+                let margin = min(
+                    new_order_margin - balances.order_margin(),
+                    balances.available(),
+                );
+                if margin > BaseOrQuote::PairedCurrency::zero() {
+                    let success = balances.try_reserve_order_margin(margin);
+                    debug_assert!(success, "Can reserve order margin");
+                }
+            }
+        }
+    }
+
     /// The margin requirement for all the tracked orders.
     pub fn order_margin(
         &self,
@@ -177,23 +377,99 @@ where
         }
     }
 
-    /// Get the order margin if a new order were to be added.
-    pub(crate) fn order_margin_with_order(
+    /// The margin requirement for all the tracked orders, at the initial margin requirement of the risk limit tier
+    /// the position reaches once all orders of either side are filled.
+    pub fn tiered_order_margin(
+        &self,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        position: &Position<I, D, BaseOrQuote>,
+    ) -> BaseOrQuote::PairedCurrency {
+        let notional = Self::projected_notional(self.bids_notional, self.asks_notional, position);
+        self.order_margin(contract_spec.init_margin_req_for(notional), position)
+    }
+
+    /// Get the tiered order margin if a new order were to be added.
+    pub fn order_margin_with_order(
         &self,
         new_order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
-        init_margin_req: Decimal<I, D>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
         position: &Position<I, D, BaseOrQuote>,
     ) -> BaseOrQuote::PairedCurrency {
-        assert2::debug_assert!(init_margin_req > Decimal::zero());
-        assert2::debug_assert!(init_margin_req <= Decimal::one());
+        self.order_margin_with_notional(
+            new_order.side(),
+            Self::margin_notional(new_order),
+            contract_spec,
+            position,
+        )
+    }
+
+    /// Get the tiered order margin if a new stop order were to be added.
+    pub fn order_margin_with_stop_order(
+        &self,
+        new_order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        position: &Position<I, D, BaseOrQuote>,
+    ) -> BaseOrQuote::PairedCurrency {
+        self.order_margin_with_notional(
+            new_order.side(),
+            new_order.notional(),
+            contract_spec,
+            position,
+        )
+    }
+
+    /// The notional value of the `position` once all orders of either side are filled, whichever is larger.
+    fn projected_notional(
+        bids_notional: BaseOrQuote::PairedCurrency,
+        asks_notional: BaseOrQuote::PairedCurrency,
+        position: &Position<I, D, BaseOrQuote>,
+    ) -> BaseOrQuote::PairedCurrency {
+        match position {
+            Position::Neutral => max(bids_notional, asks_notional),
+            Position::Long(inner) => max(
+                inner.notional() + bids_notional,
+                asks_notional - inner.notional(),
+            ),
+            Position::Short(inner) => max(
+                bids_notional - inner.notional(),
+                inner.notional() + asks_notional,
+            ),
+        }
+    }
+
+    /// The notional value of a limit order which requires order margin.
+    /// A reduce-only order can never increase the position, so it does not require any.
+    #[inline(always)]
+    fn margin_notional(
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+    ) -> BaseOrQuote::PairedCurrency {
+        if order.reduce_only() {
+            Zero::zero()
+        } else {
+            order.notional()
+        }
+    }
 
+    fn order_margin_with_notional(
+        &self,
+        side: Side,
+        new_notional: BaseOrQuote::PairedCurrency,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        position: &Position<I, D, BaseOrQuote>,
+    ) -> BaseOrQuote::PairedCurrency {
         let mut buy_notional = self.bids_notional;
         let mut sell_notional = self.asks_notional;
-        let new_notional = new_order.notional();
-        match new_order.side() {
+        match side {
             Side::Buy => buy_notional += new_notional,
             Side::Sell => sell_notional += new_notional,
         }
+        let init_margin_req = contract_spec.init_margin_req_for(Self::projected_notional(
+            buy_notional,
+            sell_notional,
+            position,
+        ));
+        assert2::debug_assert!(init_margin_req > Decimal::zero());
+        assert2::debug_assert!(init_margin_req <= Decimal::one());
 
         match position {
             Position::Neutral => max(buy_notional, sell_notional) * init_margin_req,
@@ -216,6 +492,24 @@ mod tests {
     use super::*;
     use crate::{DECIMALS, prelude::*, test_fee_maker};
 
+    fn contract_spec_with_leverage<I, const D: u8, BaseOrQuote>(
+        leverage: u8,
+    ) -> ContractSpecification<I, D, BaseOrQuote>
+    where
+        I: Mon<D>,
+        BaseOrQuote: Currency<I, D>,
+    {
+        ContractSpecification::new(
+            Leverage::new(leverage).unwrap(),
+            Decimal::one(),
+            PriceFilter::default(),
+            QuantityFilter::default(),
+            Fee::from(Decimal::zero()),
+            Fee::from(Decimal::zero()),
+        )
+        .unwrap()
+    }
+
     #[test]
     fn order_margin_assert_limit_order_reduces_qty() {
         let new_active_order = LimitOrder::new(
@@ -339,7 +633,8 @@ mod tests {
         let mut order_margin =
             OrderMargin::<_, 4, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
 
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec_with_leverage(leverage);
+        let init_margin_req = contract_spec.init_margin_req();
 
         let qty = BaseCurrency::new(qty, 0);
         let limit_price = QuoteCurrency::new(limit_price, 0);
@@ -354,7 +649,7 @@ mod tests {
         let position = Position::Neutral;
         orders.iter().for_each(|order| {
             order_margin
-                .try_insert(order.clone(), &mut balances, &position, init_margin_req)
+                .try_insert(order.clone(), &mut balances, &position, &contract_spec)
                 .unwrap()
         });
 
@@ -374,7 +669,7 @@ mod tests {
                 CancelBy::OrderId(order.id()),
                 &mut balances,
                 &position,
-                init_margin_req,
+                &contract_spec,
             );
         });
         let om = QuoteCurrency::new(0, 0);
@@ -405,7 +700,8 @@ mod tests {
         let mut order_margin =
             OrderMargin::<_, 4, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
 
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec_with_leverage(leverage);
+        let init_margin_req = contract_spec.init_margin_req();
 
         let qty = BaseCurrency::new(qty, 0);
         let limit_price = QuoteCurrency::new(limit_price, 0);
@@ -419,7 +715,7 @@ mod tests {
         let position = Position::Neutral;
         buy_orders.iter().for_each(|order| {
             order_margin
-                .try_insert(order.clone(), &mut balances, &position, init_margin_req)
+                .try_insert(order.clone(), &mut balances, &position, &contract_spec)
                 .unwrap();
         });
         let notional: QuoteCurrency<i32, 4> = buy_orders.iter().map(|o| o.notional()).sum();
@@ -432,7 +728,7 @@ mod tests {
         }));
         sell_orders.iter().for_each(|order| {
             order_margin
-                .try_insert(order.clone(), &mut balances, &position, init_margin_req)
+                .try_insert(order.clone(), &mut balances, &position, &contract_spec)
                 .unwrap();
         });
 
@@ -452,7 +748,7 @@ mod tests {
                 CancelBy::OrderId(order.id()),
                 &mut balances,
                 &position,
-                init_margin_req,
+                &contract_spec,
             );
         });
         sell_orders.iter().for_each(|order| {
@@ -460,7 +756,7 @@ mod tests {
                 CancelBy::OrderId(order.id()),
                 &mut balances,
                 &position,
-                init_margin_req,
+                &contract_spec,
             );
         });
         assert_eq!(
@@ -479,7 +775,8 @@ mod tests {
     fn order_margin_long_orders_of_same_qty(leverage: u8) {
         let mut order_margin =
             OrderMargin::<_, 4, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec_with_leverage(leverage);
+        let init_margin_req = contract_spec.init_margin_req();
         let qty = BaseCurrency::new(3, 0);
         let limit_price = QuoteCurrency::new(100, 0);
         let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
@@ -489,7 +786,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(0.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
 
         let pos_entry_price = QuoteCurrency::new(90, 0);
@@ -516,7 +813,8 @@ mod tests {
     ) {
         let mut order_margin =
             OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec_with_leverage(leverage);
+        let init_margin_req = contract_spec.init_margin_req();
         let qty = BaseCurrency::new(qty, 0);
         let limit_price = QuoteCurrency::new(limit_price, 0);
 
@@ -527,7 +825,7 @@ mod tests {
         let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
         let position = Position::Neutral;
         order_margin
-            .try_insert(order.clone(), &mut balances, &position, init_margin_req)
+            .try_insert(order.clone(), &mut balances, &position, &contract_spec)
             .unwrap();
         assert_eq!(order_margin.active_limit_orders.num_active(), 1);
         assert_eq!(balances.order_margin(), notional * init_margin_req);
@@ -547,7 +845,7 @@ mod tests {
             LimitOrderFill::FullyFilled { .. } => panic!("Expected `PartiallyFilled`"),
         }
         let remaining_qty = order.remaining_quantity();
-        order_margin.fill_order(order, &mut balances, &position, init_margin_req);
+        order_margin.fill_order(order, &mut balances, &position, &contract_spec);
         assert_eq!(order_margin.active_limit_orders.num_active(), 1);
         assert_eq!(remaining_qty, filled_qty);
         let om = QuoteCurrency::convert_from(remaining_qty, limit_price) * init_margin_req;
@@ -562,7 +860,8 @@ mod tests {
     #[tracing_test::traced_test]
     fn order_margin_no_position() {
         let position = Position::default();
-        let init_margin_req = Decimal::one();
+        let contract_spec = contract_spec_with_leverage(1);
+        let init_margin_req = contract_spec.init_margin_req();
         let mut order_margin = OrderMargin::new(NonZeroUsize::new(10).unwrap());
 
         assert_eq!(
@@ -578,7 +877,7 @@ mod tests {
         let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
         let position = Position::Neutral;
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         assert_eq!(order_margin.active_limit_orders.asks().len(), 0);
         assert_eq!(
@@ -591,7 +890,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(1.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         assert_eq!(order_margin.active_limit_orders.bids().len(), 1);
         assert_eq!(order_margin.active_limit_orders.asks().len(), 1);
@@ -605,7 +904,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(2.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         assert_eq!(order_margin.active_limit_orders.bids().len(), 1);
         assert_eq!(order_margin.active_limit_orders.asks().len(), 2);
@@ -623,7 +922,8 @@ mod tests {
         let entry_price = QuoteCurrency::new(100, 0);
 
         let position = Position::Long(PositionInner::new(qty, entry_price));
-        let init_margin_req = Decimal::one();
+        let contract_spec = contract_spec_with_leverage(1);
+        let init_margin_req = contract_spec.init_margin_req();
 
         assert_eq!(
             order_margin.order_margin(init_margin_req, &position),
@@ -636,7 +936,7 @@ mod tests {
         let order = order.into_pending(meta);
         let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(90, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
@@ -647,7 +947,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(1.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(90, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om,);
@@ -658,7 +958,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(2.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(120, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
@@ -669,13 +969,79 @@ mod tests {
         let meta = ExchangeOrderMeta::new(3.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(185, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
         assert_eq!(balances.order_margin(), om);
     }
 
+    #[test]
+    #[tracing_test::traced_test]
+    fn order_margin_tiered() {
+        let mut order_margin =
+            OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
+        let tier = |max_notional, max_leverage, maintenance_margin_bps| {
+            RiskLimitTier::new(
+                QuoteCurrency::new(max_notional, 0),
+                Leverage::new(max_leverage).unwrap(),
+                Decimal::try_from_scaled(maintenance_margin_bps, 4).unwrap(),
+            )
+            .unwrap()
+        };
+        let contract_spec = contract_spec_with_leverage(10)
+            .with_risk_limit_tiers(vec![tier(1000, 10, 500), tier(2000, 5, 1000)])
+            .unwrap();
+        let position = Position::Long(PositionInner::new(
+            BaseCurrency::new(5, 0),
+            QuoteCurrency::new(100, 0),
+        ));
+        let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
+
+        let order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        order_margin
+            .try_insert(order, &mut balances, &position, &contract_spec)
+            .unwrap();
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(50, 0));
+
+        // Once filled, the bids grow the position into the second tier, which requires 20% for all of them.
+        let order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(100, 0),
+            BaseCurrency::new(1, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(1.into(), 0.into()));
+        assert_eq!(
+            order_margin.order_margin_with_order(&order, &contract_spec, &position),
+            QuoteCurrency::new(120, 0)
+        );
+        order_margin
+            .try_insert(order.clone(), &mut balances, &position, &contract_spec)
+            .unwrap();
+        assert_eq!(
+            order_margin.tiered_order_margin(&contract_spec, &position),
+            QuoteCurrency::new(120, 0)
+        );
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(120, 0));
+
+        order_margin
+            .remove(
+                CancelBy::OrderId(order.id()),
+                &mut balances,
+                &position,
+                &contract_spec,
+            )
+            .unwrap();
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(50, 0));
+    }
+
     #[test]
     #[tracing_test::traced_test]
     fn order_margin_with_short() {
@@ -686,7 +1052,8 @@ mod tests {
         let entry_price = QuoteCurrency::new(100, 0);
 
         let position = Position::Short(PositionInner::new(qty, entry_price));
-        let init_margin_req = Decimal::one();
+        let contract_spec = contract_spec_with_leverage(1);
+        let init_margin_req = contract_spec.init_margin_req();
 
         assert_eq!(
             order_margin.order_margin(init_margin_req, &position),
@@ -700,7 +1067,7 @@ mod tests {
         let order = order.into_pending(meta);
         let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         assert_eq!(
             order_margin.order_margin(init_margin_req, &position),
@@ -713,7 +1080,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(1.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(100, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
@@ -724,7 +1091,7 @@ mod tests {
         let meta = ExchangeOrderMeta::new(2.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(220, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
@@ -735,10 +1102,228 @@ mod tests {
         let meta = ExchangeOrderMeta::new(3.into(), 0.into());
         let order = order.into_pending(meta);
         order_margin
-            .try_insert(order, &mut balances, &position, init_margin_req)
+            .try_insert(order, &mut balances, &position, &contract_spec)
             .unwrap();
         let om = QuoteCurrency::new(220, 0);
         assert_eq!(order_margin.order_margin(init_margin_req, &position), om);
         assert_eq!(balances.order_margin(), om);
     }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn order_margin_stop_orders() {
+        let mut order_margin =
+            OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
+        let contract_spec = contract_spec_with_leverage(1);
+        let qty = BaseCurrency::<i64, DECIMALS>::one();
+        let position = Position::Long(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+        let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
+
+        // A stop loss on the position does not require any margin.
+        let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty)
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        order_margin
+            .try_insert_stop(stop_loss.clone(), &mut balances, &position, &contract_spec)
+            .unwrap();
+        assert_eq!(balances.order_margin(), QuoteCurrency::zero());
+
+        let stop_entry = StopOrder::new_stop_limit(
+            Side::Buy,
+            QuoteCurrency::new(110, 0),
+            QuoteCurrency::new(111, 0),
+            qty,
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(1.into(), 0.into()));
+        assert_eq!(
+            order_margin.order_margin_with_stop_order(&stop_entry, &contract_spec, &position),
+            QuoteCurrency::new(111, 0)
+        );
+        order_margin
+            .try_insert_stop(stop_entry.clone(), &mut balances, &position, &contract_spec)
+            .unwrap();
+        assert_eq!(order_margin.stop_orders().len(), 2);
+        assert!(!order_margin.is_empty());
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(111, 0));
+
+        assert_eq!(
+            order_margin.remove_stop(
+                CancelBy::OrderId(stop_entry.id()),
+                &mut balances,
+                &position,
+                &contract_spec
+            ),
+            Ok(stop_entry)
+        );
+        assert_eq!(balances.order_margin(), QuoteCurrency::zero());
+        assert_eq!(
+            order_margin.remove_stop(
+                CancelBy::UserOrderId(NoUserOrderId),
+                &mut balances,
+                &position,
+                &contract_spec
+            ),
+            Ok(stop_loss)
+        );
+        assert!(order_margin.is_empty());
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn order_margin_stop_orders_share_max_active_orders() {
+        let mut order_margin =
+            OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(2).unwrap());
+        let contract_spec = contract_spec_with_leverage(1);
+        let qty = BaseCurrency::<i64, DECIMALS>::one();
+        let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
+
+        let order = LimitOrder::new(Side::Buy, QuoteCurrency::new(100, 0), qty)
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        order_margin
+            .try_insert(order, &mut balances, &Position::Neutral, &contract_spec)
+            .unwrap();
+        let stop_order = |id: u64| {
+            StopOrder::new_stop_market(Side::Buy, QuoteCurrency::new(110, 0), qty)
+                .unwrap()
+                .into_pending(ExchangeOrderMeta::new(id.into(), 0.into()))
+        };
+        order_margin
+            .try_insert_stop(
+                stop_order(1),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec,
+            )
+            .unwrap();
+        assert_eq!(order_margin.num_open_orders(), 2);
+        assert_eq!(
+            order_margin.try_insert_stop(
+                stop_order(2),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec
+            ),
+            Err(Error::MaxNumberOfActiveOrders)
+        );
+        let order = LimitOrder::new(Side::Sell, QuoteCurrency::new(120, 0), qty)
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(3.into(), 0.into()));
+        assert_eq!(
+            order_margin.try_insert(order, &mut balances, &Position::Neutral, &contract_spec),
+            Err(Error::MaxNumberOfActiveOrders)
+        );
+        assert_eq!(order_margin.num_open_orders(), 2);
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn order_margin_hidden_orders() {
+        let mut order_margin =
+            OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(10).unwrap());
+        let contract_spec = contract_spec_with_leverage(1);
+        let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
+
+        let order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(100, 0),
+            BaseCurrency::<i64, DECIMALS>::new(2, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        order_margin
+            .try_insert_hidden(
+                order.clone(),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec,
+            )
+            .unwrap();
+        assert!(!order_margin.is_empty());
+        assert!(order_margin.active_limit_orders().is_empty());
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(200, 0));
+
+        order_margin.reveal_hidden(0).unwrap();
+        assert!(order_margin.hidden_orders().is_empty());
+        assert_eq!(order_margin.active_limit_orders().num_active(), 1);
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(200, 0));
+
+        // Hidden orders can be removed just like active ones.
+        let hidden = LimitOrder::new(
+            Side::Sell,
+            QuoteCurrency::new(110, 0),
+            BaseCurrency::<i64, DECIMALS>::new(3, 0),
+        )
+        .unwrap()
+        .into_pending(ExchangeOrderMeta::new(1.into(), 0.into()));
+        order_margin
+            .try_insert_hidden(
+                hidden.clone(),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec,
+            )
+            .unwrap();
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(330, 0));
+        assert_eq!(
+            order_margin.remove(
+                CancelBy::OrderId(hidden.id()),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec
+            ),
+            Ok(hidden)
+        );
+        assert_eq!(balances.order_margin(), QuoteCurrency::new(200, 0));
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn order_margin_hidden_orders_share_max_active_orders() {
+        let mut order_margin =
+            OrderMargin::<_, DECIMALS, _, NoUserOrderId>::new(NonZeroUsize::new(2).unwrap());
+        let contract_spec = contract_spec_with_leverage(1);
+        let mut balances = Balances::new(QuoteCurrency::new(10_000, 0));
+        let hidden_order = |id: u64| {
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(100, 0),
+                BaseCurrency::<i64, DECIMALS>::one(),
+            )
+            .unwrap()
+            .into_pending(ExchangeOrderMeta::new(id.into(), 0.into()))
+        };
+
+        order_margin
+            .try_insert_hidden(
+                hidden_order(0),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec,
+            )
+            .unwrap();
+        order_margin
+            .try_insert(
+                hidden_order(1),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec,
+            )
+            .unwrap();
+        assert_eq!(order_margin.num_open_orders(), 2);
+        assert_eq!(
+            order_margin.try_insert_hidden(
+                hidden_order(2),
+                &mut balances,
+                &Position::Neutral,
+                &contract_spec
+            ),
+            Err(Error::MaxNumberOfActiveOrders)
+        );
+
+        // Revealing a hidden order does not change the number of open orders.
+        order_margin.reveal_hidden(0).unwrap();
+        assert_eq!(order_margin.num_open_orders(), 2);
+    }
 }
@@ -1,14 +1,16 @@
 use std::{cmp::Ordering, ops::Neg};
 
 use const_decimal::Decimal;
-use num::One;
-use num_traits::Zero;
+use num_traits::{Signed, Zero};
 use tracing::debug;
 
 use crate::{
+    contract_specification::ContractSpecification,
+    market_state::MarketState,
     position_inner::PositionInner,
     prelude::{Currency, Mon, QuoteCurrency},
-    types::{Balances, MarginCurrency, Side},
+    types::{Balances, MarginCurrency, MarginMode, Side},
+    utils::min,
 };
 
 /// A futures position can be one of three variants.
@@ -48,6 +50,18 @@ where
         }
     }
 
+    /// Return the positions unrealized profit and loss,
+    /// marked to market according to the `MarkMethod` of the `market_state`.
+    pub fn marked_unrealized_pnl(
+        &self,
+        market_state: &MarketState<I, D>,
+    ) -> BaseOrQuote::PairedCurrency {
+        self.unrealized_pnl(
+            market_state.mark_to_market_price(Side::Sell),
+            market_state.mark_to_market_price(Side::Buy),
+        )
+    }
+
     /// The quantity of the position, is negative when short.
     pub fn quantity(&self) -> BaseOrQuote {
         match self {
@@ -57,6 +71,17 @@ where
         }
     }
 
+    /// The quantity by which an order on `side` reduces the position at most,
+    /// or `None` if such an order would not reduce it at all.
+    pub(crate) fn reducible_quantity(&self, side: Side) -> Option<BaseOrQuote> {
+        match (self, side) {
+            (Position::Long(inner), Side::Sell) | (Position::Short(inner), Side::Buy) => {
+                Some(inner.quantity())
+            }
+            _ => None,
+        }
+    }
+
     /// The entry price of the position which is the total cost of the position relative to its quantity.
     pub fn entry_price(&self) -> QuoteCurrency<I, D> {
         match self {
@@ -75,14 +100,154 @@ where
         }
     }
 
+    /// The funding payment of the position for the given `funding_rate`, valued at the `mark_price`.
+    /// A positive value is paid by the trader, a negative value is received.
+    pub fn funding_payment(
+        &self,
+        funding_rate: Decimal<I, D>,
+        mark_price: QuoteCurrency<I, D>,
+    ) -> BaseOrQuote::PairedCurrency {
+        match self {
+            Position::Neutral => BaseOrQuote::PairedCurrency::zero(),
+            Position::Long(inner) => {
+                BaseOrQuote::PairedCurrency::convert_from(inner.quantity(), mark_price)
+                    * funding_rate
+            }
+            Position::Short(inner) => {
+                (BaseOrQuote::PairedCurrency::convert_from(inner.quantity(), mark_price)
+                    * funding_rate)
+                    .neg()
+            }
+        }
+    }
+
+    /// The mark-to-market price at which the position no longer meets its maintenance margin
+    /// and is liquidated by the `MarginModeRiskEngine` of the `margin_mode`.
+    /// In isolated margin mode, it is the price at which the losses consume the position margin down to the maintenance margin,
+    /// so funding payments taken from the position margin move it closer.
+    /// In cross margin mode, it is the price at which the equity of the account falls to the maintenance margin,
+    /// which takes the available balance into account.
+    ///
+    /// # Returns:
+    /// The liquidation price or `None` if the position is neutral or can not be liquidated at any positive price.
+    pub fn liquidation_price(
+        &self,
+        margin_mode: MarginMode,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        market_state: &MarketState<I, D>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        if *self == Position::Neutral {
+            return None;
+        }
+        let (margin, maint_margin_req) = match margin_mode {
+            MarginMode::Isolated => {
+                let notional = self.total_cost();
+                (
+                    balances.position_margin()
+                        - notional * contract_spec.maintenance_margin_for(notional),
+                    Decimal::zero(),
+                )
+            }
+            MarginMode::Cross => {
+                let mark_price = self
+                    .mark_to_market_price(market_state)
+                    .unwrap_or_else(|| self.entry_price());
+                let notional =
+                    BaseOrQuote::PairedCurrency::convert_from(self.quantity().abs(), mark_price);
+                (
+                    balances.sum(),
+                    contract_spec.maintenance_margin_for(notional),
+                )
+            }
+        };
+        BaseOrQuote::PairedCurrency::liquidation_price(
+            self.entry_price(),
+            self.quantity(),
+            margin,
+            maint_margin_req,
+        )
+    }
+
+    /// The price at which the losses of the position exhaust the margin backing it,
+    /// which is the position margin in isolated margin mode and the sum of the balances in cross margin mode.
+    ///
+    /// # Returns:
+    /// The bankruptcy price or `None` if the position is neutral or the margin covers its losses at any positive price.
+    pub fn bankruptcy_price(
+        &self,
+        margin_mode: MarginMode,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        if *self == Position::Neutral {
+            return None;
+        }
+        let margin = match margin_mode {
+            MarginMode::Isolated => balances.position_margin(),
+            MarginMode::Cross => balances.sum(),
+        };
+        BaseOrQuote::PairedCurrency::liquidation_price(
+            self.entry_price(),
+            self.quantity(),
+            margin,
+            Decimal::zero(),
+        )
+    }
+
+    /// The distance of the mark-to-market price to the `liquidation_price`, relative to the mark-to-market price.
+    /// E.g. `0.1` means the price can move 10% against the position before it is liquidated.
+    ///
+    /// # Returns:
+    /// The distance or `None` if the position is neutral, can not be liquidated or there is no mark-to-market price yet.
+    pub fn distance_to_liquidation(
+        &self,
+        margin_mode: MarginMode,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        market_state: &MarketState<I, D>,
+    ) -> Option<Decimal<I, D>> {
+        let liquidation_price =
+            self.liquidation_price(margin_mode, contract_spec, balances, market_state)?;
+        self.distance_to(liquidation_price, market_state)
+    }
+
+    /// The distance of the mark-to-market price to the `liquidation_price`, relative to the mark-to-market price.
+    pub(crate) fn distance_to(
+        &self,
+        liquidation_price: QuoteCurrency<I, D>,
+        market_state: &MarketState<I, D>,
+    ) -> Option<Decimal<I, D>> {
+        let mark_price = self.mark_to_market_price(market_state)?;
+        let distance = match self {
+            Position::Long(_) => mark_price - liquidation_price,
+            Position::Short(_) => liquidation_price - mark_price,
+            Position::Neutral => unreachable!("A neutral position has no liquidation price"),
+        };
+        Some(*distance.as_ref() / *mark_price.as_ref())
+    }
+
+    /// The price at which the position is marked to market, if there is one.
+    fn mark_to_market_price(
+        &self,
+        market_state: &MarketState<I, D>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        let mark_price = match self {
+            Position::Neutral => return None,
+            Position::Long(_) => market_state.mark_to_market_price(Side::Sell),
+            Position::Short(_) => market_state.mark_to_market_price(Side::Buy),
+        };
+        (mark_price > QuoteCurrency::zero()).then_some(mark_price)
+    }
+
     /// Change a position while doing proper accounting and balance transfers.
+    /// The position margin is reserved according to the risk limit tier of the resulting position in the `contract_spec`.
     pub fn change(
         &mut self,
         filled_qty: BaseOrQuote,
         fill_price: QuoteCurrency<I, D>,
         side: Side,
         balances: &mut Balances<I, D, BaseOrQuote::PairedCurrency>,
-        init_margin_req: Decimal<I, D>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
     ) {
         use Position::*;
         use Side::*;
@@ -92,12 +257,15 @@ where
             filled_qty > BaseOrQuote::zero(),
             "The filled_qty must be greater than zero"
         );
-        assert2::debug_assert!(init_margin_req <= Decimal::one());
-        assert2::debug_assert!(init_margin_req >= Decimal::zero());
-        debug_assert_eq!(
-            balances.position_margin(),
-            self.total_cost() * init_margin_req
+        // Funding payments the available balance could not cover may have consumed part of the position margin.
+        assert2::debug_assert!(
+            balances.position_margin() <= contract_spec.init_margin(self.total_cost())
         );
+        let only_reduces = match self {
+            Neutral => false,
+            Long(inner) => side == Sell && filled_qty < inner.quantity(),
+            Short(inner) => side == Buy && filled_qty < inner.quantity(),
+        };
 
         let pnl = match self {
             Neutral => {
@@ -159,7 +327,7 @@ where
                 }
             },
         };
-        let new_position_margin = self.total_cost() * init_margin_req;
+        let new_position_margin = contract_spec.init_margin(self.total_cost());
         assert!(new_position_margin >= Zero::zero());
         match new_position_margin.cmp(&balances.position_margin()) {
             Ordering::Less => {
@@ -167,13 +335,21 @@ where
                 balances.free_position_margin(delta);
             }
             Ordering::Equal => {}
+            // Reducing a position never reserves margin, even if funding payments depleted it.
+            Ordering::Greater if only_reduces => {}
             Ordering::Greater => {
-                let delta = new_position_margin - balances.position_margin();
-                let success = balances.try_reserve_position_margin(delta);
-                debug_assert!(success, "Can reserve position margin");
+                // A position margin depleted by funding payments is only topped up as far as the available balance allows.
+                let delta = min(
+                    new_position_margin - balances.position_margin(),
+                    balances.available(),
+                );
+                if delta > BaseOrQuote::PairedCurrency::zero() {
+                    let success = balances.try_reserve_position_margin(delta);
+                    debug_assert!(success, "Can reserve position margin");
+                }
             }
         }
-        debug_assert_eq!(new_position_margin, balances.position_margin());
+        assert2::debug_assert!(balances.position_margin() <= new_position_margin);
         balances.apply_pnl(pnl);
     }
 }
@@ -204,7 +380,19 @@ mod tests {
     use proptest::prelude::*;
 
     use super::*;
-    use crate::prelude::*;
+    use crate::{prelude::*, test_fee_maker, test_fee_taker};
+
+    fn contract_spec(leverage: u8) -> ContractSpecification<i64, 5, BaseCurrency<i64, 5>> {
+        ContractSpecification::new(
+            Leverage::new(leverage).unwrap(),
+            Decimal::try_from_scaled(5, 1).unwrap(),
+            PriceFilter::default(),
+            QuantityFilter::default(),
+            test_fee_maker(),
+            test_fee_taker(),
+        )
+        .unwrap()
+    }
 
     #[test]
     fn position_display() {
@@ -222,7 +410,8 @@ mod tests {
         let entry_price = QuoteCurrency::from(Decimal::try_from_scaled(9584_23, 2).unwrap());
 
         let notional = QuoteCurrency::convert_from(qty, entry_price);
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec(leverage);
+        let init_margin_req = contract_spec.init_margin_req();
         let mut balances = Balances::new(QuoteCurrency::new(10000, 0));
         let init_margin = notional * init_margin_req;
         assert!(balances.try_reserve_position_margin(init_margin));
@@ -230,7 +419,7 @@ mod tests {
         let mut pos = Position::Short(PositionInner::new(qty, entry_price));
 
         let exit_price = QuoteCurrency::new(30204_27, 2);
-        pos.change(qty, exit_price, Side::Buy, &mut balances, init_margin_req);
+        pos.change(qty, exit_price, Side::Buy, &mut balances, &contract_spec);
         assert_eq!(pos, Position::Neutral);
         assert_eq!(
             balances,
@@ -254,13 +443,48 @@ mod tests {
         let filled_qty = BaseCurrency::new(16800, 5);
         let fill_price = QuoteCurrency::new(6001260000, 5);
         let mut balances = Balances::new(QuoteCurrency::new(1000, 0));
-        let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+        let contract_spec = contract_spec(leverage);
         pos.change(
             filled_qty,
             fill_price,
             Side::Sell,
             &mut balances,
-            init_margin_req,
+            &contract_spec,
+        );
+    }
+
+    #[test_case::test_matrix([1, 5, 10])]
+    fn position_funding_payment_linear(qty: i64) {
+        let qty = BaseCurrency::<i64, 5>::new(qty, 0);
+        let mark_price = QuoteCurrency::new(100, 0);
+        let rate = Decimal::try_from_scaled(1, 4).unwrap();
+        let expected = QuoteCurrency::convert_from(qty, mark_price) * rate;
+
+        assert_eq!(
+            Position::Neutral.funding_payment(rate, mark_price),
+            QuoteCurrency::zero()
+        );
+        let long = Position::Long(PositionInner::new(qty, QuoteCurrency::new(90, 0)));
+        assert_eq!(long.funding_payment(rate, mark_price), expected);
+        let short = Position::Short(PositionInner::new(qty, QuoteCurrency::new(90, 0)));
+        assert_eq!(short.funding_payment(rate, mark_price), -expected);
+    }
+
+    #[test]
+    fn position_funding_payment_inverse() {
+        let qty = QuoteCurrency::<i64, 5>::new(1000, 0);
+        let mark_price = QuoteCurrency::new(200, 0);
+        let rate = Decimal::try_from_scaled(-1, 3).unwrap();
+
+        let long = Position::Long(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+        assert_eq!(
+            long.funding_payment(rate, mark_price),
+            BaseCurrency::new(-5, 3)
+        );
+        let short = Position::Short(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+        assert_eq!(
+            short.funding_payment(rate, mark_price),
+            BaseCurrency::new(5, 3)
         );
     }
 
@@ -281,7 +505,8 @@ mod tests {
         fn position_change_proptest_neutral(qty in 1..100_i64, fill_price in 1..100_i64, leverage in 1..10_u8, do_buy in 0..2_i32) {
             let filled_qty = BaseCurrency::<i64, 5>::new(qty, 0);
             let fill_price = QuoteCurrency::new(fill_price, 0);
-            let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+            let contract_spec = contract_spec(leverage);
+            let init_margin_req = contract_spec.init_margin_req();
             let notional = QuoteCurrency::convert_from(filled_qty, fill_price);
             let margin = notional * init_margin_req;
 
@@ -299,7 +524,7 @@ mod tests {
                 fill_price,
                 side,
                 &mut balances,
-                init_margin_req,
+                &contract_spec,
             );
             match side {
                 Side::Buy => assert_eq!(position, Position::Long(PositionInner::new(filled_qty, fill_price))),
@@ -320,7 +545,8 @@ mod tests {
         fn position_change_proptest_long_sell(qty in 1..100_i64, fill_price in 1..100_i64, leverage in 1..10_u8) {
             let filled_qty = BaseCurrency::<i64, 5>::new(qty, 0);
             let fill_price = QuoteCurrency::new(fill_price, 0);
-            let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+            let contract_spec = contract_spec(leverage);
+            let init_margin_req = contract_spec.init_margin_req();
 
             let start_qty = BaseCurrency::new(50, 0);
             let mut position = Position::Long(PositionInner::new(start_qty, fill_price));
@@ -333,7 +559,7 @@ mod tests {
                 fill_price,
                 Side::Sell,
                 &mut balances,
-                init_margin_req,
+                &contract_spec,
             );
             let new_qty = (start_qty - filled_qty).abs();
             if filled_qty > start_qty {
@@ -359,7 +585,8 @@ mod tests {
         fn position_change_proptest_long_buy(qty in 1..50_i64, fill_price in 50..100_i64, leverage in 1..10_u8) {
             let filled_qty = BaseCurrency::<i64, 5>::new(qty, 0);
             let fill_price = QuoteCurrency::new(fill_price, 0);
-            let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+            let contract_spec = contract_spec(leverage);
+            let init_margin_req = contract_spec.init_margin_req();
 
             let start_qty = BaseCurrency::new(50, 0);
             let mut position = Position::Long(PositionInner::new(start_qty, fill_price));
@@ -372,7 +599,7 @@ mod tests {
                 fill_price,
                 Side::Buy,
                 &mut balances,
-                init_margin_req,
+                &contract_spec,
             );
             let new_qty = start_qty + filled_qty;
             assert_eq!(position, Position::Long(PositionInner::new(new_qty, fill_price)));
@@ -392,7 +619,8 @@ mod tests {
         fn position_change_proptest_short_buy(qty in 1..100_i64, fill_price in 1..100_i64, leverage in 1..10_u8) {
             let filled_qty = BaseCurrency::<i64, 5>::new(qty, 0);
             let fill_price = QuoteCurrency::new(fill_price, 0);
-            let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+            let contract_spec = contract_spec(leverage);
+            let init_margin_req = contract_spec.init_margin_req();
 
             let start_qty = BaseCurrency::new(50, 0);
             let mut position = Position::Short(PositionInner::new(start_qty, fill_price));
@@ -405,7 +633,7 @@ mod tests {
                 fill_price,
                 Side::Buy,
                 &mut balances,
-                init_margin_req,
+                &contract_spec,
             );
             let new_qty = (start_qty - filled_qty).abs();
             if filled_qty > start_qty {
@@ -431,7 +659,8 @@ mod tests {
         fn position_change_proptest_short_sell(qty in 1..50_i64, leverage in 1..10_u8) {
             let filled_qty = BaseCurrency::<i64, 5>::new(qty, 0);
             let fill_price = QuoteCurrency::new(100, 0);
-            let init_margin_req = Leverage::new(leverage).unwrap().init_margin_req();
+            let contract_spec = contract_spec(leverage);
+            let init_margin_req = contract_spec.init_margin_req();
 
             let start_qty = BaseCurrency::new(50, 0);
             let mut position = Position::Short(PositionInner::new(start_qty, fill_price));
@@ -444,7 +673,7 @@ mod tests {
                 fill_price,
                 Side::Sell,
                 &mut balances,
-                init_margin_req,
+                &contract_spec,
             );
             let new_qty = start_qty + filled_qty;
             assert_eq!(position, Position::Short(PositionInner::new(new_qty, fill_price)));
@@ -0,0 +1,317 @@
+use num::Zero;
+use tracing::trace;
+
+use super::{IsolatedMarginRiskEngine, RiskEngine};
+use crate::{
+    contract_specification::ContractSpecification,
+    market_state::MarketState,
+    order_margin::OrderMargin,
+    prelude::{Currency, Mon, Position, QuoteCurrency, RiskError},
+    types::{
+        Balances, LimitOrder, MarginCurrency, MarginMode, MarketOrder, Pending, Side, StopOrder,
+        UserOrderId,
+    },
+    utils::max,
+};
+
+/// Backs the position with the total equity of the account, being its balances plus the unrealized profit and loss.
+/// New orders are checked like with the `IsolatedMarginRiskEngine`, but the unrealized profit and loss adds to the available balance.
+#[derive(Debug, Clone)]
+pub struct CrossMarginRiskEngine<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    isolated: IsolatedMarginRiskEngine<I, D, BaseOrQuote>,
+}
+
+impl<I, const D: u8, BaseOrQuote> CrossMarginRiskEngine<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new instance with the margin requirements and fees of the `contract_spec`.
+    pub fn new(contract_spec: ContractSpecification<I, D, BaseOrQuote>) -> Self {
+        Self {
+            isolated: IsolatedMarginRiskEngine::new(contract_spec),
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> CrossMarginRiskEngine<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+{
+    /// The balance available for new orders, including the unrealized profit and loss of the `position`.
+    /// The `unreserved_margin` is margin which the unrealized profits already back,
+    /// as it exceeded the `available` balance when it was reserved.
+    fn available_balance(
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        available: BaseOrQuote::PairedCurrency,
+        unreserved_margin: BaseOrQuote::PairedCurrency,
+    ) -> BaseOrQuote::PairedCurrency {
+        available + position.marked_unrealized_pnl(market_state) - unreserved_margin
+    }
+
+    /// The position margin which is not reserved in the `balances`.
+    fn unreserved_position_margin(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> BaseOrQuote::PairedCurrency {
+        max(
+            self.isolated
+                .contract_spec()
+                .init_margin(position.total_cost())
+                - balances.position_margin(),
+            BaseOrQuote::PairedCurrency::zero(),
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> RiskEngine<I, D, BaseOrQuote, UserOrderIdT>
+    for CrossMarginRiskEngine<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    fn check_market_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        fill_price: QuoteCurrency<I, D>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        let unreserved_order_margin = max(
+            order_margin.tiered_order_margin(self.isolated.contract_spec(), position)
+                - balances.order_margin(),
+            BaseOrQuote::PairedCurrency::zero(),
+        );
+        self.isolated.check_market_order_against(
+            position,
+            order,
+            fill_price,
+            Self::available_balance(
+                market_state,
+                position,
+                balances.available(),
+                unreserved_order_margin,
+            ),
+            balances.position_margin(),
+        )
+    }
+
+    fn check_limit_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        self.isolated.check_limit_order_against(
+            position,
+            order,
+            Self::available_balance(
+                market_state,
+                position,
+                balances.available(),
+                self.unreserved_position_margin(position, balances),
+            ),
+            balances.order_margin(),
+            order_margin,
+        )
+    }
+
+    fn check_stop_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        self.isolated.check_stop_order_against(
+            position,
+            order,
+            Self::available_balance(
+                market_state,
+                position,
+                balances.available(),
+                self.unreserved_position_margin(position, balances),
+            ),
+            balances.order_margin(),
+            order_margin,
+        )
+    }
+
+    fn check_maintenance_margin(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Result<(), RiskError> {
+        let (quantity, mark_price) = match position {
+            Position::Neutral => return Ok(()),
+            Position::Long(inner) => (
+                inner.quantity(),
+                market_state.mark_to_market_price(Side::Sell),
+            ),
+            Position::Short(inner) => (
+                inner.quantity(),
+                market_state.mark_to_market_price(Side::Buy),
+            ),
+        };
+        let equity = balances.sum() + position.marked_unrealized_pnl(market_state);
+        let notional = BaseOrQuote::PairedCurrency::convert_from(quantity, mark_price);
+        let maint_margin = notional
+            * self
+                .isolated
+                .contract_spec()
+                .maintenance_margin_for(notional);
+        trace!("equity: {equity}, maint_margin: {maint_margin}");
+        if equity < maint_margin {
+            return Err(RiskError::Liquidate);
+        }
+
+        Ok(())
+    }
+
+    fn check_leverage(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<(), RiskError> {
+        IsolatedMarginRiskEngine::check_leverage_against(
+            position,
+            order_margin,
+            contract_spec,
+            balances.sum() + position.marked_unrealized_pnl(market_state),
+        )
+    }
+
+    fn update_contract_spec(&mut self, contract_spec: &ContractSpecification<I, D, BaseOrQuote>) {
+        RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::update_contract_spec(
+            &mut self.isolated,
+            contract_spec,
+        );
+    }
+
+    fn liquidation_price(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        position.liquidation_price(
+            MarginMode::Cross,
+            self.isolated.contract_spec(),
+            balances,
+            market_state,
+        )
+    }
+
+    fn bankruptcy_price(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        position.bankruptcy_price(MarginMode::Cross, balances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_decimal::Decimal;
+
+    use super::*;
+    use crate::{DECIMALS, prelude::*, test_fee_maker, test_fee_taker};
+
+    type Engine = CrossMarginRiskEngine<i64, DECIMALS, BaseCurrency<i64, DECIMALS>>;
+
+    fn market_state(bid: i64) -> MarketState<i64, DECIMALS> {
+        let ask = QuoteCurrency::new(bid + 1, 0);
+        MarketState::from_components(QuoteCurrency::new(bid, 0), ask, ask, 0.into(), 0)
+    }
+
+    #[test_case::test_case(84, true)]
+    #[test_case::test_case(83, false)]
+    fn cross_margin_check_maintenance_margin_long(bid: i64, keeps_position: bool) {
+        let contract_spec = ContractSpecification::new(
+            leverage!(5),
+            Decimal::try_from_scaled(5, 1).unwrap(),
+            PriceFilter::default(),
+            QuantityFilter::default(),
+            test_fee_maker(),
+            test_fee_taker(),
+        )
+        .unwrap();
+        let re = Engine::new(contract_spec);
+        // The isolated position would be liquidated below 90.
+        let position = Position::Long(PositionInner::new(
+            BaseCurrency::new(40, 0),
+            QuoteCurrency::new(100, 0),
+        ));
+        let balances = Balances::new(QuoteCurrency::new(1000, 0));
+
+        let result = RiskEngine::<_, DECIMALS, _, NoUserOrderId>::check_maintenance_margin(
+            &re,
+            &market_state(bid),
+            &position,
+            &balances,
+        );
+        if keeps_position {
+            result.unwrap();
+        } else {
+            assert_eq!(result, Err(RiskError::Liquidate));
+        }
+        RiskEngine::<_, DECIMALS, _, NoUserOrderId>::check_maintenance_margin(
+            &re,
+            &market_state(bid),
+            &Position::Neutral,
+            &balances,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cross_margin_available_balance() {
+        let position = Position::Long(PositionInner::new(
+            BaseCurrency::new(40, 0),
+            QuoteCurrency::new(100, 0),
+        ));
+        let available = QuoteCurrency::new(1000, 0);
+        let zero = QuoteCurrency::zero();
+        assert_eq!(
+            Engine::available_balance(&market_state(95), &position, available, zero),
+            QuoteCurrency::new(800, 0)
+        );
+        assert_eq!(
+            Engine::available_balance(&market_state(105), &position, available, zero),
+            QuoteCurrency::new(1200, 0)
+        );
+        assert_eq!(
+            Engine::available_balance(
+                &market_state(105),
+                &position,
+                available,
+                QuoteCurrency::new(150, 0)
+            ),
+            QuoteCurrency::new(1050, 0)
+        );
+        assert_eq!(
+            Engine::available_balance(&market_state(95), &Position::Neutral, available, zero),
+            available
+        );
+    }
+}
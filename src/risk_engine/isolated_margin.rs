@@ -7,11 +7,16 @@ use crate::{
     market_state::MarketState,
     order_margin::OrderMargin,
     prelude::{Currency, Mon, Position, PositionInner, QuoteCurrency, RiskError},
-    types::{Balances, LimitOrder, MarginCurrency, MarketOrder, Pending, Side, UserOrderId},
+    types::{
+        Balances, LimitOrder, MarginCurrency, MarginMode, MarketOrder, Pending, Side, StopOrder,
+        UserOrderId,
+    },
 };
 
+/// Backs the position only with the margin reserved for it,
+/// so it is liquidated once the price moves past its liquidation price.
 #[derive(Debug, Clone)]
-pub(crate) struct IsolatedMarginRiskEngine<I, const D: u8, BaseOrQuote>
+pub struct IsolatedMarginRiskEngine<I, const D: u8, BaseOrQuote>
 where
     I: Mon<D>,
     BaseOrQuote: Currency<I, D>,
@@ -24,9 +29,15 @@ where
     I: Mon<D>,
     BaseOrQuote: Currency<I, D>,
 {
-    pub(crate) fn new(contract_spec: ContractSpecification<I, D, BaseOrQuote>) -> Self {
+    /// Create a new instance with the margin requirements and fees of the `contract_spec`.
+    pub fn new(contract_spec: ContractSpecification<I, D, BaseOrQuote>) -> Self {
         Self { contract_spec }
     }
+
+    #[inline(always)]
+    pub(super) fn contract_spec(&self) -> &ContractSpecification<I, D, BaseOrQuote> {
+        &self.contract_spec
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote, UserOrderIdT> RiskEngine<I, D, BaseOrQuote, UserOrderIdT>
@@ -39,61 +50,79 @@ where
 {
     fn check_market_order(
         &self,
+        _market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
         order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         fill_price: QuoteCurrency<I, D>,
         balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        _order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
     ) -> Result<(), RiskError> {
-        match order.side() {
-            Side::Buy => self.check_market_buy_order(position, order, fill_price, balances),
-            Side::Sell => self.check_market_sell_order(position, order, fill_price, balances),
-        }
+        self.check_market_order_against(
+            position,
+            order,
+            fill_price,
+            balances.available(),
+            balances.position_margin(),
+        )
     }
 
     fn check_limit_order(
         &self,
+        _market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
         order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
-        available_balance: BaseOrQuote::PairedCurrency,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
         order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
     ) -> Result<(), RiskError> {
-        let om = order_margin.order_margin(self.contract_spec.init_margin_req(), position);
-        let new_order_margin = order_margin.order_margin_with_order(
-            order,
-            self.contract_spec.init_margin_req(),
+        self.check_limit_order_against(
             position,
-        );
-
-        trace!(
-            "order_margin: {om:?}, new_order_margin: {new_order_margin:?}, available_balance: {available_balance:?}"
-        );
-        if new_order_margin > available_balance + om {
-            return Err(RiskError::NotEnoughAvailableBalance);
-        }
+            order,
+            balances.available(),
+            balances.order_margin(),
+            order_margin,
+        )
+    }
 
-        Ok(())
+    fn check_stop_order(
+        &self,
+        _market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        self.check_stop_order_against(
+            position,
+            order,
+            balances.available(),
+            balances.order_margin(),
+            order_margin,
+        )
     }
 
     fn check_maintenance_margin(
         &self,
         market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
     ) -> Result<(), RiskError> {
-        let maint_margin_req = self.contract_spec.maintenance_margin();
+        let Some(liquidation_price) = position.liquidation_price(
+            MarginMode::Isolated,
+            &self.contract_spec,
+            balances,
+            market_state,
+        ) else {
+            return Ok(());
+        };
         match position {
             Position::Neutral => return Ok(()),
-            Position::Long(inner) => {
-                let liquidation_price =
-                    inner.entry_price().liquidation_price_long(maint_margin_req);
-                if market_state.bid() < liquidation_price {
+            Position::Long(_) => {
+                if market_state.mark_to_market_price(Side::Sell) < liquidation_price {
                     return Err(RiskError::Liquidate);
                 }
             }
-            Position::Short(inner) => {
-                let liquidation_price = inner
-                    .entry_price()
-                    .liquidation_price_short(maint_margin_req);
-                if market_state.ask() > liquidation_price {
+            Position::Short(_) => {
+                if market_state.mark_to_market_price(Side::Buy) > liquidation_price {
                     return Err(RiskError::Liquidate);
                 }
             }
@@ -101,6 +130,43 @@ where
 
         Ok(())
     }
+
+    fn check_leverage(
+        &self,
+        _market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<(), RiskError> {
+        Self::check_leverage_against(position, order_margin, contract_spec, balances.sum())
+    }
+
+    fn update_contract_spec(&mut self, contract_spec: &ContractSpecification<I, D, BaseOrQuote>) {
+        self.contract_spec = contract_spec.clone();
+    }
+
+    fn liquidation_price(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        position.liquidation_price(
+            MarginMode::Isolated,
+            &self.contract_spec,
+            balances,
+            market_state,
+        )
+    }
+
+    fn bankruptcy_price(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        position.bankruptcy_price(MarginMode::Isolated, balances)
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote> IsolatedMarginRiskEngine<I, D, BaseOrQuote>
@@ -109,12 +175,131 @@ where
     BaseOrQuote: Currency<I, D>,
     BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
 {
+    /// Checks a new market order against the `available` balance,
+    /// along with the `position_margin` that is released when the order flips the position.
+    pub(super) fn check_market_order_against<UserOrderIdT>(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        fill_price: QuoteCurrency<I, D>,
+        available: BaseOrQuote::PairedCurrency,
+        position_margin: BaseOrQuote::PairedCurrency,
+    ) -> Result<(), RiskError>
+    where
+        UserOrderIdT: UserOrderId,
+    {
+        if order.reduce_only() {
+            Self::check_reduce_only(position, order.side(), order.quantity())?;
+        }
+        match order.side() {
+            Side::Buy => {
+                self.check_market_buy_order(position, order, fill_price, available, position_margin)
+            }
+            Side::Sell => self.check_market_sell_order(
+                position,
+                order,
+                fill_price,
+                available,
+                position_margin,
+            ),
+        }
+    }
+
+    /// Checks a new limit order against the `available` balance,
+    /// along with the `reserved_order_margin` which already backs the other orders.
+    pub(super) fn check_limit_order_against<UserOrderIdT>(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        available: BaseOrQuote::PairedCurrency,
+        reserved_order_margin: BaseOrQuote::PairedCurrency,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError>
+    where
+        UserOrderIdT: UserOrderId,
+    {
+        if order.reduce_only() {
+            Self::check_reduce_only(position, order.side(), order.remaining_quantity())?;
+        }
+        self.check_risk_limit(
+            position,
+            order.side(),
+            order.remaining_quantity(),
+            order.limit_price(),
+        )?;
+        let new_order_margin =
+            order_margin.order_margin_with_order(order, &self.contract_spec, position);
+
+        trace!(
+            "reserved_order_margin: {reserved_order_margin:?}, new_order_margin: {new_order_margin:?}, available: {available:?}"
+        );
+        if new_order_margin > available + reserved_order_margin {
+            return Err(RiskError::NotEnoughAvailableBalance);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a new stop order like a limit order, see `check_limit_order_against`.
+    pub(super) fn check_stop_order_against<UserOrderIdT>(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        available: BaseOrQuote::PairedCurrency,
+        reserved_order_margin: BaseOrQuote::PairedCurrency,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError>
+    where
+        UserOrderIdT: UserOrderId,
+    {
+        self.check_risk_limit(
+            position,
+            order.side(),
+            order.quantity(),
+            order.notional_price(),
+        )?;
+        let new_order_margin =
+            order_margin.order_margin_with_stop_order(order, &self.contract_spec, position);
+
+        trace!(
+            "reserved_order_margin: {reserved_order_margin:?}, new_order_margin: {new_order_margin:?}, available: {available:?}"
+        );
+        if new_order_margin > available + reserved_order_margin {
+            return Err(RiskError::NotEnoughAvailableBalance);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the `equity` covers the position margin and order margin required by the `contract_spec`.
+    pub(super) fn check_leverage_against<UserOrderIdT>(
+        position: &Position<I, D, BaseOrQuote>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+        equity: BaseOrQuote::PairedCurrency,
+    ) -> Result<(), RiskError>
+    where
+        UserOrderIdT: UserOrderId,
+    {
+        let new_position_margin = contract_spec.init_margin(position.total_cost());
+        let new_order_margin = order_margin.tiered_order_margin(contract_spec, position);
+        trace!(
+            "new_position_margin: {new_position_margin:?}, new_order_margin: {new_order_margin:?}, equity: {equity:?}"
+        );
+        if new_position_margin + new_order_margin > equity {
+            return Err(RiskError::NotEnoughAvailableBalance);
+        }
+
+        Ok(())
+    }
+
     fn check_market_buy_order<UserOrderIdT>(
         &self,
         position: &Position<I, D, BaseOrQuote>,
         order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         fill_price: QuoteCurrency<I, D>,
-        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        available: BaseOrQuote::PairedCurrency,
+        position_margin: BaseOrQuote::PairedCurrency,
     ) -> Result<(), RiskError>
     where
         UserOrderIdT: UserOrderId,
@@ -126,10 +311,11 @@ where
                 // A long position increases in size.
                 let notional_value =
                     BaseOrQuote::PairedCurrency::convert_from(order.quantity(), fill_price);
-                let init_margin = notional_value * self.contract_spec.init_margin_req();
+                let init_margin =
+                    self.increased_position_margin(position, notional_value, position_margin)?;
 
                 let fee = notional_value * *self.contract_spec.fee_taker().as_ref();
-                if init_margin + fee > balances.available() {
+                if init_margin + fee > available {
                     return Err(RiskError::NotEnoughAvailableBalance);
                 }
             }
@@ -139,24 +325,23 @@ where
                     return Ok(());
                 }
                 // The order reduces the short and puts on a long
-                let released_from_old_pos = balances.position_margin();
+                let released_from_old_pos = position_margin;
 
                 let new_long_size = Self::quantity_minus_position(order.quantity(), pos_inner);
                 assert2::debug_assert!(new_long_size > BaseOrQuote::zero());
                 let new_notional_value =
                     BaseOrQuote::PairedCurrency::convert_from(new_long_size, fill_price);
                 assert2::debug_assert!(new_notional_value > BaseOrQuote::PairedCurrency::zero());
-                let new_init_margin = new_notional_value * self.contract_spec.init_margin_req();
+                if self.contract_spec.exceeds_risk_limit(new_notional_value) {
+                    return Err(RiskError::RiskLimitExceeded);
+                }
+                let new_init_margin = self.contract_spec.init_margin(new_notional_value);
                 assert2::debug_assert!(new_init_margin > BaseOrQuote::PairedCurrency::zero());
 
                 let fee = new_notional_value * *self.contract_spec.fee_taker().as_ref();
 
-                if Self::margin_exceeds_risk(
-                    new_init_margin,
-                    fee,
-                    balances.available(),
-                    released_from_old_pos,
-                ) {
+                if Self::margin_exceeds_risk(new_init_margin, fee, available, released_from_old_pos)
+                {
                     return Err(RiskError::NotEnoughAvailableBalance);
                 }
             }
@@ -170,7 +355,8 @@ where
         position: &Position<I, D, BaseOrQuote>,
         order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         fill_price: QuoteCurrency<I, D>,
-        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        available: BaseOrQuote::PairedCurrency,
+        position_margin: BaseOrQuote::PairedCurrency,
     ) -> Result<(), RiskError>
     where
         UserOrderIdT: UserOrderId,
@@ -181,10 +367,11 @@ where
             Position::Neutral | Position::Short(_) => {
                 let notional_value =
                     BaseOrQuote::PairedCurrency::convert_from(order.quantity(), fill_price);
-                let init_margin = notional_value * self.contract_spec.init_margin_req();
+                let init_margin =
+                    self.increased_position_margin(position, notional_value, position_margin)?;
                 let fee = notional_value * *self.contract_spec.fee_taker().as_ref();
 
-                if init_margin + fee > balances.available() {
+                if init_margin + fee > available {
                     return Err(RiskError::NotEnoughAvailableBalance);
                 }
             }
@@ -195,24 +382,23 @@ where
                     return Ok(());
                 }
                 // The order reduces the long position and opens a short.
-                let released_from_old_pos = balances.position_margin();
+                let released_from_old_pos = position_margin;
 
                 let new_short_size = Self::quantity_minus_position(order.quantity(), pos_inner);
                 assert2::debug_assert!(new_short_size > BaseOrQuote::zero());
                 let new_notional_value =
                     BaseOrQuote::PairedCurrency::convert_from(new_short_size, fill_price);
                 assert2::debug_assert!(new_notional_value > BaseOrQuote::PairedCurrency::zero());
-                let new_init_margin = new_notional_value * self.contract_spec.init_margin_req();
+                if self.contract_spec.exceeds_risk_limit(new_notional_value) {
+                    return Err(RiskError::RiskLimitExceeded);
+                }
+                let new_init_margin = self.contract_spec.init_margin(new_notional_value);
                 assert2::debug_assert!(new_init_margin > BaseOrQuote::PairedCurrency::zero());
 
                 let fee = new_notional_value * *self.contract_spec.fee_taker().as_ref();
 
-                if Self::margin_exceeds_risk(
-                    new_init_margin,
-                    fee,
-                    balances.available(),
-                    released_from_old_pos,
-                ) {
+                if Self::margin_exceeds_risk(new_init_margin, fee, available, released_from_old_pos)
+                {
                     return Err(RiskError::NotEnoughAvailableBalance);
                 }
             }
@@ -220,6 +406,70 @@ where
         Ok(())
     }
 
+    /// The additional position margin required when a position grows by the `notional_value`,
+    /// as the whole position is margined according to the risk limit tier it grows into.
+    fn increased_position_margin(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        notional_value: BaseOrQuote::PairedCurrency,
+        position_margin: BaseOrQuote::PairedCurrency,
+    ) -> Result<BaseOrQuote::PairedCurrency, RiskError> {
+        let new_position_notional = position.total_cost() + notional_value;
+        if self.contract_spec.exceeds_risk_limit(new_position_notional) {
+            return Err(RiskError::RiskLimitExceeded);
+        }
+        let init_margin_req = self
+            .contract_spec
+            .init_margin_req_for(new_position_notional);
+        Ok(
+            notional_value * init_margin_req + position.total_cost() * init_margin_req
+                - position_margin,
+        )
+    }
+
+    /// Ensure the position stays within the risk limit once an order of `quantity` on `side` at `price` is filled.
+    fn check_risk_limit(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        side: Side,
+        quantity: BaseOrQuote,
+        price: QuoteCurrency<I, D>,
+    ) -> Result<(), RiskError> {
+        let new_position_notional = match (position, side) {
+            (Position::Neutral, _) => BaseOrQuote::PairedCurrency::convert_from(quantity, price),
+            (Position::Long(inner), Side::Buy) | (Position::Short(inner), Side::Sell) => {
+                inner.notional() + BaseOrQuote::PairedCurrency::convert_from(quantity, price)
+            }
+            (Position::Long(inner), Side::Sell) | (Position::Short(inner), Side::Buy) => {
+                if quantity <= inner.quantity() {
+                    // The order only reduces the position.
+                    return Ok(());
+                }
+                BaseOrQuote::PairedCurrency::convert_from(quantity - inner.quantity(), price)
+            }
+        };
+        if self.contract_spec.exceeds_risk_limit(new_position_notional) {
+            return Err(RiskError::RiskLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// A reduce-only order must be on the opposite side of the position and must not exceed its quantity.
+    fn check_reduce_only(
+        position: &Position<I, D, BaseOrQuote>,
+        side: Side,
+        quantity: BaseOrQuote,
+    ) -> Result<(), RiskError> {
+        if !position
+            .reducible_quantity(side)
+            .is_some_and(|reducible_qty| quantity <= reducible_qty)
+        {
+            return Err(RiskError::ReduceOnlyOrderWouldIncreasePosition);
+        }
+
+        Ok(())
+    }
+
     #[inline(always)]
     fn margin_exceeds_risk(
         new_margin_req: BaseOrQuote::PairedCurrency,
@@ -289,6 +539,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn isolated_margin_check_reduce_only() {
+        type Engine = IsolatedMarginRiskEngine<i64, 5, BaseCurrency<i64, 5>>;
+        let qty = BaseCurrency::new(5, 0);
+        let long = Position::Long(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+        let short = Position::Short(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+
+        Engine::check_reduce_only(&long, Side::Sell, qty).unwrap();
+        Engine::check_reduce_only(&short, Side::Buy, BaseCurrency::new(1, 0)).unwrap();
+        for (position, side, quantity) in [
+            (&long, Side::Buy, BaseCurrency::new(1, 0)),
+            (&long, Side::Sell, BaseCurrency::new(6, 0)),
+            (&short, Side::Sell, BaseCurrency::new(1, 0)),
+            (&short, Side::Buy, BaseCurrency::new(6, 0)),
+            (&Position::Neutral, Side::Buy, BaseCurrency::new(1, 0)),
+            (&Position::Neutral, Side::Sell, BaseCurrency::new(1, 0)),
+        ] {
+            assert_eq!(
+                Engine::check_reduce_only(position, side, quantity),
+                Err(RiskError::ReduceOnlyOrderWouldIncreasePosition)
+            );
+        }
+    }
+
     #[test_case::test_case(2, 75)]
     #[test_case::test_case(3, 84)]
     #[test_case::test_case(5, 90)]
@@ -319,6 +593,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
 
@@ -335,6 +610,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
 
@@ -350,6 +626,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
 
@@ -360,7 +637,8 @@ mod tests {
             RiskEngine::<_, DECIMALS, _, NoUserOrderId>::check_maintenance_margin(
                 &re,
                 &market_state,
-                &position
+                &position,
+                &Balances::new(QuoteCurrency::new(1000, 0)),
             ),
             Err(RiskError::Liquidate)
         );
@@ -371,6 +649,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
     }
@@ -406,6 +685,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
 
@@ -416,7 +696,8 @@ mod tests {
             RiskEngine::<i64, DECIMALS, _, NoUserOrderId>::check_maintenance_margin(
                 &re,
                 &market_state,
-                &position
+                &position,
+                &Balances::new(QuoteCurrency::new(1000, 0)),
             ),
             Err(RiskError::Liquidate)
         );
@@ -427,6 +708,7 @@ mod tests {
             &re,
             &market_state,
             &position,
+            &Balances::new(QuoteCurrency::new(1000, 0)),
         )
         .unwrap();
     }
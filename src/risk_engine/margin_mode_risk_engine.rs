@@ -0,0 +1,219 @@
+use super::{CrossMarginRiskEngine, IsolatedMarginRiskEngine, RiskEngine};
+use crate::{
+    contract_specification::ContractSpecification,
+    market_state::MarketState,
+    order_margin::OrderMargin,
+    prelude::{Currency, Mon, Position, QuoteCurrency, RiskError},
+    types::{
+        Balances, LimitOrder, MarginCurrency, MarginMode, MarketOrder, Pending, StopOrder,
+        UserOrderId,
+    },
+};
+
+/// The risk engine of the `MarginMode` selected in the `Config`, which `Exchange::new` uses.
+#[derive(Debug, Clone)]
+pub enum MarginModeRiskEngine<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// For `MarginMode::Isolated`.
+    Isolated(IsolatedMarginRiskEngine<I, D, BaseOrQuote>),
+    /// For `MarginMode::Cross`.
+    Cross(CrossMarginRiskEngine<I, D, BaseOrQuote>),
+}
+
+impl<I, const D: u8, BaseOrQuote> MarginModeRiskEngine<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create the risk engine of the `margin_mode`.
+    pub fn new(
+        margin_mode: MarginMode,
+        contract_spec: ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Self {
+        match margin_mode {
+            MarginMode::Isolated => Self::Isolated(IsolatedMarginRiskEngine::new(contract_spec)),
+            MarginMode::Cross => Self::Cross(CrossMarginRiskEngine::new(contract_spec)),
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> RiskEngine<I, D, BaseOrQuote, UserOrderIdT>
+    for MarginModeRiskEngine<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    fn check_market_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        fill_price: QuoteCurrency<I, D>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        match self {
+            Self::Isolated(re) => re.check_market_order(
+                market_state,
+                position,
+                order,
+                fill_price,
+                balances,
+                order_margin,
+            ),
+            Self::Cross(re) => re.check_market_order(
+                market_state,
+                position,
+                order,
+                fill_price,
+                balances,
+                order_margin,
+            ),
+        }
+    }
+
+    fn check_limit_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        match self {
+            Self::Isolated(re) => {
+                re.check_limit_order(market_state, position, order, balances, order_margin)
+            }
+            Self::Cross(re) => {
+                re.check_limit_order(market_state, position, order, balances, order_margin)
+            }
+        }
+    }
+
+    fn check_stop_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError> {
+        match self {
+            Self::Isolated(re) => {
+                re.check_stop_order(market_state, position, order, balances, order_margin)
+            }
+            Self::Cross(re) => {
+                re.check_stop_order(market_state, position, order, balances, order_margin)
+            }
+        }
+    }
+
+    fn check_maintenance_margin(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Result<(), RiskError> {
+        match self {
+            Self::Isolated(re) => {
+                RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::check_maintenance_margin(
+                    re,
+                    market_state,
+                    position,
+                    balances,
+                )
+            }
+            Self::Cross(re) => {
+                RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::check_maintenance_margin(
+                    re,
+                    market_state,
+                    position,
+                    balances,
+                )
+            }
+        }
+    }
+
+    fn check_leverage(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
+    ) -> Result<(), RiskError> {
+        match self {
+            Self::Isolated(re) => re.check_leverage(
+                market_state,
+                position,
+                balances,
+                order_margin,
+                contract_spec,
+            ),
+            Self::Cross(re) => re.check_leverage(
+                market_state,
+                position,
+                balances,
+                order_margin,
+                contract_spec,
+            ),
+        }
+    }
+
+    fn update_contract_spec(&mut self, contract_spec: &ContractSpecification<I, D, BaseOrQuote>) {
+        match self {
+            Self::Isolated(re) => {
+                RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::update_contract_spec(
+                    re,
+                    contract_spec,
+                )
+            }
+            Self::Cross(re) => RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::update_contract_spec(
+                re,
+                contract_spec,
+            ),
+        }
+    }
+
+    fn liquidation_price(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        match self {
+            Self::Isolated(re) => RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::liquidation_price(
+                re,
+                market_state,
+                position,
+                balances,
+            ),
+            Self::Cross(re) => RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::liquidation_price(
+                re,
+                market_state,
+                position,
+                balances,
+            ),
+        }
+    }
+
+    fn bankruptcy_price(
+        &self,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        match self {
+            Self::Isolated(re) => RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::bankruptcy_price(
+                re, position, balances,
+            ),
+            Self::Cross(re) => RiskEngine::<I, D, BaseOrQuote, UserOrderIdT>::bankruptcy_price(
+                re, position, balances,
+            ),
+        }
+    }
+}
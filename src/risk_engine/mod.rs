@@ -12,8 +12,12 @@
 //!    The risk engine enforces position limits on each futures contract to prevent excessive speculation and manipulation of prices.
 //!    Position limits are set by the exchange and restrict the maximum number of contracts that any trader can hold for a particular futures contract.
 
+mod cross_margin;
 mod isolated_margin;
+mod margin_mode_risk_engine;
 mod risk_engine_trait;
 
-pub(crate) use isolated_margin::IsolatedMarginRiskEngine;
-pub(crate) use risk_engine_trait::RiskEngine;
+pub use cross_margin::CrossMarginRiskEngine;
+pub use isolated_margin::IsolatedMarginRiskEngine;
+pub use margin_mode_risk_engine::MarginModeRiskEngine;
+pub use risk_engine_trait::RiskEngine;
@@ -1,11 +1,15 @@
 use crate::{
+    contract_specification::ContractSpecification,
     market_state::MarketState,
     order_margin::OrderMargin,
     prelude::{Currency, Mon, Position, QuoteCurrency, RiskError},
-    types::{Balances, LimitOrder, MarginCurrency, MarketOrder, Pending, UserOrderId},
+    types::{Balances, LimitOrder, MarginCurrency, MarketOrder, Pending, StopOrder, UserOrderId},
 };
 
-pub(crate) trait RiskEngine<I, const D: u8, BaseOrQuote, UserOrderIdT>
+/// Checks whether the account can satisfy the margin requirements of new orders and keep its position open.
+/// Implement it to supply custom risk rules to `Exchange::with_risk_engine`,
+/// for example by wrapping one of the provided risk engines.
+pub trait RiskEngine<I, const D: u8, BaseOrQuote, UserOrderIdT>
 where
     I: Mon<D>,
     BaseOrQuote: Currency<I, D>,
@@ -28,18 +32,32 @@ where
     /// If Err, the account cannot satisfy the margin requirements.
     fn check_market_order(
         &self,
+        market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
         order: &MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
         fill_price: QuoteCurrency<I, D>,
         balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
     ) -> Result<(), RiskError>;
 
     /// Checks if the account it able to satisfy the margin requirements for a new limit order.
     fn check_limit_order(
         &self,
+        market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
         order: &LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
-        available_wallet_balance: BaseOrQuote::PairedCurrency,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+    ) -> Result<(), RiskError>;
+
+    /// Checks if the account it able to satisfy the margin requirements for a new stop order.
+    /// The stop order reserves order margin until it is either triggered or cancelled.
+    fn check_stop_order(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        order: &StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
         order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
     ) -> Result<(), RiskError>;
 
@@ -49,7 +67,8 @@ where
     ///
     /// # Arguments:
     /// `market_state`: The current market information.
-    /// `account`: The user account.
+    /// `position`: The current position of the user.
+    /// `balances`: The balances of the user.
     ///
     /// # Returns:
     /// If Err, the account must be liquidated.
@@ -57,5 +76,48 @@ where
         &self,
         market_state: &MarketState<I, D>,
         position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Result<(), RiskError>;
+
+    /// Checks if the account is able to satisfy the margin requirements of the `contract_spec` with a changed leverage,
+    /// before `Exchange::set_leverage` adopts it with `update_contract_spec`.
+    ///
+    /// # Returns:
+    /// If Err, the account cannot satisfy the margin requirements of the position and orders with the new leverage.
+    fn check_leverage(
+        &self,
+        market_state: &MarketState<I, D>,
+        position: &Position<I, D, BaseOrQuote>,
+        balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+        order_margin: &OrderMargin<I, D, BaseOrQuote, UserOrderIdT>,
+        contract_spec: &ContractSpecification<I, D, BaseOrQuote>,
     ) -> Result<(), RiskError>;
+
+    /// Adopt the changed `contract_spec`, e.g. after `Exchange::set_leverage`.
+    /// Risk engines which don't depend on the contract specification can ignore it.
+    fn update_contract_spec(&mut self, _contract_spec: &ContractSpecification<I, D, BaseOrQuote>) {}
+
+    /// The mark-to-market price at which `check_maintenance_margin` liquidates the `position`,
+    /// which `Exchange::liquidation_price` reports.
+    /// Risk engines which can not tell it in advance keep the default of `None`.
+    fn liquidation_price(
+        &self,
+        _market_state: &MarketState<I, D>,
+        _position: &Position<I, D, BaseOrQuote>,
+        _balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        None
+    }
+
+    /// The price at which the losses of the `position` exhaust the margin backing it,
+    /// which `Exchange::bankruptcy_price` reports and liquidations are settled against.
+    /// Risk engines which can not tell it keep the default of `None`,
+    /// in which case liquidations are filled at the market price.
+    fn bankruptcy_price(
+        &self,
+        _position: &Position<I, D, BaseOrQuote>,
+        _balances: &Balances<I, D, BaseOrQuote::PairedCurrency>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        None
+    }
 }
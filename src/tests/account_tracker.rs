@@ -0,0 +1,69 @@
+use crate::{
+    mock_exchange_linear, mock_exchange_linear_with_account_tracker, prelude::*,
+    tests::test_utils::test_bba, types::NANOS_PER_SECOND,
+};
+
+#[test]
+fn account_tracker_disabled() {
+    let exchange = mock_exchange_linear();
+    assert!(exchange.account_tracker().is_none());
+}
+
+#[test]
+fn account_tracker_config() {
+    let config = mock_exchange_linear().config().clone();
+    assert_eq!(
+        config.clone().with_account_tracker(0.into()).unwrap_err(),
+        ConfigError::InvalidSampleInterval
+    );
+    let config = config
+        .with_account_tracker(NANOS_PER_SECOND.into())
+        .unwrap();
+    assert_eq!(
+        config.account_tracker_interval_ns(),
+        Some(NANOS_PER_SECOND.into())
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn account_tracker_round_trip() {
+    let mut exchange = mock_exchange_linear_with_account_tracker(QuoteCurrency::new(1000, 0));
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(&test_bba(109, 110, NANOS_PER_SECOND))
+        .unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    // The gap of a second without market updates is filled with the latest equity.
+    exchange
+        .update_state(&test_bba(109, 110, 3 * NANOS_PER_SECOND))
+        .unwrap();
+
+    let tracker = exchange.account_tracker().unwrap();
+    assert_eq!(tracker.sample_interval_ns(), NANOS_PER_SECOND.into());
+    let equity_curve = tracker.equity_curve();
+    assert_eq!(equity_curve.len(), 4);
+    assert_eq!(equity_curve[0], (0.into(), QuoteCurrency::new(1000, 0)));
+    assert_eq!(equity_curve[2].1, QuoteCurrency::new(1044373, 3));
+    assert_eq!(
+        equity_curve[3],
+        (
+            (3 * NANOS_PER_SECOND).into(),
+            QuoteCurrency::new(1044373, 3)
+        )
+    );
+
+    // 500 bought at 100 and 545 sold at 109.
+    assert_eq!(tracker.traded_notional(), QuoteCurrency::new(1045, 0));
+    assert_eq!(tracker.fees_paid(), QuoteCurrency::new(627, 3));
+    assert_eq!(tracker.num_winning_trades(), 1);
+    assert_eq!(tracker.num_losing_trades(), 0);
+    assert_eq!(tracker.win_rate(), Some(1.0));
+    assert_eq!(tracker.max_drawdown(), 0.0);
+    assert!(tracker.sharpe_ratio().is_some());
+}
@@ -0,0 +1,95 @@
+use const_decimal::Decimal;
+
+use crate::{
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+/// The liquidation of a bankrupt counterparty on `side` of 4 contracts at 112.
+fn auto_deleverage(side: Side) -> AutoDeleverage<i64, 5, BaseCurrency<i64, 5>> {
+    AutoDeleverage {
+        side,
+        quantity: BaseCurrency::new(4, 0),
+        bankruptcy_price: QuoteCurrency::new(112, 0),
+        timestamp_exchange_ns: 2.into(),
+    }
+}
+
+fn adl_model(min_ranking: i64) -> AdlModel<i64, 5> {
+    AdlModel::new(
+        AdlRankingIndicator::PnlTimesLeverage,
+        Decimal::try_from_scaled(min_ranking, 2).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn auto_deleverage_profitable_position() {
+    // The long of 10 entered at 100 has an unrealized profit of 90 at a bid of 109.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config.with_adl_model(adl_model(40)),
+    );
+    exchange.update_state(&test_bba(109, 110, 1)).unwrap();
+    exchange.update_state(&auto_deleverage(Side::Buy)).unwrap();
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(6, 0));
+    let OrderEvent::AutoDeleveraged { order, ranking } = &exchange.order_events()[0] else {
+        panic!("Expected an auto-deleveraging");
+    };
+    assert_eq!(order.side(), Side::Sell);
+    assert_eq!(order.quantity(), BaseCurrency::new(4, 0));
+    assert_eq!(order.state().avg_fill_price(), QuoteCurrency::new(112, 0));
+    // The unrealized profit of 90 relative to the position margin of 200.
+    assert_eq!(*ranking, Decimal::try_from_scaled(45, 2).unwrap());
+    // The realized profit of 48 without any fee.
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(9274, 1))
+            .position_margin(QuoteCurrency::new(120, 0))
+            .order_margin(QuoteCurrency::zero())
+            .total_fees_paid(QuoteCurrency::new(6, 1))
+            .build()
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn auto_deleverage_skipped() {
+    // Without an `AdlModel` the counterparty liquidations are ignored.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(109, 110, 1)).unwrap();
+    exchange.update_state(&auto_deleverage(Side::Buy)).unwrap();
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(10, 0));
+
+    // A bankrupt long does not deleverage other longs.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config.with_adl_model(adl_model(40)),
+    );
+    exchange.update_state(&test_bba(109, 110, 1)).unwrap();
+    exchange.update_state(&auto_deleverage(Side::Sell)).unwrap();
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(10, 0));
+
+    // The position ranks behind the simulated counterparties.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config.with_adl_model(adl_model(50)),
+    );
+    exchange.update_state(&test_bba(109, 110, 1)).unwrap();
+    exchange.update_state(&auto_deleverage(Side::Buy)).unwrap();
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(10, 0));
+    assert!(exchange.order_events().is_empty());
+}
@@ -0,0 +1,123 @@
+use crate::{
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+#[test]
+#[tracing_test::traced_test]
+fn cross_margin_keeps_position_past_isolated_liquidation_price() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    assert_eq!(
+        exchange.update_state(&test_bba(85, 86, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+
+    // The equity of 997.6 - 600 still covers the maintenance margin of 340.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(85, 86, 1)).unwrap();
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(40, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn cross_margin_liquidates_once_equity_falls_below_maintenance_margin() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(84, 85, 1)).unwrap();
+
+    // The equity of 997.6 - 680 no longer covers the maintenance margin of 332.
+    assert_eq!(
+        exchange.update_state(&test_bba(83, 84, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.balances().sum() < QuoteCurrency::new(320, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn cross_margin_unrealized_loss_reduces_available_balance() {
+    let order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(94, 0),
+        BaseCurrency::new(1, 0),
+    )
+    .unwrap();
+
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(95, 96, 1)).unwrap();
+    exchange.submit_limit_order(order.clone()).unwrap();
+
+    // The unrealized loss of 200 exceeds the available balance of 197.6.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(95, 96, 1)).unwrap();
+    assert_eq!(
+        exchange.submit_limit_order(order),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn cross_margin_unrealized_profit_backs_new_orders() {
+    let order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(105, 0),
+        BaseCurrency::new(10, 0),
+    )
+    .unwrap();
+
+    // The order margin of 210 exceeds the available balance of 197.6.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(110, 111, 1)).unwrap();
+    assert_eq!(
+        exchange.submit_limit_order(order.clone()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+
+    // The unrealized profit of 400 backs the order margin the available balance can not cover.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(110, 111, 1)).unwrap();
+    exchange.submit_limit_order(order).unwrap();
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(exchange.balances().available(), QuoteCurrency::zero());
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(1976, 1)
+    );
+}
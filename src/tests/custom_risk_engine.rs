@@ -0,0 +1,234 @@
+use const_decimal::Decimal;
+use num_traits::Signed;
+
+use crate::{
+    mock_exchange_linear,
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+/// Rejects orders which would grow the position beyond a maximum notional value,
+/// on top of the checks of the `IsolatedMarginRiskEngine`.
+#[derive(Debug, Clone)]
+struct MaxNotionalRiskEngine {
+    inner: IsolatedMarginRiskEngine<i64, 5, BaseCurrency<i64, 5>>,
+    max_notional: QuoteCurrency<i64, 5>,
+}
+
+impl MaxNotionalRiskEngine {
+    fn check_notional(
+        &self,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        side: Side,
+        quantity: BaseCurrency<i64, 5>,
+        price: QuoteCurrency<i64, 5>,
+    ) -> Result<(), RiskError> {
+        let signed_qty = match side {
+            Side::Buy => position.quantity() + quantity,
+            Side::Sell => position.quantity() - quantity,
+        };
+        if QuoteCurrency::convert_from(signed_qty.abs(), price) > self.max_notional {
+            return Err(RiskError::NotEnoughAvailableBalance);
+        }
+        Ok(())
+    }
+}
+
+impl RiskEngine<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId> for MaxNotionalRiskEngine {
+    fn check_market_order(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        order: &MarketOrder<
+            i64,
+            5,
+            BaseCurrency<i64, 5>,
+            NoUserOrderId,
+            Pending<i64, 5, BaseCurrency<i64, 5>>,
+        >,
+        fill_price: QuoteCurrency<i64, 5>,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+        order_margin: &OrderMargin<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+    ) -> Result<(), RiskError> {
+        self.check_notional(position, order.side(), order.quantity(), fill_price)?;
+        self.inner.check_market_order(
+            market_state,
+            position,
+            order,
+            fill_price,
+            balances,
+            order_margin,
+        )
+    }
+
+    fn check_limit_order(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        order: &LimitOrder<
+            i64,
+            5,
+            BaseCurrency<i64, 5>,
+            NoUserOrderId,
+            Pending<i64, 5, BaseCurrency<i64, 5>>,
+        >,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+        order_margin: &OrderMargin<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+    ) -> Result<(), RiskError> {
+        self.check_notional(
+            position,
+            order.side(),
+            order.remaining_quantity(),
+            order.limit_price(),
+        )?;
+        self.inner
+            .check_limit_order(market_state, position, order, balances, order_margin)
+    }
+
+    fn check_stop_order(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        order: &StopOrder<
+            i64,
+            5,
+            BaseCurrency<i64, 5>,
+            NoUserOrderId,
+            Pending<i64, 5, BaseCurrency<i64, 5>>,
+        >,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+        order_margin: &OrderMargin<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+    ) -> Result<(), RiskError> {
+        self.inner
+            .check_stop_order(market_state, position, order, balances, order_margin)
+    }
+
+    fn check_maintenance_margin(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+    ) -> Result<(), RiskError> {
+        RiskEngine::<_, 5, _, NoUserOrderId>::check_maintenance_margin(
+            &self.inner,
+            market_state,
+            position,
+            balances,
+        )
+    }
+
+    fn check_leverage(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+        order_margin: &OrderMargin<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+        contract_spec: &ContractSpecification<i64, 5, BaseCurrency<i64, 5>>,
+    ) -> Result<(), RiskError> {
+        self.inner.check_leverage(
+            market_state,
+            position,
+            balances,
+            order_margin,
+            contract_spec,
+        )
+    }
+
+    fn liquidation_price(
+        &self,
+        market_state: &MarketState<i64, 5>,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+    ) -> Option<QuoteCurrency<i64, 5>> {
+        RiskEngine::<_, 5, _, NoUserOrderId>::liquidation_price(
+            &self.inner,
+            market_state,
+            position,
+            balances,
+        )
+    }
+
+    fn bankruptcy_price(
+        &self,
+        position: &Position<i64, 5, BaseCurrency<i64, 5>>,
+        balances: &Balances<i64, 5, QuoteCurrency<i64, 5>>,
+    ) -> Option<QuoteCurrency<i64, 5>> {
+        RiskEngine::<_, 5, _, NoUserOrderId>::bankruptcy_price(&self.inner, position, balances)
+    }
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn custom_risk_engine() {
+    let config = mock_exchange_linear().config().clone();
+    let risk_engine = MaxNotionalRiskEngine {
+        inner: IsolatedMarginRiskEngine::new(config.contract_spec().clone()),
+        max_notional: QuoteCurrency::new(500, 0),
+    };
+    let mut exchange = Exchange::with_risk_engine(config, risk_engine);
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(99, 0),
+            ask: QuoteCurrency::new(100, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(4, 0)).unwrap())
+        .unwrap();
+    // The balance would allow it, but the notional of 600 exceeds the cap.
+    assert_eq!(
+        exchange.submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(2, 0)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(
+        exchange.submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(98, 0),
+                BaseCurrency::new(2, 0)
+            )
+            .unwrap()
+        ),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    // Reducing the position is always possible.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(4, 0)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn custom_risk_engine_liquidation_price() {
+    // The config asks for cross margin, but the installed risk engine backs the position in isolation.
+    let config = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::zero(),
+        |config| config,
+    )
+    .config()
+    .clone();
+    let risk_engine = MaxNotionalRiskEngine {
+        inner: IsolatedMarginRiskEngine::new(config.contract_spec().clone()),
+        max_notional: QuoteCurrency::new(1000, 0),
+    };
+    let mut exchange = Exchange::with_risk_engine(config, risk_engine);
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(10, 0)).unwrap())
+        .unwrap();
+    exchange.update_state(&test_bba(100, 101, 1)).unwrap();
+    assert_eq!(
+        exchange.liquidation_price(),
+        Some(QuoteCurrency::new(90, 0))
+    );
+    assert_eq!(exchange.bankruptcy_price(), Some(QuoteCurrency::new(80, 0)));
+    assert_eq!(
+        exchange.distance_to_liquidation(),
+        Some(Decimal::try_from_scaled(1, 1).unwrap())
+    );
+}
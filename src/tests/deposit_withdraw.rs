@@ -0,0 +1,96 @@
+use crate::{
+    mock_exchange_linear, mock_exchange_linear_with_account_tracker,
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+    types::NANOS_PER_SECOND,
+};
+
+#[test]
+#[tracing_test::traced_test]
+fn deposit_withdraw() {
+    let mut exchange = mock_exchange_linear();
+    assert_eq!(
+        exchange.deposit(QuoteCurrency::zero()),
+        Err(Error::InvalidTransferAmount)
+    );
+    exchange.deposit(QuoteCurrency::new(500, 0)).unwrap();
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1500, 0));
+
+    assert_eq!(
+        exchange.withdraw(QuoteCurrency::new(-1, 0)),
+        Err(Error::InvalidTransferAmount)
+    );
+    assert_eq!(
+        exchange.withdraw(QuoteCurrency::new(2000, 0)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.withdraw(QuoteCurrency::new(300, 0)).unwrap();
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1200, 0));
+    assert_eq!(
+        exchange.balances().net_deposits(),
+        QuoteCurrency::new(200, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn withdraw_respects_margin_in_use() {
+    // The margin in use is never available for a withdrawal.
+    // The long of 40 entered at 100 leaves an available balance of 197.6 next to a position margin of 800.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    assert_eq!(
+        exchange.withdraw(QuoteCurrency::new(198, 0)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.withdraw(QuoteCurrency::new(150, 0)).unwrap();
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(800, 0)
+    );
+
+    // With cross margin, the available balance also backs the position.
+    // The equity of 997.6 - 600 covers the maintenance margin of 340 with 57.6 to spare.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(85, 86, 1)).unwrap();
+    let balances = exchange.balances().clone();
+    assert_eq!(
+        exchange.withdraw(QuoteCurrency::new(60, 0)),
+        Err(Error::RiskError(RiskError::WithdrawalBreachesMargin))
+    );
+    assert_eq!(exchange.balances(), &balances);
+    exchange.withdraw(QuoteCurrency::new(50, 0)).unwrap();
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1476, 1));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn deposit_is_not_a_return() {
+    let mut exchange = mock_exchange_linear_with_account_tracker(QuoteCurrency::new(1000, 0));
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange.deposit(QuoteCurrency::new(1000, 0)).unwrap();
+    exchange
+        .update_state(&test_bba(99, 100, NANOS_PER_SECOND))
+        .unwrap();
+
+    let tracker = exchange.account_tracker().unwrap();
+    assert_eq!(tracker.cash_flows(), &vec![(
+        0.into(),
+        QuoteCurrency::new(1000, 0)
+    )]);
+    assert_eq!(
+        tracker.equity_curve().last().unwrap().1,
+        QuoteCurrency::new(2000, 0)
+    );
+    assert_eq!(tracker.ln_returns(), vec![0.0]);
+    assert_eq!(tracker.max_drawdown(), 0.0);
+}
@@ -0,0 +1,145 @@
+use std::num::NonZeroUsize;
+
+use const_decimal::Decimal;
+
+use crate::{prelude::*, test_fee_maker, test_fee_taker, tests::test_utils::test_bba};
+
+fn fee<MakerTaker>(bps: i64) -> Fee<i64, 5, MakerTaker> {
+    Fee::from(Decimal::try_from_scaled(bps, 4).unwrap())
+}
+
+fn tier(min_volume: i64, maker_bps: i64, taker_bps: i64) -> FeeTier<i64, 5, QuoteCurrency<i64, 5>> {
+    FeeTier::new(
+        QuoteCurrency::new(min_volume, 0),
+        fee(maker_bps),
+        fee(taker_bps),
+    )
+    .unwrap()
+}
+
+fn contract_spec() -> ContractSpecification<i64, 5, BaseCurrency<i64, 5>> {
+    ContractSpecification::new(
+        leverage!(5),
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        test_fee_maker(),
+        test_fee_taker(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn fee_tiers_must_ascend() {
+    assert_eq!(
+        contract_spec()
+            .with_fee_tiers(vec![tier(2000, 1, 4), tier(1000, -1, 4)])
+            .unwrap_err(),
+        ConfigError::InvalidFeeTiers
+    );
+    assert_eq!(
+        contract_spec()
+            .with_fee_tiers(vec![tier(1000, 1, 4), tier(1000, -1, 4)])
+            .unwrap_err(),
+        ConfigError::InvalidFeeTiers
+    );
+    // The taker fee of a tier must not exceed the one of the contract specification.
+    assert_eq!(
+        contract_spec()
+            .with_fee_tiers(vec![tier(1000, 1, 7)])
+            .unwrap_err(),
+        ConfigError::InvalidFeeTiers
+    );
+
+    let contract_spec = contract_spec()
+        .with_fee_tiers(vec![tier(1000, 1, 5), tier(2000, -1, 4)])
+        .unwrap();
+    assert_eq!(contract_spec.fee_tier(QuoteCurrency::new(999, 0)), None);
+    assert_eq!(
+        contract_spec.fee_maker_for(QuoteCurrency::new(999, 0)),
+        test_fee_maker()
+    );
+    assert_eq!(
+        contract_spec.fee_tier(QuoteCurrency::new(1000, 0)),
+        Some(&tier(1000, 1, 5))
+    );
+    assert_eq!(
+        contract_spec.fee_maker_for(QuoteCurrency::new(5000, 0)),
+        fee(-1)
+    );
+    assert_eq!(
+        contract_spec.fee_taker_for(QuoteCurrency::new(5000, 0)),
+        fee(4)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn fee_tiers_maker_rebate() {
+    let contract_spec = contract_spec()
+        .with_fee_tiers(vec![tier(1000, -1, 4)])
+        .unwrap();
+    let config = Config::new(
+        QuoteCurrency::new(1000, 0),
+        NonZeroUsize::new(10).unwrap(),
+        contract_spec,
+        OrderRateLimits::default(),
+    )
+    .unwrap();
+    let mut exchange = Exchange::<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>::new(config);
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+
+    // The base fees apply until the traded volume reaches the first tier.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.traded_volume(), QuoteCurrency::new(995, 0));
+    assert_eq!(exchange.fee_tier(), None);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(1, 0)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.traded_volume(), QuoteCurrency::new(1095, 0));
+    assert_eq!(exchange.fee_tier(), Some(&tier(1000, -1, 4)));
+
+    // The maker fill of the tier pays a rebate.
+    let order = LimitOrder::new(
+        Side::Sell,
+        QuoteCurrency::new(101, 0),
+        BaseCurrency::new(1, 0),
+    )
+    .unwrap();
+    exchange.submit_limit_order(order).unwrap();
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(102, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(
+        exchange.balances().total_rebates_received(),
+        QuoteCurrency::new(101, 4)
+    );
+    // The fees of 0.3, 0.297 and 0.06, netted with the rebate of 0.0101.
+    assert_eq!(
+        exchange.balances().total_fees_paid(),
+        QuoteCurrency::new(6469, 4)
+    );
+    // The loss of 5 on the first round trip and the profit of 1 on the second one.
+    assert_eq!(
+        exchange.balances().available(),
+        QuoteCurrency::new(9953531, 4)
+    );
+
+    // The volume rolls out of the window after 30 days.
+    exchange
+        .update_state(&test_bba(99, 100, FEE_TIER_VOLUME_WINDOW_NS + 1))
+        .unwrap();
+    assert_eq!(exchange.traded_volume(), QuoteCurrency::zero());
+    assert_eq!(exchange.fee_tier(), None);
+}
@@ -0,0 +1,234 @@
+use const_decimal::Decimal;
+
+use crate::{
+    mock_exchange_inverse, mock_exchange_linear,
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+#[test_case::test_matrix([Side::Buy, Side::Sell])]
+#[tracing_test::traced_test]
+fn funding_linear(side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(2, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(side, qty).unwrap())
+        .unwrap();
+    let available = exchange.balances().available();
+
+    let rate = Decimal::try_from_scaled(1, 4).unwrap();
+    exchange
+        .update_state(&Funding {
+            rate,
+            settlement_ts_ns: 10.into(),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    // Nothing is settled before the settlement timestamp.
+    assert_eq!(exchange.balances().available(), available);
+    assert_eq!(
+        exchange.balances().total_funding_paid(),
+        QuoteCurrency::zero()
+    );
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(102, 0),
+            timestamp_exchange_ns: 10.into(),
+        })
+        .unwrap();
+    let notional = QuoteCurrency::convert_from(qty, QuoteCurrency::new(101, 0));
+    let payment = match side {
+        Side::Buy => notional * rate,
+        Side::Sell => -(notional * rate),
+    };
+    assert_eq!(exchange.balances().total_funding_paid(), payment);
+    assert_eq!(exchange.balances().available(), available - payment);
+
+    // The funding is only settled once.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(102, 0),
+            timestamp_exchange_ns: 11.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.balances().total_funding_paid(), payment);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn funding_announced_at_prior_settlement() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(2, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+    let available = exchange.balances().available();
+
+    let rate = Decimal::try_from_scaled(1, 4).unwrap();
+    exchange
+        .update_state(&Funding {
+            rate,
+            settlement_ts_ns: 10.into(),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    // The next interval is announced in the same update as the prior settlement.
+    let next_rate = Decimal::try_from_scaled(3, 4).unwrap();
+    exchange
+        .update_state(&Funding {
+            rate: next_rate,
+            settlement_ts_ns: 20.into(),
+            timestamp_exchange_ns: 10.into(),
+        })
+        .unwrap();
+    let notional = QuoteCurrency::convert_from(qty, QuoteCurrency::new(1005, 1));
+    assert_eq!(exchange.balances().total_funding_paid(), notional * rate);
+    assert_eq!(exchange.market_state().funding_rate(), next_rate);
+    assert_eq!(
+        exchange.market_state().next_funding_ts_ns(),
+        Some(20.into())
+    );
+
+    // The announcement may also only arrive after the prior settlement time.
+    exchange
+        .update_state(&Funding {
+            rate,
+            settlement_ts_ns: 30.into(),
+            timestamp_exchange_ns: 25.into(),
+        })
+        .unwrap();
+    assert_eq!(
+        exchange.balances().total_funding_paid(),
+        notional * rate + notional * next_rate
+    );
+    assert_eq!(
+        exchange.balances().available(),
+        available - notional * rate - notional * next_rate
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn funding_depleted_position_margin_partial_close() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config,
+    );
+    exchange.withdraw(QuoteCurrency::new(7994, 1)).unwrap();
+    exchange.update_state(&test_bba(101, 102, 1)).unwrap();
+    exchange
+        .update_state(&Funding {
+            rate: Decimal::try_from_scaled(5, 2).unwrap(),
+            settlement_ts_ns: 2.into(),
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    // The payment of 50.75 at the mark price of 101.5 is taken from the position margin of 200.
+    assert_eq!(exchange.balances().available(), QuoteCurrency::zero());
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(14925, 2)
+    );
+    // Both the liquidation and the bankruptcy price follow the depleted position margin.
+    assert_eq!(
+        exchange.liquidation_price(),
+        Some(QuoteCurrency::new(95075, 3))
+    );
+    assert_eq!(
+        exchange.bankruptcy_price(),
+        Some(QuoteCurrency::new(85075, 3))
+    );
+
+    // The smaller position would require a margin of 180, but reducing it never reserves more.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(1, 0)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(
+            BaseCurrency::new(9, 0),
+            QuoteCurrency::new(100, 0)
+        ))
+    );
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(14925, 2)
+    );
+    // The profit of 1 less the taker fee of 0.0606.
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(9394, 4));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn funding_inverse() {
+    let mut exchange = mock_exchange_inverse(BaseCurrency::new(10, 0));
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = QuoteCurrency::new(500, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+    let available = exchange.balances().available();
+
+    // Negative funding means longs receive funding.
+    let rate = Decimal::try_from_scaled(-1, 3).unwrap();
+    exchange
+        .update_state(&Funding {
+            rate,
+            settlement_ts_ns: 1.into(),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    let payment = BaseCurrency::convert_from(qty, QuoteCurrency::new(1005, 1)) * rate;
+    assert!(payment < BaseCurrency::zero());
+    assert_eq!(exchange.balances().total_funding_paid(), payment);
+    assert_eq!(exchange.balances().available(), available - payment);
+}
+
+#[test]
+fn funding_neutral_position() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange
+        .update_state(&Funding {
+            rate: Decimal::try_from_scaled(1, 4).unwrap(),
+            settlement_ts_ns: 0.into(),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    assert_eq!(
+        exchange.balances().total_funding_paid(),
+        QuoteCurrency::zero()
+    );
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
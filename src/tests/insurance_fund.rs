@@ -0,0 +1,97 @@
+use std::num::NonZeroUsize;
+
+use const_decimal::Decimal;
+
+use crate::{
+    prelude::*,
+    test_fee_maker, test_fee_taker,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+#[test]
+fn insurance_fund_config() {
+    let contract_spec = ContractSpecification::<i64, 5, BaseCurrency<i64, 5>>::new(
+        leverage!(1),
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        test_fee_maker(),
+        test_fee_taker(),
+    )
+    .unwrap();
+    let config = Config::new(
+        QuoteCurrency::new(1000, 0),
+        NonZeroUsize::new(10).unwrap(),
+        contract_spec,
+        OrderRateLimits::default(),
+    )
+    .unwrap();
+    assert_eq!(config.insurance_fund(), QuoteCurrency::zero());
+    assert_eq!(
+        config
+            .with_insurance_fund(QuoteCurrency::new(-1, 0))
+            .unwrap_err(),
+        ConfigError::InvalidInsuranceFund
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn insurance_fund_collects_liquidations_at_bankruptcy_price() {
+    let liquidation_model = LiquidationModel::new(
+        LiquidationMode::Full,
+        Decimal::try_from_scaled(5, 3).unwrap(),
+        LiquidationSettlement::BankruptcyPrice,
+    )
+    .unwrap();
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| {
+            config
+                .with_liquidation_model(liquidation_model)
+                .with_insurance_fund(QuoteCurrency::new(100, 0))
+                .unwrap()
+        },
+    );
+    assert_eq!(exchange.insurance_fund(), QuoteCurrency::new(100, 0));
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+    // The position taken over at 80 is closed at 89, along with the liquidation fee of 4.
+    assert_eq!(exchange.insurance_fund(), QuoteCurrency::new(194, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn insurance_fund_covers_fill_beyond_bankruptcy_price() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| {
+            config
+                .with_insurance_fund(QuoteCurrency::new(50, 0))
+                .unwrap()
+        },
+    );
+    assert_eq!(
+        exchange.update_state(&test_bba(70, 71, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+    let OrderEvent::Liquidated { order, .. } = &exchange.order_events()[0] else {
+        panic!("Expected a liquidation");
+    };
+    assert_eq!(order.state().avg_fill_price(), QuoteCurrency::new(80, 0));
+    // The loss of 100 beyond the bankruptcy price depletes the insurance fund.
+    assert_eq!(exchange.insurance_fund(), QuoteCurrency::zero());
+    // Only the position margin and the taker fees of 0.6 and 0.48 are lost.
+    assert_eq!(
+        exchange.balances().available(),
+        QuoteCurrency::new(79892, 2)
+    );
+}
@@ -0,0 +1,186 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+    PriceLevel {
+        price: QuoteCurrency::new(price, 0),
+        quantity: BaseCurrency::new(quantity, 0),
+    }
+}
+
+fn l2_book() -> L2Book<i64, 5, BaseCurrency<i64, 5>> {
+    L2Book {
+        depth: OrderBookDepth::new(vec![level(100, 5), level(99, 5)], vec![
+            level(101, 2),
+            level(102, 3),
+            level(103, 5),
+        ]),
+        timestamp_exchange_ns: 0.into(),
+    }
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_book_market_order_walks_depth() {
+    let mut exchange = mock_exchange_linear();
+    let book = l2_book();
+    exchange.update_state(&book).unwrap();
+    assert_eq!(exchange.market_state().bid(), QuoteCurrency::new(100, 0));
+    assert_eq!(exchange.market_state().ask(), QuoteCurrency::new(101, 0));
+    assert_eq!(exchange.depth(), &book.depth);
+
+    let qty = BaseCurrency::new(5, 0);
+    let order = exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+    let avg_fill_price = QuoteCurrency::new(1016, 1);
+    assert_eq!(order.state().avg_fill_price(), avg_fill_price);
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, avg_fill_price))
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_delta_moves_best_bid_and_ask() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&l2_book()).unwrap();
+    exchange
+        .update_state(&L2Delta {
+            bids: vec![level(100, 0), level(98, 5)],
+            asks: vec![level(101, 0)],
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.market_state().bid(), QuoteCurrency::new(99, 0));
+    assert_eq!(exchange.market_state().ask(), QuoteCurrency::new(102, 0));
+    assert_eq!(
+        exchange.depth(),
+        &OrderBookDepth::new(vec![level(99, 5), level(98, 5)], vec![
+            level(102, 3),
+            level(103, 5)
+        ])
+    );
+
+    let order = exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(10, 0)).unwrap())
+        .unwrap();
+    assert_eq!(order.state().avg_fill_price(), QuoteCurrency::new(985, 1));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_book_fills_resting_limit_order() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&l2_book()).unwrap();
+
+    let limit_price = QuoteCurrency::new(100, 0);
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(Side::Buy, limit_price, BaseCurrency::new(3, 0)).unwrap(),
+        )
+        .unwrap();
+
+    // The book moved through the resting bid, but the liquidity at its limit price does not fill it.
+    let fills = exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(vec![level(98, 5)], vec![level(99, 2), level(100, 5)]),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(
+        &fills[0],
+        LimitOrderFill::PartiallyFilled { filled_quantity, .. } if *filled_quantity == BaseCurrency::new(2, 0)
+    ));
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(BaseCurrency::new(2, 0), limit_price))
+    );
+    assert_eq!(
+        exchange
+            .active_limit_orders()
+            .get_by_id(order.id(), Side::Buy)
+            .unwrap()
+            .remaining_quantity(),
+        BaseCurrency::new(1, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_book_immediate_or_cancel_limited_by_depth() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&l2_book()).unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(102, 0),
+        BaseCurrency::new(10, 0),
+    )
+    .unwrap();
+    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    let order = exchange.submit_limit_order(order).unwrap();
+    let filled_qty = BaseCurrency::new(5, 0);
+    let avg_fill_price = QuoteCurrency::new(1016, 1);
+    assert_eq!(order.filled_quantity(), filled_qty);
+    assert_eq!(order.state().filled_quantity(), &FilledQuantity::Filled {
+        cumulative_qty: filled_qty,
+        avg_price: avg_fill_price,
+    });
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: order.id(),
+        reason: CancelReason::UnfilledRemainder,
+    }]);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(filled_qty, avg_fill_price))
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_book_fill_or_kill_limited_by_depth() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&l2_book()).unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(102, 0),
+        BaseCurrency::new(6, 0),
+    )
+    .unwrap();
+    order.set_time_in_force(TimeInForce::FillOrKill);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.filled_quantity(), BaseCurrency::zero());
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: order.id(),
+        reason: CancelReason::UnfilledRemainder,
+    }]);
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn l2_book_marketable_remainder_rests() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&l2_book()).unwrap();
+
+    let limit_price = QuoteCurrency::new(101, 0);
+    let mut order = LimitOrder::new(Side::Buy, limit_price, BaseCurrency::new(5, 0)).unwrap();
+    order.set_re_pricing(RePricing::Marketable);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.filled_quantity(), BaseCurrency::new(2, 0));
+    assert_eq!(order.remaining_quantity(), BaseCurrency::new(3, 0));
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(BaseCurrency::new(2, 0), limit_price))
+    );
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(303, 0)
+    );
+}
@@ -0,0 +1,151 @@
+use crate::{mock_exchange_linear, prelude::*, tests::test_utils::test_bba};
+
+fn exchange_with_latency(
+    order_entry_latency: LatencyModel,
+    market_data_latency: LatencyModel,
+) -> Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId> {
+    let config = mock_exchange_linear()
+        .config()
+        .clone()
+        .with_order_entry_latency(order_entry_latency)
+        .unwrap()
+        .with_market_data_latency(market_data_latency)
+        .unwrap();
+    let mut exchange = Exchange::new(config);
+    exchange.update_state(&test_bba(100, 101, 0)).unwrap();
+    exchange
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn latency_market_order_fills_at_arrival() {
+    let mut exchange =
+        exchange_with_latency(LatencyModel::Fixed(10.into()), LatencyModel::NoLatency);
+    let qty = BaseCurrency::new(1, 0);
+    let arrival_ts_ns = exchange.send_request(OrderRequest::SubmitMarketOrder(
+        MarketOrder::new(Side::Buy, qty).unwrap(),
+    ));
+    assert_eq!(arrival_ts_ns, 10.into());
+    assert_eq!(exchange.pending_requests().len(), 1);
+    assert!(exchange.order_responses().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+
+    // The order reached the exchange before the market moved.
+    exchange.update_state(&test_bba(110, 111, 20)).unwrap();
+    assert!(exchange.pending_requests().is_empty());
+    let fill_price = QuoteCurrency::new(101, 0);
+    assert_eq!(exchange.order_responses().len(), 1);
+    assert!(matches!(
+        &exchange.order_responses()[0],
+        OrderResponse::MarketOrderFilled(order)
+            if order.state().avg_fill_price() == fill_price
+                && order.state().meta().ts_exchange_received() == 10.into()
+    ));
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, fill_price))
+    );
+    assert_eq!(exchange.market_state().current_ts_ns(), 20.into());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn latency_limit_order_rejected_at_arrival() {
+    let mut exchange =
+        exchange_with_latency(LatencyModel::Fixed(10.into()), LatencyModel::NoLatency);
+    let order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(100, 0),
+        BaseCurrency::new(1, 0),
+    )
+    .unwrap();
+    exchange.send_request(OrderRequest::SubmitLimitOrder(order.clone()));
+
+    // The ask moves below the limit price while the order is in flight.
+    exchange.update_state(&test_bba(98, 99, 5)).unwrap();
+    assert!(exchange.order_responses().is_empty());
+    exchange.update_state(&test_bba(98, 99, 15)).unwrap();
+    assert_eq!(exchange.order_responses().len(), 1);
+    assert!(matches!(
+        &exchange.order_responses()[0],
+        OrderResponse::Rejected {
+            request: OrderRequest::SubmitLimitOrder(rejected),
+            error: Error::OrderError(OrderError::GoodTillCrossingRejectedOrder { .. }),
+        } if *rejected == order
+    ));
+    assert!(exchange.active_limit_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn latency_includes_market_data_latency() {
+    let mut exchange = exchange_with_latency(
+        LatencyModel::Fixed(10.into()),
+        LatencyModel::Fixed(5.into()),
+    );
+    let arrival_ts_ns = exchange.send_request(OrderRequest::SubmitMarketOrder(
+        MarketOrder::new(Side::Sell, BaseCurrency::new(1, 0)).unwrap(),
+    ));
+    assert_eq!(arrival_ts_ns, 15.into());
+
+    exchange.update_state(&test_bba(100, 101, 14)).unwrap();
+    assert!(exchange.order_responses().is_empty());
+    exchange.update_state(&test_bba(100, 101, 15)).unwrap();
+    assert!(matches!(
+        &exchange.order_responses()[0],
+        OrderResponse::MarketOrderFilled(_)
+    ));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn latency_requests_arrive_in_order() {
+    let mut exchange = exchange_with_latency(
+        LatencyModel::Trace(vec![10.into(), 1.into()]),
+        LatencyModel::NoLatency,
+    );
+    exchange.send_request(OrderRequest::SubmitLimitOrder(
+        LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(99, 0),
+            BaseCurrency::new(1, 0),
+        )
+        .unwrap(),
+    ));
+    // The cancellation can not overtake the submission.
+    let arrival_ts_ns =
+        exchange.send_request(OrderRequest::CancelLimitOrder(CancelBy::OrderId(0.into())));
+    assert_eq!(arrival_ts_ns, 10.into());
+
+    exchange.update_state(&test_bba(100, 101, 10)).unwrap();
+    let responses = exchange.order_responses();
+    assert_eq!(responses.len(), 2);
+    assert!(
+        matches!(&responses[0], OrderResponse::LimitOrderSubmitted(order) if order.id() == 0.into())
+    );
+    assert!(
+        matches!(&responses[1], OrderResponse::LimitOrderCancelled(order) if order.id() == 0.into())
+    );
+    assert!(exchange.active_limit_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn latency_none_processes_requests_immediately() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&test_bba(100, 101, 0)).unwrap();
+    let arrival_ts_ns = exchange.send_request(OrderRequest::SubmitLimitOrder(
+        LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::new(99, 0),
+            BaseCurrency::new(1, 0),
+        )
+        .unwrap(),
+    ));
+    assert_eq!(arrival_ts_ns, 0.into());
+    assert!(exchange.pending_requests().is_empty());
+    assert!(matches!(&exchange.order_responses()[..], [
+        OrderResponse::LimitOrderSubmitted(_)
+    ]));
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+}
@@ -1,4 +1,131 @@
+use const_decimal::Decimal;
+
+use crate::{
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+/// The quantity, fill price, bankruptcy price and liquidation fee of the liquidation orders in the `order_events`.
+fn liquidations(
+    exchange: &Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+) -> Vec<(
+    BaseCurrency<i64, 5>,
+    QuoteCurrency<i64, 5>,
+    QuoteCurrency<i64, 5>,
+    QuoteCurrency<i64, 5>,
+)> {
+    exchange
+        .order_events()
+        .iter()
+        .filter_map(|event| match event {
+            OrderEvent::Liquidated {
+                order,
+                bankruptcy_price,
+                liquidation_fee,
+            } => Some((
+                order.quantity(),
+                order.state().avg_fill_price(),
+                *bankruptcy_price,
+                *liquidation_fee,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidate_full_at_market_price() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config,
+    );
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(liquidations(&exchange), vec![(
+        BaseCurrency::new(10, 0),
+        QuoteCurrency::new(89, 0),
+        QuoteCurrency::new(80, 0),
+        QuoteCurrency::zero()
+    )]);
+    // The loss of 110 and the taker fees of 0.6 and 0.534.
+    assert_eq!(
+        exchange.balances().available(),
+        QuoteCurrency::new(888866, 3)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidate_at_bankruptcy_price_with_liquidation_fee() {
+    let liquidation_model = LiquidationModel::new(
+        LiquidationMode::Full,
+        Decimal::try_from_scaled(5, 3).unwrap(),
+        LiquidationSettlement::BankruptcyPrice,
+    )
+    .unwrap();
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |config| config.with_liquidation_model(liquidation_model),
+    );
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(liquidations(&exchange), vec![(
+        BaseCurrency::new(10, 0),
+        QuoteCurrency::new(80, 0),
+        QuoteCurrency::new(80, 0),
+        QuoteCurrency::new(4, 0)
+    )]);
+    // The whole position margin is lost, without a taker fee but with the liquidation fee.
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(7954, 1))
+            .position_margin(QuoteCurrency::zero())
+            .order_margin(QuoteCurrency::zero())
+            .total_fees_paid(QuoteCurrency::new(46, 1))
+            .build()
+    );
+}
+
 #[test]
-fn liquidate() {
-    todo!()
+#[tracing_test::traced_test]
+fn liquidate_partially() {
+    let liquidation_model = LiquidationModel::new(
+        LiquidationMode::Partial(Decimal::try_from_scaled(25, 2).unwrap()),
+        Decimal::zero(),
+        LiquidationSettlement::MarketPrice,
+    )
+    .unwrap();
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config.with_liquidation_model(liquidation_model),
+    );
+    exchange.update_state(&test_bba(84, 85, 1)).unwrap();
+
+    // Closing a quarter restores the equity of 317.102 above the maintenance margin of 249.
+    assert_eq!(
+        exchange.update_state(&test_bba(83, 84, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(liquidations(&exchange), vec![(
+        BaseCurrency::new(10, 0),
+        QuoteCurrency::new(83, 0),
+        QuoteCurrency::new(7506, 2),
+        QuoteCurrency::zero()
+    )]);
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(30, 0));
+    exchange.update_state(&test_bba(83, 84, 1)).unwrap();
 }
@@ -0,0 +1,140 @@
+use std::num::NonZeroUsize;
+
+use const_decimal::Decimal;
+
+use crate::{
+    prelude::*,
+    test_fee_maker, test_fee_taker,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidation_price_isolated_linear() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::zero(),
+        |config| config,
+    );
+    assert_eq!(exchange.liquidation_price(), None);
+    assert_eq!(exchange.bankruptcy_price(), None);
+    assert_eq!(exchange.distance_to_liquidation(), None);
+
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(10, 0)).unwrap())
+        .unwrap();
+    exchange.update_state(&test_bba(100, 101, 1)).unwrap();
+    assert_eq!(
+        exchange.liquidation_price(),
+        Some(QuoteCurrency::new(90, 0))
+    );
+    assert_eq!(exchange.bankruptcy_price(), Some(QuoteCurrency::new(80, 0)));
+    assert_eq!(
+        exchange.distance_to_liquidation(),
+        Some(Decimal::try_from_scaled(1, 1).unwrap())
+    );
+
+    exchange.update_state(&test_bba(90, 91, 1)).unwrap();
+    assert_eq!(exchange.distance_to_liquidation(), Some(Decimal::zero()));
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidation_price_cross_linear() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::zero(),
+        |config| config,
+    );
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(40, 0)).unwrap())
+        .unwrap();
+    // The equity of 997.6 covers losses until it equals the maintenance margin of 10% at 83.4.
+    assert_eq!(
+        exchange.liquidation_price(),
+        Some(QuoteCurrency::new(834, 1))
+    );
+    assert_eq!(
+        exchange.bankruptcy_price(),
+        Some(QuoteCurrency::new(7506, 2))
+    );
+
+    // The margin of an open order keeps backing the position.
+    exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(90, 0),
+                BaseCurrency::new(1, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(18, 0)
+    );
+    assert_eq!(
+        exchange.liquidation_price(),
+        Some(QuoteCurrency::new(834, 1))
+    );
+    assert_eq!(
+        exchange.bankruptcy_price(),
+        Some(QuoteCurrency::new(7506, 2))
+    );
+
+    exchange.update_state(&test_bba(84, 85, 1)).unwrap();
+    assert_eq!(
+        exchange.update_state(&test_bba(83, 84, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidation_price_isolated_inverse() {
+    let contract_spec = ContractSpecification::new(
+        leverage!(5),
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        test_fee_maker(),
+        test_fee_taker(),
+    )
+    .unwrap();
+    let config = Config::new(
+        BaseCurrency::new(10, 0),
+        NonZeroUsize::new(10).unwrap(),
+        contract_spec,
+        OrderRateLimits::default(),
+    )
+    .unwrap();
+    let mut exchange = Exchange::<i64, 5, QuoteCurrency<i64, 5>, NoUserOrderId>::new(config);
+    exchange.update_state(&test_bba(100, 101, 1)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, QuoteCurrency::new(1000, 0)).unwrap())
+        .unwrap();
+
+    // The position margin of 2 Base covers a rise of the short to 125.
+    assert_eq!(
+        exchange.bankruptcy_price(),
+        Some(QuoteCurrency::new(125, 0))
+    );
+    // The losses reach the maintenance margin at 100 / 0.9.
+    let liquidation_price = exchange.liquidation_price().unwrap();
+    assert!(liquidation_price > QuoteCurrency::new(111, 0));
+    assert!(liquidation_price < QuoteCurrency::new(112, 0));
+    assert!(exchange.distance_to_liquidation().unwrap() > Decimal::zero());
+
+    exchange.update_state(&test_bba(110, 111, 1)).unwrap();
+    assert_eq!(
+        exchange.update_state(&test_bba(111, 112, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+}
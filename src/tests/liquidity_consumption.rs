@@ -0,0 +1,193 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+    PriceLevel {
+        price: QuoteCurrency::new(price, 0),
+        quantity: BaseCurrency::new(quantity, 0),
+    }
+}
+
+/// An exchange with a ladder of bids at 100, 99 and 98, each with a quantity of 2.
+fn exchange_with_bid_ladder() -> Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId> {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(vec![level(96, 5)], vec![level(101, 5)]),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    for price in [100, 99, 98] {
+        exchange
+            .submit_limit_order(
+                LimitOrder::new(
+                    Side::Buy,
+                    QuoteCurrency::new(price, 0),
+                    BaseCurrency::new(2, 0),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+    exchange
+}
+
+fn filled_quantity(
+    fills: &[LimitOrderFill<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>],
+) -> BaseCurrency<i64, 5> {
+    fills
+        .iter()
+        .map(|fill| match fill {
+            LimitOrderFill::PartiallyFilled {
+                filled_quantity, ..
+            }
+            | LimitOrderFill::FullyFilled {
+                filled_quantity, ..
+            } => *filled_quantity,
+        })
+        .fold(BaseCurrency::zero(), |acc, qty| acc + qty)
+}
+
+/// The liquidity priced through the ladder only totals 3, so the bid at 100 fills fully, the one at 99 partially
+/// and the one at 98 not at all.
+fn assert_ladder_filled_by_three(
+    exchange: &Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>,
+    fills: &[LimitOrderFill<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>],
+) {
+    assert_eq!(fills.len(), 2);
+    assert_eq!(filled_quantity(fills), BaseCurrency::new(3, 0));
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(3, 0));
+    assert_eq!(exchange.active_limit_orders().num_active(), 2);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidity_consumption_trade() {
+    let mut exchange = exchange_with_bid_ladder();
+    let fills = exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(97, 0),
+            quantity: BaseCurrency::new(3, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap()
+        .clone();
+    assert_ladder_filled_by_three(&exchange, &fills);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidity_consumption_candle() {
+    let mut exchange = exchange_with_bid_ladder();
+    let candle = Candle::new(
+        QuoteCurrency::new(96, 0),
+        QuoteCurrency::new(97, 0),
+        QuoteCurrency::new(96, 0),
+        QuoteCurrency::new(101, 0),
+        1.into(),
+    )
+    .unwrap();
+    // A `Candle` carries no volume, so it only fills the best price level its low trades through.
+    let fills = exchange.update_state(&candle).unwrap().clone();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(fills[0], LimitOrderFill::FullyFilled { .. }));
+    assert_eq!(filled_quantity(&fills), BaseCurrency::new(2, 0));
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(2, 0));
+    assert_eq!(exchange.active_limit_orders().num_active(), 2);
+
+    // The next candle fills the next price level.
+    let fills = exchange.update_state(&candle).unwrap().clone();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(filled_quantity(&fills), BaseCurrency::new(2, 0));
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(4, 0));
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidity_consumption_smart_candle() {
+    let mut exchange = exchange_with_bid_ladder();
+    let ask_order = exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Sell,
+                QuoteCurrency::new(103, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    let trades = [
+        Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: BaseCurrency::new(2, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        },
+        Trade {
+            price: QuoteCurrency::new(97, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 2.into(),
+        },
+        Trade {
+            price: QuoteCurrency::new(104, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 3.into(),
+        },
+    ];
+    let bba = Bba {
+        bid: QuoteCurrency::new(96, 0),
+        ask: QuoteCurrency::new(101, 0),
+        timestamp_exchange_ns: 3.into(),
+    };
+    let smart_candle = SmartCandle::new(
+        &trades,
+        bba,
+        exchange.config().contract_spec().price_filter(),
+    );
+    let fills = exchange.update_state(&smart_candle).unwrap().clone();
+
+    // The sells fill 3 of the bids and the buy fills 1 of the ask.
+    assert_eq!(fills.len(), 3);
+    assert_eq!(filled_quantity(&fills), BaseCurrency::new(4, 0));
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(2, 0));
+    assert_eq!(
+        exchange
+            .active_limit_orders()
+            .get_by_id(ask_order.id(), Side::Sell)
+            .unwrap()
+            .remaining_quantity(),
+        BaseCurrency::new(1, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidity_consumption_l2_book() {
+    let mut exchange = exchange_with_bid_ladder();
+    let fills = exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(vec![level(95, 5)], vec![level(97, 1), level(98, 2)]),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap()
+        .clone();
+    assert_ladder_filled_by_three(&exchange, &fills);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn liquidity_consumption_l2_delta() {
+    let mut exchange = exchange_with_bid_ladder();
+    let fills = exchange
+        .update_state(&L2Delta {
+            bids: Vec::new(),
+            asks: vec![level(98, 2), level(97, 1)],
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap()
+        .clone();
+    assert_ladder_filled_by_three(&exchange, &fills);
+}
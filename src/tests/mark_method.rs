@@ -0,0 +1,145 @@
+use std::num::NonZeroU32;
+
+use const_decimal::Decimal;
+
+use crate::{
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+fn index_price(price: i64, scale: u8) -> IndexPrice<i64, 5> {
+    IndexPrice {
+        price: QuoteCurrency::new(price, scale),
+        timestamp_exchange_ns: 1.into(),
+    }
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn mark_method_unrealized_pnl() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |mut config| {
+            config
+                .contract_spec_mut()
+                .set_mark_method(MarkMethod::BidAsk);
+            config
+        },
+    );
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    assert_eq!(
+        exchange
+            .position()
+            .marked_unrealized_pnl(exchange.market_state()),
+        QuoteCurrency::new(-10, 0)
+    );
+
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |mut config| {
+            config
+                .contract_spec_mut()
+                .set_mark_method(MarkMethod::MidPrice);
+            config
+        },
+    );
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    assert_eq!(
+        exchange
+            .position()
+            .marked_unrealized_pnl(exchange.market_state()),
+        QuoteCurrency::new(-5, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn mark_method_fair_price_ignores_wicks() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |mut config| {
+            config
+                .contract_spec_mut()
+                .set_mark_method(MarkMethod::BidAsk);
+            config
+        },
+    );
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    assert_eq!(
+        exchange.update_state(&test_bba(85, 86, 1)).unwrap_err(),
+        RiskError::Liquidate
+    );
+
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |mut config| {
+            config
+                .contract_spec_mut()
+                .set_mark_method(MarkMethod::FairPrice {
+                    basis_periods: NonZeroU32::new(10).unwrap(),
+                });
+            config
+        },
+    );
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    exchange.update_state(&test_bba(85, 86, 1)).unwrap();
+    // The basis of the wick only moves the moving average by a fraction.
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    assert!(exchange.market_state().mark_price() > QuoteCurrency::new(96, 0));
+    assert_eq!(exchange.position().quantity(), BaseCurrency::new(10, 0));
+
+    // Once the index price follows, the position is liquidated.
+    assert_eq!(
+        exchange.update_state(&index_price(88, 0)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn mark_method_fair_price_from_index_constituents() {
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(10, 0),
+        |mut config| {
+            config
+                .contract_spec_mut()
+                .set_mark_method(MarkMethod::FairPrice {
+                    basis_periods: NonZeroU32::new(10).unwrap(),
+                });
+            config
+        },
+    );
+    exchange.update_state(&index_price(995, 1)).unwrap();
+    let constituent = |price| IndexConstituent {
+        price: QuoteCurrency::new(price, 0),
+        weight: Decimal::one(),
+    };
+    exchange.update_state(&test_bba(101, 102, 1)).unwrap();
+    exchange
+        .update_state(
+            &IndexPrice::from_constituents(&[constituent(99), constituent(101)], 2.into()).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        exchange.market_state().index_price(),
+        QuoteCurrency::new(100, 0)
+    );
+    assert_eq!(
+        exchange.market_state().premium_index(),
+        Decimal::try_from_scaled(1, 2).unwrap()
+    );
+    // The basis moved from 0 towards 1.5 by 2 / 11.
+    assert!(exchange.market_state().mark_price() > QuoteCurrency::new(100, 0));
+    assert!(exchange.market_state().mark_price() < QuoteCurrency::new(1015, 1));
+}
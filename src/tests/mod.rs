@@ -1,7 +1,33 @@
+mod account_tracker;
 mod amend;
+mod auto_deleverage;
 mod cancel_limit_order;
+mod cross_margin;
+mod custom_risk_engine;
+mod deposit_withdraw;
+mod fee_tiers;
+mod funding;
+mod insurance_fund;
+mod l2_book;
+mod latency;
+mod liquidate;
+mod liquidation_price;
+mod liquidity_consumption;
+mod mark_method;
+mod order_groups;
 mod partial_order_fill;
+mod queue_position;
+mod re_pricing;
+mod reduce_only;
+mod risk_limit_tiers;
+mod set_leverage;
+mod slippage;
+mod stop_order;
 mod submit_limit_buy_order;
 mod submit_limit_sell_order;
 mod submit_market_buy_order;
 mod submit_market_sell_order;
+mod test_utils;
+mod time_in_force;
+mod trade_ledger;
+mod trailing_stop;
@@ -0,0 +1,455 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test]
+#[tracing_test::traced_test]
+fn oco_order_fill_and_trigger() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(95, 0), qty).unwrap();
+    let [take_profit, stop_loss] = exchange
+        .submit_oco_order(take_profit.into(), stop_loss.into())
+        .unwrap();
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(exchange.active_stop_orders().len(), 1);
+
+    // A partial fill of the take-profit reduces the stop-loss.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(111, 0),
+            quantity: BaseCurrency::new(2, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![
+        OrderEvent::QuantityAdjusted {
+            order_id: stop_loss.id(),
+            quantity: BaseCurrency::new(3, 0),
+        }
+    ]);
+    assert_eq!(
+        exchange.active_stop_orders()[0].quantity(),
+        BaseCurrency::new(3, 0)
+    );
+
+    // Triggering the stop-loss cancels the rest of the take-profit.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(95, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events().len(), 2);
+    assert!(matches!(
+        exchange.order_events()[0],
+        OrderEvent::StopTriggered {
+            triggered_order: TriggeredOrder::Market(_),
+            ..
+        }
+    ));
+    assert_eq!(exchange.order_events()[1], OrderEvent::Cancelled {
+        order_id: take_profit.id(),
+        reason: CancelReason::LinkedOrder,
+    });
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn oco_order_cancel_leg() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(2, 0);
+    let buy = LimitOrder::new(Side::Buy, QuoteCurrency::new(95, 0), qty).unwrap();
+    let stop_buy = StopOrder::new_stop_market(Side::Buy, QuoteCurrency::new(105, 0), qty).unwrap();
+    let [buy, stop_buy] = exchange
+        .submit_oco_order(buy.into(), stop_buy.into())
+        .unwrap();
+
+    exchange
+        .cancel_limit_order(CancelBy::OrderId(buy.id()))
+        .unwrap();
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: stop_buy.id(),
+        reason: CancelReason::LinkedOrder,
+    }]);
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn oco_order_second_leg_rejected() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(2, 0);
+    let buy = LimitOrder::new(Side::Buy, QuoteCurrency::new(95, 0), qty).unwrap();
+    // Would trigger immediately at the current ask.
+    let mut stop_buy =
+        StopOrder::new_stop_market(Side::Buy, QuoteCurrency::new(100, 0), qty).unwrap();
+    stop_buy.set_trigger_by(TriggerBy::BidAsk);
+    assert!(matches!(
+        exchange.submit_oco_order(buy.into(), stop_buy.into()),
+        Err(Error::OrderError(
+            OrderError::StopOrderWouldTriggerImmediately { .. }
+        ))
+    ));
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn bracket_order() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(99, 0), qty).unwrap();
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    let (entry, [take_profit, stop_loss]) = exchange
+        .submit_bracket_order(entry, take_profit.into(), stop_loss.into())
+        .unwrap();
+    // The exits are held back until the entry is filled.
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert!(exchange.active_stop_orders().is_empty());
+
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: BaseCurrency::new(2, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![
+        OrderEvent::Activated {
+            order_id: take_profit.id(),
+            quantity: BaseCurrency::new(2, 0),
+        },
+        OrderEvent::Activated {
+            order_id: stop_loss.id(),
+            quantity: BaseCurrency::new(2, 0),
+        },
+    ]);
+    assert_eq!(exchange.active_limit_orders().num_active(), 2);
+    assert_eq!(exchange.active_stop_orders().len(), 1);
+
+    // The exits grow with the entry fills.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![
+        OrderEvent::QuantityAdjusted {
+            order_id: take_profit.id(),
+            quantity: BaseCurrency::new(3, 0),
+        },
+        OrderEvent::QuantityAdjusted {
+            order_id: stop_loss.id(),
+            quantity: BaseCurrency::new(3, 0),
+        },
+    ]);
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(
+            BaseCurrency::new(3, 0),
+            QuoteCurrency::new(99, 0)
+        ))
+    );
+
+    // Triggering the stop-loss cancels the take-profit and the rest of the entry.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(90, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 3.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events().len(), 3);
+    assert_eq!(&exchange.order_events()[1..], &[
+        OrderEvent::Cancelled {
+            order_id: take_profit.id(),
+            reason: CancelReason::LinkedOrder,
+        },
+        OrderEvent::Cancelled {
+            order_id: entry.id(),
+            reason: CancelReason::LinkedOrder,
+        },
+    ]);
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn bracket_order_entry_cancelled() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(99, 0), qty).unwrap();
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    let (entry, [_, stop_loss]) = exchange
+        .submit_bracket_order(entry, take_profit.into(), stop_loss.into())
+        .unwrap();
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: BaseCurrency::new(2, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+
+    // The active exits keep protecting the filled part of the entry.
+    exchange
+        .cancel_limit_order(CancelBy::OrderId(entry.id()))
+        .unwrap();
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(exchange.active_stop_orders().len(), 1);
+
+    // Filling the take-profit cancels the stop-loss.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(111, 0),
+            quantity: BaseCurrency::new(2, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: stop_loss.id(),
+        reason: CancelReason::LinkedOrder,
+    }]);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn bracket_order_marketable_entry() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let mut entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(102, 0), qty).unwrap();
+    entry.set_re_pricing(RePricing::Marketable);
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    let (entry, [take_profit, stop_loss]) = exchange
+        .submit_bracket_order(entry, take_profit.into(), stop_loss.into())
+        .unwrap();
+    // The entry is filled upon submission, which activates the exits right away.
+    assert_eq!(entry.remaining_quantity(), BaseCurrency::zero());
+    assert_eq!(exchange.order_events(), &vec![
+        OrderEvent::Activated {
+            order_id: take_profit.id(),
+            quantity: qty,
+        },
+        OrderEvent::Activated {
+            order_id: stop_loss.id(),
+            quantity: qty,
+        },
+    ]);
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(exchange.active_stop_orders().len(), 1);
+
+    // Triggering the stop-loss cancels the take-profit.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(90, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events().len(), 2);
+    assert_eq!(exchange.order_events()[1], OrderEvent::Cancelled {
+        order_id: take_profit.id(),
+        reason: CancelReason::LinkedOrder,
+    });
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn bracket_order_immediate_or_cancel_entry() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(
+                vec![PriceLevel {
+                    price: QuoteCurrency::new(100, 0),
+                    quantity: BaseCurrency::new(5, 0),
+                }],
+                vec![PriceLevel {
+                    price: QuoteCurrency::new(101, 0),
+                    quantity: BaseCurrency::new(2, 0),
+                }],
+            ),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let mut entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(101, 0), qty).unwrap();
+    entry.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    let (entry, [take_profit, stop_loss]) = exchange
+        .submit_bracket_order(entry, take_profit.into(), stop_loss.into())
+        .unwrap();
+    // The depth only fills part of the entry, the remainder of which is cancelled.
+    let filled_qty = BaseCurrency::new(2, 0);
+    assert_eq!(entry.filled_quantity(), filled_qty);
+    assert_eq!(exchange.order_events(), &vec![
+        OrderEvent::Cancelled {
+            order_id: entry.id(),
+            reason: CancelReason::UnfilledRemainder,
+        },
+        OrderEvent::Activated {
+            order_id: take_profit.id(),
+            quantity: filled_qty,
+        },
+        OrderEvent::Activated {
+            order_id: stop_loss.id(),
+            quantity: filled_qty,
+        },
+    ]);
+
+    // The exits protect the filled part, and filling the take-profit cancels the stop-loss.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(111, 0),
+            quantity: filled_qty,
+            side: Side::Buy,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: stop_loss.id(),
+        reason: CancelReason::LinkedOrder,
+    }]);
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn bracket_order_unfilled_immediate_or_cancel_entry() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let mut entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(99, 0), qty).unwrap();
+    entry.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    let take_profit = LimitOrder::new(Side::Sell, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    let (entry, _) = exchange
+        .submit_bracket_order(entry, take_profit.into(), stop_loss.into())
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: entry.id(),
+        reason: CancelReason::UnfilledRemainder,
+    }]);
+
+    // The group is dropped along with the entry, so the exits are never activated.
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: qty,
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+    assert!(exchange.active_limit_orders().is_empty());
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+fn bracket_order_invalid_exit_side() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    let entry = LimitOrder::new(Side::Buy, QuoteCurrency::new(99, 0), qty).unwrap();
+    let take_profit = LimitOrder::new(Side::Buy, QuoteCurrency::new(110, 0), qty).unwrap();
+    let stop_loss = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(90, 0), qty).unwrap();
+    assert_eq!(
+        exchange.submit_bracket_order(entry, take_profit.into(), stop_loss.into()),
+        Err(Error::OrderError(OrderError::InvalidBracketExitSide))
+    );
+    assert!(exchange.active_limit_orders().is_empty());
+}
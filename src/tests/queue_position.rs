@@ -0,0 +1,192 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+    PriceLevel {
+        price: QuoteCurrency::new(price, 0),
+        quantity: BaseCurrency::new(quantity, 0),
+    }
+}
+
+fn exchange_with_queue_position_tracking() -> Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>
+{
+    let config = mock_exchange_linear()
+        .config()
+        .clone()
+        .with_queue_position_tracking(true);
+    let mut exchange = Exchange::new(config);
+    exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(vec![level(100, 3), level(99, 5)], vec![
+                level(101, 2),
+                level(102, 5),
+            ]),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange
+}
+
+fn sell_trade(quantity: i64, ts: i64) -> Trade<i64, 5, BaseCurrency<i64, 5>> {
+    Trade {
+        price: QuoteCurrency::new(100, 0),
+        quantity: BaseCurrency::new(quantity, 0),
+        side: Side::Sell,
+        timestamp_exchange_ns: ts.into(),
+    }
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn queue_position_consumed_by_trades() {
+    let mut exchange = exchange_with_queue_position_tracking();
+    let limit_price = QuoteCurrency::new(100, 0);
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(Side::Buy, limit_price, BaseCurrency::new(2, 0)).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(order.queue_ahead(), Some(BaseCurrency::new(3, 0)));
+
+    // The trade only consumes part of the queue ahead.
+    assert!(exchange.update_state(&sell_trade(2, 1)).unwrap().is_empty());
+    let queue_ahead = |exchange: &Exchange<_, 5, _, _>| {
+        exchange
+            .active_limit_orders()
+            .get_by_id(order.id(), Side::Buy)
+            .unwrap()
+            .queue_ahead()
+    };
+    assert_eq!(queue_ahead(&exchange), Some(BaseCurrency::new(1, 0)));
+
+    // The rest of the queue is consumed, so the order fills at the touch.
+    let fills = exchange.update_state(&sell_trade(2, 2)).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(
+        &fills[0],
+        LimitOrderFill::PartiallyFilled { filled_quantity, .. } if *filled_quantity == BaseCurrency::new(1, 0)
+    ));
+    assert_eq!(queue_ahead(&exchange), Some(BaseCurrency::zero()));
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(BaseCurrency::new(1, 0), limit_price))
+    );
+
+    let fills = exchange.update_state(&sell_trade(2, 3)).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(&fills[0], LimitOrderFill::FullyFilled { .. }));
+    assert!(exchange.active_limit_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn queue_position_consumed_by_cancellations() {
+    let mut exchange = exchange_with_queue_position_tracking();
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(100, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    // The visible quantity at the level shrinks without any trades.
+    exchange
+        .update_state(&L2Delta {
+            bids: vec![level(100, 1)],
+            asks: Vec::new(),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(
+        exchange
+            .active_limit_orders()
+            .get_by_id(order.id(), Side::Buy)
+            .unwrap()
+            .queue_ahead(),
+        Some(BaseCurrency::new(1, 0))
+    );
+
+    let fills = exchange.update_state(&sell_trade(3, 2)).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(&fills[0], LimitOrderFill::FullyFilled { .. }));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn queue_position_seeded_from_sized_bba() {
+    let config = mock_exchange_linear()
+        .config()
+        .clone()
+        .with_queue_position_tracking(true);
+    let mut exchange = Exchange::<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>::new(config);
+    exchange
+        .update_state(&SizedBba {
+            bid: QuoteCurrency::new(100, 0),
+            bid_quantity: BaseCurrency::new(4, 0),
+            ask: QuoteCurrency::new(101, 0),
+            ask_quantity: BaseCurrency::new(1, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Sell,
+                QuoteCurrency::new(101, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(order.queue_ahead(), Some(BaseCurrency::new(1, 0)));
+
+    // The best bid and ask moved away without any size, so the depth is stale.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(99, 0),
+            ask: QuoteCurrency::new(100, 0),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(98, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(order.queue_ahead(), None);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn queue_position_not_tracked_by_default() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&L2Book {
+            depth: OrderBookDepth::new(vec![level(100, 3)], vec![level(101, 2)]),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let order = exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(100, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(order.queue_ahead(), None);
+
+    // The worst possible queue position is assumed.
+    assert!(exchange.update_state(&sell_trade(5, 1)).unwrap().is_empty());
+}
@@ -0,0 +1,189 @@
+use crate::{mock_exchange_linear, prelude::*, test_fee_taker};
+
+#[test]
+#[tracing_test::traced_test]
+fn re_pricing_marketable() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let qty = BaseCurrency::new(5, 0);
+    let mut order = LimitOrder::new(Side::Buy, QuoteCurrency::new(105, 0), qty).unwrap();
+    order.set_re_pricing(RePricing::Marketable);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.remaining_quantity(), BaseCurrency::zero());
+    assert_eq!(order.filled_quantity(), qty);
+
+    // The order fills at the ask it crosses, not at its limit price.
+    let fill_price = QuoteCurrency::new(101, 0);
+    assert_eq!(order.state().filled_quantity(), &FilledQuantity::Filled {
+        cumulative_qty: qty,
+        avg_price: fill_price,
+    });
+    let notional = QuoteCurrency::convert_from(qty, fill_price);
+    let fee = notional * *test_fee_taker().as_ref();
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, fill_price))
+    );
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(1000, 0) - notional - fee)
+            .position_margin(notional)
+            .order_margin(QuoteCurrency::zero())
+            .total_fees_paid(fee)
+            .build()
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn re_pricing_marketable_rejected() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Sell,
+        QuoteCurrency::new(99, 0),
+        BaseCurrency::new(15, 0),
+    )
+    .unwrap();
+    order.set_re_pricing(RePricing::Marketable);
+    assert_eq!(
+        exchange.submit_limit_order(order),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test_case::test_matrix([Side::Buy, Side::Sell])]
+#[tracing_test::traced_test]
+fn re_pricing_price_adjust(side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(102, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let (limit_price, adjusted_price) = match side {
+        Side::Buy => (QuoteCurrency::new(103, 0), QuoteCurrency::new(101, 0)),
+        Side::Sell => (QuoteCurrency::new(99, 0), QuoteCurrency::new(101, 0)),
+    };
+    let mut order = LimitOrder::new(side, limit_price, BaseCurrency::new(5, 0)).unwrap();
+    order.set_re_pricing(RePricing::PriceAdjust);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.limit_price(), adjusted_price);
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(505, 0)
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn re_pricing_hide_not_slide() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let limit_price = QuoteCurrency::new(101, 0);
+    let mut order = LimitOrder::new(Side::Buy, limit_price, BaseCurrency::new(5, 0)).unwrap();
+    order.set_re_pricing(RePricing::HideNotSlide);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.limit_price(), limit_price);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(exchange.hidden_limit_orders(), &[order.clone()]);
+    // The hidden order still requires order margin.
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(505, 0)
+    );
+
+    // The away market quotation is still locked.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(99, 0),
+            ask: QuoteCurrency::new(100, 0),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert!(exchange.active_limit_orders().is_empty());
+
+    // The ask is lifted, so the order is placed into the book at its original price.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(102, 0),
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert!(exchange.hidden_limit_orders().is_empty());
+    assert_eq!(
+        exchange
+            .active_limit_orders()
+            .get_by_id(order.id(), Side::Buy),
+        Some(&order)
+    );
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(505, 0)
+    );
+
+    exchange
+        .cancel_limit_order(CancelBy::OrderId(order.id()))
+        .unwrap();
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn re_pricing_hide_not_slide_cancel() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Sell,
+        QuoteCurrency::new(99, 0),
+        BaseCurrency::new(5, 0),
+    )
+    .unwrap();
+    order.set_re_pricing(RePricing::HideNotSlide);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(exchange.hidden_limit_orders().len(), 1);
+    assert_eq!(
+        exchange.cancel_limit_order(CancelBy::OrderId(order.id())),
+        Ok(order)
+    );
+    assert!(exchange.hidden_limit_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
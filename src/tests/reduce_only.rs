@@ -0,0 +1,188 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test_case::test_matrix([Side::Buy, Side::Sell])]
+#[tracing_test::traced_test]
+fn reduce_only_rejected_without_position(side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = MarketOrder::new(side, BaseCurrency::new(5, 0)).unwrap();
+    order.set_reduce_only(true);
+    assert_eq!(
+        exchange.submit_market_order(order),
+        Err(Error::RiskError(
+            RiskError::ReduceOnlyOrderWouldIncreasePosition
+        ))
+    );
+
+    let limit_price = match side {
+        Side::Buy => QuoteCurrency::new(99, 0),
+        Side::Sell => QuoteCurrency::new(102, 0),
+    };
+    let mut order = LimitOrder::new(side, limit_price, BaseCurrency::new(5, 0)).unwrap();
+    order.set_reduce_only(true);
+    assert_eq!(
+        exchange.submit_limit_order(order),
+        Err(Error::RiskError(
+            RiskError::ReduceOnlyOrderWouldIncreasePosition
+        ))
+    );
+    assert!(exchange.active_limit_orders().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn reduce_only_market_order_clamped() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let mut order = MarketOrder::new(Side::Sell, BaseCurrency::new(10, 0)).unwrap();
+    order.set_reduce_only(true);
+    let order = exchange.submit_market_order(order).unwrap();
+    assert_eq!(order.quantity(), qty);
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(exchange.balances().position_margin(), QuoteCurrency::zero());
+}
+
+#[test_case::test_matrix([Side::Buy, Side::Sell])]
+#[tracing_test::traced_test]
+fn reduce_only_close_position(side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(side, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+
+    let order = MarketOrder::close_position(exchange.position()).unwrap();
+    assert_eq!(order.side(), side.inverted());
+    exchange.submit_market_order(order).unwrap();
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(
+        MarketOrder::<_, _, _, NoUserOrderId, _>::close_position(exchange.position()),
+        Err(OrderError::NoPositionToClose)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn reduce_only_limit_order_clamped_and_cancelled() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+
+    let order =
+        LimitOrder::close_position(exchange.position(), QuoteCurrency::new(110, 0)).unwrap();
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.remaining_quantity(), BaseCurrency::new(5, 0));
+    // A reduce-only order does not require any order margin.
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+
+    // The position shrinks, so the reduce-only order is clamped.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(3, 0)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange
+            .active_limit_orders()
+            .get_by_id(order.id(), Side::Sell)
+            .unwrap()
+            .remaining_quantity(),
+        BaseCurrency::new(2, 0)
+    );
+    assert_eq!(
+        exchange.order_events().last(),
+        Some(&OrderEvent::QuantityAdjusted {
+            order_id: order.id(),
+            quantity: BaseCurrency::new(2, 0),
+        })
+    );
+
+    // The position is closed, so the reduce-only order is cancelled.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(2, 0)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.order_events().last(),
+        Some(&OrderEvent::Cancelled {
+            order_id: order.id(),
+            reason: CancelReason::ReduceOnly,
+        })
+    );
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn reduce_only_limit_orders_never_flip_position() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let order_0 =
+        LimitOrder::close_position(exchange.position(), QuoteCurrency::new(102, 0)).unwrap();
+    let order_0 = exchange.submit_limit_order(order_0).unwrap();
+    let order_1 =
+        LimitOrder::close_position(exchange.position(), QuoteCurrency::new(103, 0)).unwrap();
+    let order_1 = exchange.submit_limit_order(order_1).unwrap();
+
+    let fills = exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(104, 0),
+            quantity: BaseCurrency::new(10, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(fills.len(), 1);
+    assert!(matches!(
+        &fills[0],
+        LimitOrderFill::FullyFilled { order_after_fill, .. } if order_after_fill.id() == order_0.id()
+    ));
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: order_1.id(),
+        reason: CancelReason::ReduceOnly,
+    }]);
+}
@@ -0,0 +1,196 @@
+use std::num::NonZeroUsize;
+
+use const_decimal::Decimal;
+
+use crate::{prelude::*, test_fee_maker, test_fee_taker, tests::test_utils::test_bba};
+
+fn tier(
+    max_notional: i64,
+    max_leverage: u8,
+    maintenance_margin_bps: i64,
+) -> RiskLimitTier<i64, 5, QuoteCurrency<i64, 5>> {
+    RiskLimitTier::new(
+        QuoteCurrency::new(max_notional, 0),
+        Leverage::new(max_leverage).unwrap(),
+        Decimal::try_from_scaled(maintenance_margin_bps, 4).unwrap(),
+    )
+    .unwrap()
+}
+
+fn contract_spec() -> ContractSpecification<i64, 5, BaseCurrency<i64, 5>> {
+    ContractSpecification::new(
+        leverage!(10),
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        test_fee_maker(),
+        test_fee_taker(),
+    )
+    .unwrap()
+}
+
+/// An exchange with a leverage of 10 and a `starting_balance`, whose positions above a notional value of 1000
+/// fall into a tier with a maximum leverage of 5 and a maintenance margin of 10%.
+fn exchange_with_tiers(
+    starting_balance: QuoteCurrency<i64, 5>,
+) -> Exchange<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId> {
+    let contract_spec = contract_spec()
+        .with_risk_limit_tiers(vec![tier(1000, 10, 500), tier(2000, 5, 1000)])
+        .unwrap();
+    let config = Config::new(
+        starting_balance,
+        NonZeroUsize::new(10).unwrap(),
+        contract_spec,
+        OrderRateLimits::default(),
+    )
+    .unwrap();
+    let mut exchange = Exchange::new(config);
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange
+}
+
+#[test]
+fn risk_limit_tiers_must_ascend() {
+    assert_eq!(
+        contract_spec()
+            .with_risk_limit_tiers(vec![tier(2000, 5, 1000), tier(1000, 10, 500)])
+            .unwrap_err(),
+        ConfigError::InvalidRiskLimitTiers
+    );
+    assert_eq!(
+        contract_spec()
+            .with_risk_limit_tiers(vec![tier(1000, 5, 1000), tier(2000, 10, 500)])
+            .unwrap_err(),
+        ConfigError::InvalidRiskLimitTiers
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn risk_limit_tiers_position_margin() {
+    let mut exchange = exchange_with_tiers(QuoteCurrency::new(1000, 0));
+
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(50, 0)
+    );
+
+    // Growing into the second tier margins the whole position at 20%.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(10, 0)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(300, 0)
+    );
+
+    // Shrinking back into the first tier releases the margin again.
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(10, 0)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(50, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn risk_limit_tiers_reject_orders_beyond_last_tier() {
+    let mut exchange = exchange_with_tiers(QuoteCurrency::new(1000, 0));
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(15, 0)).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(6, 0)).unwrap()),
+        Err(Error::RiskError(RiskError::RiskLimitExceeded))
+    );
+    assert_eq!(
+        exchange.submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(99, 0),
+                BaseCurrency::new(6, 0)
+            )
+            .unwrap()
+        ),
+        Err(Error::RiskError(RiskError::RiskLimitExceeded))
+    );
+    // Flipping into a short beyond the last tier is rejected as well.
+    assert_eq!(
+        exchange
+            .submit_market_order(MarketOrder::new(Side::Sell, BaseCurrency::new(36, 0)).unwrap()),
+        Err(Error::RiskError(RiskError::RiskLimitExceeded))
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn risk_limit_tiers_liquidation_price() {
+    let mut exchange = exchange_with_tiers(QuoteCurrency::new(1000, 0));
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(15, 0)).unwrap())
+        .unwrap();
+
+    // With 20% initial and 10% maintenance margin, the position is liquidated below 90 instead of 95.
+    exchange.update_state(&test_bba(92, 93, 0)).unwrap();
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 0)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn risk_limit_tiers_order_margin() {
+    let mut exchange = exchange_with_tiers(QuoteCurrency::new(300, 0));
+    exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(99, 0),
+                BaseCurrency::new(5, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    // The orders stay within the first tier, so the order margin is reserved at the leverage of 10.
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(495, 1)
+    );
+
+    // Both orders would grow the position into the second tier, whose 20% of 1584 exceed the balance of 300.
+    assert_eq!(
+        exchange.submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(99, 0),
+                BaseCurrency::new(11, 0)
+            )
+            .unwrap()
+        ),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(99, 0),
+                BaseCurrency::new(10, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    // All orders are reserved at the 20% of the second tier they would grow the position into.
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(297, 0)
+    );
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(3, 0));
+}
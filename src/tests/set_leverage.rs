@@ -0,0 +1,134 @@
+use crate::{
+    mock_exchange_linear,
+    prelude::*,
+    tests::test_utils::{mock_exchange_linear_with_long, test_bba},
+};
+
+#[test]
+#[tracing_test::traced_test]
+fn set_leverage_recomputes_margin() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    exchange
+        .submit_limit_order(
+            LimitOrder::new(
+                Side::Buy,
+                QuoteCurrency::new(98, 0),
+                BaseCurrency::new(2, 0),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    let fees = QuoteCurrency::new(3, 1);
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(1000, 0) - QuoteCurrency::new(696, 0) - fees)
+            .position_margin(QuoteCurrency::new(500, 0))
+            .order_margin(QuoteCurrency::new(196, 0))
+            .total_fees_paid(fees)
+            .build()
+    );
+
+    exchange.set_leverage(leverage!(5)).unwrap();
+    assert_eq!(
+        exchange.config().contract_spec().init_margin_req(),
+        leverage!(5).init_margin_req()
+    );
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(1000, 0) - QuoteCurrency::new(1392, 1) - fees)
+            .position_margin(QuoteCurrency::new(100, 0))
+            .order_margin(QuoteCurrency::new(392, 1))
+            .total_fees_paid(fees)
+            .build()
+    );
+
+    exchange.set_leverage(leverage!(1)).unwrap();
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(500, 0)
+    );
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(196, 0)
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn set_leverage_not_enough_available_balance() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange.set_leverage(leverage!(5)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(40, 0)).unwrap())
+        .unwrap();
+    let balances = exchange.balances().clone();
+    assert_eq!(balances.position_margin(), QuoteCurrency::new(800, 0));
+
+    assert_eq!(
+        exchange.set_leverage(leverage!(1)),
+        Err(RiskError::NotEnoughAvailableBalance)
+    );
+    assert_eq!(exchange.balances(), &balances);
+    assert_eq!(
+        exchange.config().contract_spec().init_margin_req(),
+        leverage!(5).init_margin_req()
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn set_leverage_moves_liquidation_price() {
+    let mut exchange = mock_exchange_linear();
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    // At a leverage of 1 the position would only be liquidated below 50.
+    exchange.update_state(&test_bba(91, 92, 0)).unwrap();
+
+    exchange.set_leverage(leverage!(5)).unwrap();
+    assert_eq!(
+        exchange.update_state(&test_bba(89, 90, 0)).unwrap_err(),
+        RiskError::Liquidate
+    );
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn set_leverage_cross_margin_unrealized_profit() {
+    // The position margin of 1000 exceeds the wallet balance of 997.6.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Isolated,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(110, 111, 1)).unwrap();
+    assert_eq!(
+        exchange.set_leverage(leverage!(4)),
+        Err(RiskError::NotEnoughAvailableBalance)
+    );
+
+    // The unrealized profit of 400 backs the position margin the available balance can not cover.
+    let mut exchange = mock_exchange_linear_with_long(
+        MarginMode::Cross,
+        leverage!(5),
+        BaseCurrency::new(40, 0),
+        |config| config,
+    );
+    exchange.update_state(&test_bba(110, 111, 1)).unwrap();
+    exchange.set_leverage(leverage!(4)).unwrap();
+    assert_eq!(exchange.balances().available(), QuoteCurrency::zero());
+    assert_eq!(
+        exchange.balances().position_margin(),
+        QuoteCurrency::new(9976, 1)
+    );
+}
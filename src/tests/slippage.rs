@@ -0,0 +1,116 @@
+use const_decimal::Decimal;
+
+use crate::{mock_exchange_linear, prelude::*, test_fee_taker};
+
+fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+    PriceLevel {
+        price: QuoteCurrency::new(price, 0),
+        quantity: BaseCurrency::new(quantity, 0),
+    }
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn slippage_market_order_walks_depth() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange.set_depth(OrderBookDepth::new(
+        vec![level(100, 5), level(99, 5)],
+        vec![level(101, 2), level(102, 3)],
+    ));
+
+    let qty = BaseCurrency::new(5, 0);
+    let order = exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+    let avg_fill_price = QuoteCurrency::new(1016, 1);
+    assert_eq!(order.state().avg_fill_price(), avg_fill_price);
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, avg_fill_price))
+    );
+    let notional = QuoteCurrency::convert_from(qty, avg_fill_price);
+    let fee = notional * *test_fee_taker().as_ref();
+    assert_eq!(
+        exchange.balances(),
+        &Balances::builder()
+            .available(QuoteCurrency::new(1000, 0) - notional - fee)
+            .position_margin(notional)
+            .order_margin(QuoteCurrency::zero())
+            .total_fees_paid(fee)
+            .build()
+    );
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn slippage_market_order_ignores_stale_depth() {
+    let mut exchange = mock_exchange_linear();
+    exchange.set_depth(OrderBookDepth::new(vec![level(100, 5)], vec![
+        level(101, 1),
+        level(102, 5),
+    ]));
+    assert_eq!(exchange.market_state().bid(), QuoteCurrency::new(100, 0));
+    assert_eq!(exchange.market_state().ask(), QuoteCurrency::new(101, 0));
+
+    // The best bid and ask moved away from the depth snapshot.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(102, 0),
+            ask: QuoteCurrency::new(103, 0),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    let order = exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    assert_eq!(order.state().avg_fill_price(), QuoteCurrency::new(103, 0));
+}
+
+#[test_case::test_case(SlippageModel::FixedTicks(2), QuoteCurrency::new(103, 0))]
+#[test_case::test_case(
+    SlippageModel::FractionOfNotional(Decimal::try_from_scaled(1, 2).unwrap()),
+    QuoteCurrency::new(10201, 2)
+)]
+#[test_case::test_case(
+    SlippageModel::SquareRootImpact {
+        coefficient: Decimal::try_from_scaled(1, 1).unwrap(),
+        reference_quantity: BaseCurrency::new(20, 0),
+    },
+    QuoteCurrency::new(10605, 2)
+)]
+#[tracing_test::traced_test]
+fn slippage_model_without_depth(
+    slippage_model: SlippageModel<i64, 5, BaseCurrency<i64, 5>>,
+    expected_fill_price: QuoteCurrency<i64, 5>,
+) {
+    let config = mock_exchange_linear()
+        .config()
+        .clone()
+        .with_slippage_model(slippage_model)
+        .unwrap();
+    let mut exchange = Exchange::<i64, 5, BaseCurrency<i64, 5>, NoUserOrderId>::new(config);
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let qty = BaseCurrency::new(5, 0);
+    let order = exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+    assert_eq!(order.state().avg_fill_price(), expected_fill_price);
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, expected_fill_price))
+    );
+}
@@ -0,0 +1,197 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test]
+#[tracing_test::traced_test]
+fn stop_market_sell_triggered_by_last_trade() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let order = StopOrder::new_stop_market(Side::Sell, QuoteCurrency::new(95, 0), qty).unwrap();
+    let order = exchange.submit_stop_order(order).unwrap();
+    assert_eq!(exchange.active_stop_orders(), &[order.clone()]);
+    // The stop order reduces the position, so no order margin is required.
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(96, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+    assert_eq!(exchange.active_stop_orders().len(), 1);
+
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(95, 0),
+            quantity: BaseCurrency::new(1, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(exchange.order_events().len(), 1);
+    match &exchange.order_events()[0] {
+        OrderEvent::StopTriggered {
+            stop_order,
+            reference_price,
+            triggered_order: TriggeredOrder::Market(filled_order),
+        } => {
+            assert_eq!(stop_order, &order);
+            assert_eq!(*reference_price, QuoteCurrency::new(95, 0));
+            assert_eq!(filled_order.state().meta().id(), order.id());
+            assert_eq!(
+                filled_order.state().avg_fill_price(),
+                QuoteCurrency::new(100, 0)
+            );
+        }
+        event => panic!("Unexpected order event {event:?}"),
+    }
+
+    // Events are only reported once.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 3.into(),
+        })
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn stop_limit_buy_triggered_by_bid_ask() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = StopOrder::new_stop_limit(
+        Side::Buy,
+        QuoteCurrency::new(105, 0),
+        QuoteCurrency::new(106, 0),
+        BaseCurrency::new(2, 0),
+    )
+    .unwrap();
+    order.set_trigger_by(TriggerBy::BidAsk);
+    let order = exchange.submit_stop_order(order).unwrap();
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(212, 0)
+    );
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(788, 0));
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(104, 0),
+            ask: QuoteCurrency::new(105, 0),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(212, 0)
+    );
+    match &exchange.order_events()[..] {
+        [
+            OrderEvent::StopTriggered {
+                triggered_order: TriggeredOrder::Limit(limit_order),
+                ..
+            },
+        ] => {
+            assert_eq!(limit_order.id(), order.id());
+            assert_eq!(limit_order.limit_price(), QuoteCurrency::new(106, 0));
+            assert_eq!(limit_order.remaining_quantity(), BaseCurrency::new(2, 0));
+        }
+        events => panic!("Unexpected order events {events:?}"),
+    }
+}
+
+#[test_case::test_matrix(
+    [Side::Buy, Side::Sell],
+    [TriggerBy::MarkPrice, TriggerBy::BidAsk]
+)]
+fn stop_order_rejected_if_triggered_immediately(side: Side, trigger_by: TriggerBy) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let trigger_price = match side {
+        Side::Buy => QuoteCurrency::new(100, 0),
+        Side::Sell => QuoteCurrency::new(101, 0),
+    };
+    let mut order =
+        StopOrder::new_stop_market(side, trigger_price, BaseCurrency::new(1, 0)).unwrap();
+    order.set_trigger_by(trigger_by);
+    assert!(matches!(
+        exchange.submit_stop_order(order),
+        Err(Error::OrderError(
+            OrderError::StopOrderWouldTriggerImmediately { .. }
+        ))
+    ));
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+fn cancel_stop_order() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let order = StopOrder::new_stop_market(
+        Side::Sell,
+        QuoteCurrency::new(90, 0),
+        BaseCurrency::new(1, 0),
+    )
+    .unwrap();
+    let order = exchange.submit_stop_order(order).unwrap();
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(90, 0)
+    );
+
+    assert_eq!(
+        exchange.cancel_stop_order(CancelBy::OrderId(order.id())),
+        Ok(order.clone())
+    );
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(
+        exchange.cancel_stop_order(CancelBy::OrderId(order.id())),
+        Err(Error::OrderIdNotFound {
+            order_id: order.id()
+        })
+    );
+}
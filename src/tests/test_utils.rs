@@ -0,0 +1,55 @@
+//! Fixtures shared by the tests.
+
+use std::num::NonZeroUsize;
+
+use const_decimal::Decimal;
+
+use crate::{DECIMALS, prelude::*, test_fee_maker, test_fee_taker, utils::NoUserOrderId};
+
+/// Constructs a mock exchange (for linear futures) for testing with a starting balance of 1000,
+/// the `margin_mode` and `leverage`, a maintenance margin of half the initial margin and the test fees.
+/// The `configure` function adjusts the `Config` before the exchange is created.
+/// After a best bid and ask of 99 and 100, a market buy of `quantity` is filled at 100 if the `quantity` is not zero.
+pub(crate) fn mock_exchange_linear_with_long(
+    margin_mode: MarginMode,
+    leverage: Leverage<i64, DECIMALS>,
+    quantity: BaseCurrency<i64, DECIMALS>,
+    configure: impl FnOnce(
+        Config<i64, DECIMALS, QuoteCurrency<i64, DECIMALS>>,
+    ) -> Config<i64, DECIMALS, QuoteCurrency<i64, DECIMALS>>,
+) -> Exchange<i64, DECIMALS, BaseCurrency<i64, DECIMALS>, NoUserOrderId> {
+    let contract_spec = ContractSpecification::new(
+        leverage,
+        Decimal::try_from_scaled(5, 1).unwrap(),
+        PriceFilter::default(),
+        QuantityFilter::default(),
+        test_fee_maker(),
+        test_fee_taker(),
+    )
+    .expect("works");
+    let config = Config::new(
+        QuoteCurrency::new(1000, 0),
+        NonZeroUsize::new(10).unwrap(),
+        contract_spec,
+        OrderRateLimits::default(),
+    )
+    .unwrap()
+    .with_margin_mode(margin_mode);
+    let mut exchange = Exchange::new(configure(config));
+    exchange.update_state(&test_bba(99, 100, 0)).unwrap();
+    if !quantity.is_zero() {
+        exchange
+            .submit_market_order(MarketOrder::new(Side::Buy, quantity).unwrap())
+            .unwrap();
+    }
+    exchange
+}
+
+/// A best bid and ask update used in tests.
+pub(crate) fn test_bba(bid: i64, ask: i64, timestamp_exchange_ns: i64) -> Bba<i64, DECIMALS> {
+    Bba {
+        bid: QuoteCurrency::new(bid, 0),
+        ask: QuoteCurrency::new(ask, 0),
+        timestamp_exchange_ns: timestamp_exchange_ns.into(),
+    }
+}
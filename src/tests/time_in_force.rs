@@ -0,0 +1,189 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test_case::test_matrix([TimeInForce::ImmediateOrCancel, TimeInForce::FillOrKill])]
+#[tracing_test::traced_test]
+fn time_in_force_immediate_filled(time_in_force: TimeInForce) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let qty = BaseCurrency::new(5, 0);
+    let limit_price = QuoteCurrency::new(101, 0);
+    let mut order = LimitOrder::new(Side::Buy, limit_price, qty).unwrap();
+    order.set_time_in_force(time_in_force);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.filled_quantity(), qty);
+    assert_eq!(order.remaining_quantity(), BaseCurrency::zero());
+    assert!(exchange.order_events().is_empty());
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.position(),
+        &Position::Long(PositionInner::new(qty, limit_price))
+    );
+}
+
+#[test_case::test_matrix(
+    [TimeInForce::ImmediateOrCancel, TimeInForce::FillOrKill],
+    [Side::Buy, Side::Sell]
+)]
+#[tracing_test::traced_test]
+fn time_in_force_immediate_filled_at_quotation(time_in_force: TimeInForce, side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    // The limit prices cross the away market quotation by several ticks.
+    let (limit_price, fill_price) = match side {
+        Side::Buy => (QuoteCurrency::new(105, 0), QuoteCurrency::new(101, 0)),
+        Side::Sell => (QuoteCurrency::new(95, 0), QuoteCurrency::new(100, 0)),
+    };
+    let qty = BaseCurrency::new(5, 0);
+    let mut order = LimitOrder::new(side, limit_price, qty).unwrap();
+    order.set_time_in_force(time_in_force);
+    let order = exchange.submit_limit_order(order).unwrap();
+    // Without a depth snapshot, even a `FillOrKill` order is filled entirely at the quotation.
+    assert_eq!(order.state().filled_quantity(), &FilledQuantity::Filled {
+        cumulative_qty: qty,
+        avg_price: fill_price,
+    });
+    assert!(exchange.order_events().is_empty());
+    let position = match side {
+        Side::Buy => Position::Long(PositionInner::new(qty, fill_price)),
+        Side::Sell => Position::Short(PositionInner::new(qty, fill_price)),
+    };
+    assert_eq!(exchange.position(), &position);
+}
+
+#[test_case::test_matrix(
+    [TimeInForce::ImmediateOrCancel, TimeInForce::FillOrKill],
+    [Side::Buy, Side::Sell]
+)]
+#[tracing_test::traced_test]
+fn time_in_force_immediate_cancelled(time_in_force: TimeInForce, side: Side) {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let limit_price = match side {
+        Side::Buy => QuoteCurrency::new(100, 0),
+        Side::Sell => QuoteCurrency::new(101, 0),
+    };
+    let mut order = LimitOrder::new(side, limit_price, BaseCurrency::new(5, 0)).unwrap();
+    order.set_time_in_force(time_in_force);
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(order.filled_quantity(), BaseCurrency::zero());
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: order.id(),
+        reason: CancelReason::UnfilledRemainder,
+    }]);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn time_in_force_good_til_date() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(99, 0),
+        BaseCurrency::new(5, 0),
+    )
+    .unwrap();
+    order.set_time_in_force(TimeInForce::GoodTilDate(0.into()));
+    assert_eq!(
+        exchange.submit_limit_order(order.clone()),
+        Err(Error::OrderError(OrderError::GoodTilDateExpired))
+    );
+
+    order.set_time_in_force(TimeInForce::GoodTilDate(10.into()));
+    let order = exchange.submit_limit_order(order).unwrap();
+    assert_eq!(
+        exchange.balances().order_margin(),
+        QuoteCurrency::new(495, 0)
+    );
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 9.into(),
+        })
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+    assert_eq!(exchange.active_limit_orders().num_active(), 1);
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 10.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.order_events(), &vec![OrderEvent::Cancelled {
+        order_id: order.id(),
+        reason: CancelReason::Expired,
+    }]);
+    assert!(exchange.active_limit_orders().is_empty());
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+    assert_eq!(exchange.balances().available(), QuoteCurrency::new(1000, 0));
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn time_in_force_good_til_date_not_filled_once_expired() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+
+    let mut order = LimitOrder::new(
+        Side::Buy,
+        QuoteCurrency::new(99, 0),
+        BaseCurrency::new(5, 0),
+    )
+    .unwrap();
+    order.set_time_in_force(TimeInForce::GoodTilDate(10.into()));
+    exchange.submit_limit_order(order).unwrap();
+
+    // The order expires before the trade at its expiry could fill it.
+    let fills = exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(98, 0),
+            quantity: BaseCurrency::new(5, 0),
+            side: Side::Sell,
+            timestamp_exchange_ns: 10.into(),
+        })
+        .unwrap();
+    assert!(fills.is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
@@ -0,0 +1,84 @@
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test]
+#[tracing_test::traced_test]
+fn trade_ledger_disabled() {
+    let exchange = mock_exchange_linear();
+    assert!(exchange.trade_ledger().is_none());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn trade_ledger_round_trip() {
+    let config = mock_exchange_linear()
+        .config()
+        .clone()
+        .with_trade_ledger(LedgerCapacity::Unbounded);
+    let mut exchange = Exchange::new(config);
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(99, 0),
+            ask: QuoteCurrency::new(100, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, BaseCurrency::new(5, 0)).unwrap())
+        .unwrap();
+    let order = LimitOrder::new(
+        Side::Sell,
+        QuoteCurrency::new(110, 0),
+        BaseCurrency::new(5, 0),
+    )
+    .unwrap();
+    exchange.submit_limit_order(order).unwrap();
+    exchange
+        .update_state(&Trade {
+            price: QuoteCurrency::new(111, 0),
+            quantity: BaseCurrency::new(5, 0),
+            side: Side::Buy,
+            timestamp_exchange_ns: 10.into(),
+        })
+        .unwrap();
+    assert_eq!(exchange.position(), &Position::Neutral);
+
+    let ledger = exchange.trade_ledger().unwrap();
+    assert_eq!(ledger.capacity(), LedgerCapacity::Unbounded);
+    assert_eq!(ledger.fills(), &[
+        Fill {
+            order_id: 0.into(),
+            side: Side::Buy,
+            quantity: BaseCurrency::new(5, 0),
+            price: QuoteCurrency::new(100, 0),
+            fee: QuoteCurrency::new(3, 1),
+            liquidity: Liquidity::Taker,
+            realized_pnl: QuoteCurrency::new(0, 0),
+            timestamp_ns: 0.into(),
+        },
+        Fill {
+            order_id: 1.into(),
+            side: Side::Sell,
+            quantity: BaseCurrency::new(5, 0),
+            price: QuoteCurrency::new(110, 0),
+            fee: QuoteCurrency::new(11, 2),
+            liquidity: Liquidity::Maker,
+            realized_pnl: QuoteCurrency::new(50, 0),
+            timestamp_ns: 10.into(),
+        },
+    ]);
+    assert_eq!(ledger.round_trips(), &[RoundTrip {
+        side: Side::Buy,
+        quantity: BaseCurrency::new(5, 0),
+        entry_price: QuoteCurrency::new(100, 0),
+        exit_price: QuoteCurrency::new(110, 0),
+        realized_pnl: QuoteCurrency::new(50, 0),
+        fees: QuoteCurrency::new(41, 2),
+        entry_ts_ns: 0.into(),
+        exit_ts_ns: 10.into(),
+    }]);
+    assert_eq!(ledger.round_trips()[0].holding_time_ns(), 10.into());
+    assert_eq!(
+        ledger.round_trips()[0].net_pnl(),
+        QuoteCurrency::new(4959, 2)
+    );
+}
@@ -0,0 +1,149 @@
+use const_decimal::Decimal;
+
+use crate::{mock_exchange_linear, prelude::*};
+
+#[test]
+#[tracing_test::traced_test]
+fn trailing_stop_absolute_offset() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let offset = TrailingOffset::Absolute(QuoteCurrency::new(5, 0));
+    let mut order = StopOrder::new_trailing_stop(Side::Sell, offset, qty).unwrap();
+    // There has not been any trade yet.
+    assert_eq!(
+        exchange.submit_stop_order(order.clone()),
+        Err(Error::OrderError(
+            OrderError::TrailingStopWithoutReferencePrice
+        ))
+    );
+    order.set_trigger_by(TriggerBy::BidAsk);
+    let order = exchange.submit_stop_order(order).unwrap();
+    assert_eq!(order.trigger_price(), QuoteCurrency::new(95, 0));
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(104, 0),
+            ask: QuoteCurrency::new(105, 0),
+            timestamp_exchange_ns: 1.into(),
+        })
+        .unwrap();
+    assert_eq!(
+        exchange.active_stop_orders()[0].trigger_price(),
+        QuoteCurrency::new(99, 0)
+    );
+
+    // The trail never moves back.
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(102, 0),
+            ask: QuoteCurrency::new(103, 0),
+            timestamp_exchange_ns: 2.into(),
+        })
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+    assert_eq!(
+        exchange.active_stop_orders()[0].trigger_price(),
+        QuoteCurrency::new(99, 0)
+    );
+
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(98, 0),
+            ask: QuoteCurrency::new(99, 0),
+            timestamp_exchange_ns: 3.into(),
+        })
+        .unwrap();
+    match &exchange.order_events()[..] {
+        [
+            OrderEvent::StopTriggered {
+                stop_order,
+                reference_price,
+                triggered_order: TriggeredOrder::Market(_),
+            },
+        ] => {
+            assert_eq!(stop_order.id(), order.id());
+            assert_eq!(*reference_price, QuoteCurrency::new(98, 0));
+        }
+        events => panic!("Unexpected order events {events:?}"),
+    }
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+    assert_eq!(exchange.balances().order_margin(), QuoteCurrency::zero());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn trailing_stop_fraction_offset_candles() {
+    let mut exchange = mock_exchange_linear();
+    exchange
+        .update_state(&Bba {
+            bid: QuoteCurrency::new(100, 0),
+            ask: QuoteCurrency::new(101, 0),
+            timestamp_exchange_ns: 0.into(),
+        })
+        .unwrap();
+    let qty = BaseCurrency::new(5, 0);
+    exchange
+        .submit_market_order(MarketOrder::new(Side::Buy, qty).unwrap())
+        .unwrap();
+
+    let offset = TrailingOffset::Fraction(Decimal::try_from_scaled(1, 1).unwrap());
+    let mut order = StopOrder::new_trailing_stop(Side::Sell, offset, qty).unwrap();
+    order.set_trigger_by(TriggerBy::BidAsk);
+    let order = exchange.submit_stop_order(order).unwrap();
+    assert_eq!(order.trigger_price(), QuoteCurrency::new(90, 0));
+
+    // The trail follows the candle high, not just the closing bid.
+    exchange
+        .update_state(
+            &Candle::new(
+                QuoteCurrency::new(110, 0),
+                QuoteCurrency::new(111, 0),
+                QuoteCurrency::new(105, 0),
+                QuoteCurrency::new(120, 0),
+                1.into(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert!(exchange.order_events().is_empty());
+    assert_eq!(
+        exchange.active_stop_orders()[0].trigger_price(),
+        QuoteCurrency::new(108, 0)
+    );
+
+    // The candle low retraces through the trail.
+    exchange
+        .update_state(
+            &Candle::new(
+                QuoteCurrency::new(110, 0),
+                QuoteCurrency::new(111, 0),
+                QuoteCurrency::new(105, 0),
+                QuoteCurrency::new(115, 0),
+                2.into(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    match &exchange.order_events()[..] {
+        [
+            OrderEvent::StopTriggered {
+                reference_price, ..
+            },
+        ] => assert_eq!(*reference_price, QuoteCurrency::new(105, 0)),
+        events => panic!("Unexpected order events {events:?}"),
+    }
+    assert!(exchange.active_stop_orders().is_empty());
+    assert_eq!(exchange.position(), &Position::Neutral);
+}
@@ -0,0 +1,442 @@
+use std::{collections::VecDeque, num::NonZeroUsize};
+
+use getset::{CopyGetters, Getters};
+use num_traits::Zero;
+
+use crate::types::{Currency, Mon, OrderId, QuoteCurrency, Side, TimestampNs};
+
+/// How many fills and round trips the `TradeLedger` retains.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerCapacity {
+    /// Retain every fill and round trip.
+    #[default]
+    Unbounded,
+    /// Retain only the most recent fills and round trips, dropping the oldest ones once full.
+    Bounded(NonZeroUsize),
+}
+
+/// Whether a fill provided or took liquidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    /// A resting limit order was filled.
+    Maker,
+    /// A market order, a marketable limit order or a liquidation took liquidity.
+    Taker,
+}
+
+impl std::fmt::Display for Liquidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// A single fill of an order, as recorded by the `TradeLedger`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fill<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The id of the filled order, assigned by the exchange.
+    pub order_id: OrderId,
+    /// The side of the filled order.
+    pub side: Side,
+    /// The filled quantity.
+    pub quantity: BaseOrQuote,
+    /// The price at which the quantity was filled.
+    pub price: QuoteCurrency<I, D>,
+    /// The trading fee of the fill, which is negative for a rebate.
+    pub fee: BaseOrQuote::PairedCurrency,
+    /// Whether the fill provided or took liquidity.
+    pub liquidity: Liquidity,
+    /// The profit and loss realized by reducing the position, excluding the fee.
+    pub realized_pnl: BaseOrQuote::PairedCurrency,
+    /// The nanosecond timestamp of the fill at the exchange.
+    pub timestamp_ns: TimestampNs,
+}
+
+/// A round trip of the position, from opening it until it is neutral or flipped again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundTrip<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The side of the fills opening the position, `Side::Buy` for a long and `Side::Sell` for a short.
+    pub side: Side,
+    /// The total quantity entered, which was also exited.
+    pub quantity: BaseOrQuote,
+    /// The quantity weighted average entry price.
+    pub entry_price: QuoteCurrency<I, D>,
+    /// The quantity weighted average exit price.
+    pub exit_price: QuoteCurrency<I, D>,
+    /// The realized profit and loss, excluding fees.
+    pub realized_pnl: BaseOrQuote::PairedCurrency,
+    /// The trading fees of the entry and exit fills.
+    pub fees: BaseOrQuote::PairedCurrency,
+    /// The nanosecond timestamp of the first entry fill.
+    pub entry_ts_ns: TimestampNs,
+    /// The nanosecond timestamp of the exit fill which closed the position.
+    pub exit_ts_ns: TimestampNs,
+}
+
+impl<I, const D: u8, BaseOrQuote> RoundTrip<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// How long the position was held.
+    #[inline]
+    pub fn holding_time_ns(&self) -> TimestampNs {
+        self.exit_ts_ns - self.entry_ts_ns
+    }
+
+    /// The realized profit and loss net of fees.
+    #[inline]
+    pub fn net_pnl(&self) -> BaseOrQuote::PairedCurrency {
+        self.realized_pnl - self.fees
+    }
+}
+
+/// The accumulated fills of the round trip which has not been closed yet.
+#[derive(Debug, Clone)]
+struct OpenRoundTrip<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    side: Side,
+    open_quantity: BaseOrQuote,
+    entry_quantity: BaseOrQuote,
+    // The sum of the entry prices weighted by their quantities.
+    entry_value: QuoteCurrency<I, D>,
+    exit_quantity: BaseOrQuote,
+    exit_value: QuoteCurrency<I, D>,
+    realized_pnl: BaseOrQuote::PairedCurrency,
+    fees: BaseOrQuote::PairedCurrency,
+    entry_ts_ns: TimestampNs,
+}
+
+impl<I, const D: u8, BaseOrQuote> OpenRoundTrip<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn new(side: Side, entry_ts_ns: TimestampNs) -> Self {
+        Self {
+            side,
+            open_quantity: BaseOrQuote::zero(),
+            entry_quantity: BaseOrQuote::zero(),
+            entry_value: QuoteCurrency::zero(),
+            exit_quantity: BaseOrQuote::zero(),
+            exit_value: QuoteCurrency::zero(),
+            realized_pnl: BaseOrQuote::PairedCurrency::zero(),
+            fees: BaseOrQuote::PairedCurrency::zero(),
+            entry_ts_ns,
+        }
+    }
+
+    fn enter(
+        &mut self,
+        quantity: BaseOrQuote,
+        price: QuoteCurrency<I, D>,
+        fee: BaseOrQuote::PairedCurrency,
+    ) {
+        self.open_quantity += quantity;
+        self.entry_quantity += quantity;
+        self.entry_value += price * *quantity.as_ref();
+        self.fees += fee;
+    }
+
+    fn exit(
+        &mut self,
+        quantity: BaseOrQuote,
+        price: QuoteCurrency<I, D>,
+        fee: BaseOrQuote::PairedCurrency,
+        realized_pnl: BaseOrQuote::PairedCurrency,
+    ) {
+        assert2::debug_assert!(quantity <= self.open_quantity);
+        self.open_quantity -= quantity;
+        self.exit_quantity += quantity;
+        self.exit_value += price * *quantity.as_ref();
+        self.fees += fee;
+        self.realized_pnl += realized_pnl;
+    }
+
+    fn into_round_trip(self, exit_ts_ns: TimestampNs) -> RoundTrip<I, D, BaseOrQuote> {
+        RoundTrip {
+            side: self.side,
+            quantity: self.entry_quantity,
+            entry_price: self.entry_value / *self.entry_quantity.as_ref(),
+            exit_price: self.exit_value / *self.exit_quantity.as_ref(),
+            realized_pnl: self.realized_pnl,
+            fees: self.fees,
+            entry_ts_ns: self.entry_ts_ns,
+            exit_ts_ns,
+        }
+    }
+}
+
+/// Records every fill of the account along with its realized profit and loss,
+/// and summarises the closed round trips of the position as `RoundTrip`s.
+/// It is enabled with `Config::with_trade_ledger` and fed by the `Exchange` on every fill.
+///
+/// Generics:
+/// - `I`: The numeric data type of currencies.
+/// - `D`: The constant decimal precision of the currencies.
+/// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct TradeLedger<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// How many fills and round trips are retained.
+    #[getset(get_copy = "pub")]
+    capacity: LedgerCapacity,
+
+    /// The fills in the order they occurred.
+    #[getset(get = "pub")]
+    fills: VecDeque<Fill<I, D, BaseOrQuote>>,
+
+    /// The closed round trips in the order they were closed.
+    #[getset(get = "pub")]
+    round_trips: VecDeque<RoundTrip<I, D, BaseOrQuote>>,
+
+    open_round_trip: Option<OpenRoundTrip<I, D, BaseOrQuote>>,
+}
+
+impl<I, const D: u8, BaseOrQuote> TradeLedger<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new instance retaining fills and round trips according to `capacity`.
+    pub(crate) fn new(capacity: LedgerCapacity) -> Self {
+        Self {
+            capacity,
+            fills: VecDeque::new(),
+            round_trips: VecDeque::new(),
+            open_round_trip: None,
+        }
+    }
+
+    /// Record a `fill`, which closes the open round trip once the position is neutral.
+    /// A fill flipping the position closes the round trip and opens the next one with the remaining quantity,
+    /// where its fee is split between them by quantity.
+    pub(crate) fn record_fill(&mut self, fill: Fill<I, D, BaseOrQuote>) {
+        assert2::debug_assert!(fill.quantity > BaseOrQuote::zero());
+        let mut remaining_qty = fill.quantity;
+        let mut remaining_fee = fill.fee;
+        if let Some(open_round_trip) = self.open_round_trip.as_mut()
+            && open_round_trip.side != fill.side
+        {
+            let exit_qty = remaining_qty.min(open_round_trip.open_quantity);
+            let exit_fee = if exit_qty == fill.quantity {
+                fill.fee
+            } else {
+                fill.fee * (*exit_qty.as_ref() / *fill.quantity.as_ref())
+            };
+            open_round_trip.exit(exit_qty, fill.price, exit_fee, fill.realized_pnl);
+            remaining_qty -= exit_qty;
+            remaining_fee -= exit_fee;
+            if open_round_trip.open_quantity == BaseOrQuote::zero() {
+                let round_trip = self
+                    .open_round_trip
+                    .take()
+                    .expect("Has an open round trip")
+                    .into_round_trip(fill.timestamp_ns);
+                push_bounded(&mut self.round_trips, round_trip, self.capacity);
+            }
+        }
+        if remaining_qty > BaseOrQuote::zero() {
+            self.open_round_trip
+                .get_or_insert_with(|| OpenRoundTrip::new(fill.side, fill.timestamp_ns))
+                .enter(remaining_qty, fill.price, remaining_fee);
+        }
+        push_bounded(&mut self.fills, fill, self.capacity);
+    }
+
+    /// Write the fills as csv with the columns
+    /// `timestamp_ns`, `order_id`, `side`, `quantity`, `price`, `fee`, `liquidity` and `realized_pnl`.
+    pub fn write_fills_csv<W: std::io::Write>(&self, writer: W) -> csv::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record([
+            "timestamp_ns",
+            "order_id",
+            "side",
+            "quantity",
+            "price",
+            "fee",
+            "liquidity",
+            "realized_pnl",
+        ])?;
+        for fill in self.fills.iter() {
+            writer.write_record([
+                fill.timestamp_ns.to_string(),
+                fill.order_id.to_string(),
+                fill.side.to_string(),
+                fill.quantity.as_ref().to_string(),
+                fill.price.as_ref().to_string(),
+                fill.fee.as_ref().to_string(),
+                fill.liquidity.to_string(),
+                fill.realized_pnl.as_ref().to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the closed round trips as csv with the columns `entry_ts_ns`, `exit_ts_ns`, `side`, `quantity`,
+    /// `entry_price`, `exit_price`, `realized_pnl`, `fees` and `holding_time_ns`.
+    pub fn write_round_trips_csv<W: std::io::Write>(&self, writer: W) -> csv::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record([
+            "entry_ts_ns",
+            "exit_ts_ns",
+            "side",
+            "quantity",
+            "entry_price",
+            "exit_price",
+            "realized_pnl",
+            "fees",
+            "holding_time_ns",
+        ])?;
+        for round_trip in self.round_trips.iter() {
+            writer.write_record([
+                round_trip.entry_ts_ns.to_string(),
+                round_trip.exit_ts_ns.to_string(),
+                round_trip.side.to_string(),
+                round_trip.quantity.as_ref().to_string(),
+                round_trip.entry_price.as_ref().to_string(),
+                round_trip.exit_price.as_ref().to_string(),
+                round_trip.realized_pnl.as_ref().to_string(),
+                round_trip.fees.as_ref().to_string(),
+                round_trip.holding_time_ns().to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Push `value` to the back, dropping the oldest value if the `capacity` is reached.
+fn push_bounded<T>(values: &mut VecDeque<T>, value: T, capacity: LedgerCapacity) {
+    if let LedgerCapacity::Bounded(capacity) = capacity
+        && values.len() >= capacity.get()
+    {
+        values.pop_front();
+    }
+    values.push_back(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    type Ledger = TradeLedger<i64, 5, BaseCurrency<i64, 5>>;
+
+    fn fill(
+        side: Side,
+        qty: i64,
+        price: i64,
+        realized_pnl: i64,
+        ts_ns: i64,
+    ) -> Fill<i64, 5, BaseCurrency<i64, 5>> {
+        Fill {
+            order_id: OrderId::from(ts_ns as u64),
+            side,
+            quantity: BaseCurrency::new(qty, 0),
+            price: QuoteCurrency::new(price, 0),
+            fee: QuoteCurrency::new(qty, 1),
+            liquidity: Liquidity::Taker,
+            realized_pnl: QuoteCurrency::new(realized_pnl, 0),
+            timestamp_ns: ts_ns.into(),
+        }
+    }
+
+    #[test]
+    fn trade_ledger_round_trip() {
+        let mut ledger = Ledger::new(LedgerCapacity::Unbounded);
+        ledger.record_fill(fill(Side::Buy, 2, 100, 0, 1));
+        ledger.record_fill(fill(Side::Buy, 2, 110, 0, 2));
+        ledger.record_fill(fill(Side::Sell, 1, 120, 15, 3));
+        assert!(ledger.round_trips().is_empty());
+        ledger.record_fill(fill(Side::Sell, 3, 100, -15, 5));
+        assert_eq!(ledger.fills().len(), 4);
+        assert_eq!(
+            ledger.round_trips(),
+            &VecDeque::from([RoundTrip {
+                side: Side::Buy,
+                quantity: BaseCurrency::new(4, 0),
+                entry_price: QuoteCurrency::new(105, 0),
+                exit_price: QuoteCurrency::new(105, 0),
+                realized_pnl: QuoteCurrency::new(0, 0),
+                fees: QuoteCurrency::new(8, 1),
+                entry_ts_ns: 1.into(),
+                exit_ts_ns: 5.into(),
+            }])
+        );
+        assert_eq!(ledger.round_trips()[0].holding_time_ns(), 4.into());
+        assert_eq!(ledger.round_trips()[0].net_pnl(), QuoteCurrency::new(-8, 1));
+    }
+
+    #[test]
+    fn trade_ledger_flip() {
+        let mut ledger = Ledger::new(LedgerCapacity::Unbounded);
+        ledger.record_fill(fill(Side::Buy, 2, 100, 0, 1));
+        ledger.record_fill(fill(Side::Sell, 5, 110, 20, 2));
+        ledger.record_fill(fill(Side::Buy, 3, 90, 60, 4));
+        assert_eq!(ledger.round_trips().len(), 2);
+        let long = ledger.round_trips()[0];
+        assert_eq!(long.side, Side::Buy);
+        assert_eq!(long.realized_pnl, QuoteCurrency::new(20, 0));
+        // The fee of the entry plus two fifths of the fee of the flipping fill.
+        assert_eq!(long.fees, QuoteCurrency::new(4, 1));
+        let short = ledger.round_trips()[1];
+        assert_eq!(short.side, Side::Sell);
+        assert_eq!(short.quantity, BaseCurrency::new(3, 0));
+        assert_eq!(short.entry_price, QuoteCurrency::new(110, 0));
+        assert_eq!(short.exit_price, QuoteCurrency::new(90, 0));
+        assert_eq!(short.fees, QuoteCurrency::new(6, 1));
+        assert_eq!(short.holding_time_ns(), 2.into());
+    }
+
+    #[test]
+    fn trade_ledger_bounded() {
+        let mut ledger = Ledger::new(LedgerCapacity::Bounded(NonZeroUsize::new(2).unwrap()));
+        for ts_ns in 0..3 {
+            ledger.record_fill(fill(Side::Buy, 1, 100, 0, 2 * ts_ns));
+            ledger.record_fill(fill(Side::Sell, 1, 101, 1, 2 * ts_ns + 1));
+        }
+        assert_eq!(ledger.fills().len(), 2);
+        assert_eq!(ledger.fills()[0].timestamp_ns, 4.into());
+        assert_eq!(ledger.round_trips().len(), 2);
+        assert_eq!(ledger.round_trips()[0].entry_ts_ns, 2.into());
+    }
+
+    #[test]
+    fn trade_ledger_write_csv() {
+        let mut ledger = Ledger::new(LedgerCapacity::Unbounded);
+        ledger.record_fill(fill(Side::Buy, 1, 100, 0, 1));
+        ledger.record_fill(fill(Side::Sell, 1, 110, 10, 3));
+
+        let mut buf = Vec::new();
+        ledger.write_fills_csv(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "timestamp_ns,order_id,side,quantity,price,fee,liquidity,realized_pnl\n\
+             1,1,Buy,1.00000,100.00000,0.10000,Taker,0.00000\n\
+             3,3,Sell,1.00000,110.00000,0.10000,Taker,10.00000\n"
+        );
+
+        let mut buf = Vec::new();
+        ledger.write_round_trips_csv(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "entry_ts_ns,exit_ts_ns,side,quantity,entry_price,exit_price,realized_pnl,fees,holding_time_ns\n\
+             1,3,Buy,1.00000,100.00000,110.00000,10.00000,0.20000,2\n"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use const_decimal::Decimal;
+use getset::CopyGetters;
+use num_traits::Zero;
+
+use super::{ConfigError, Mon};
+
+/// The indicator which ranks a profitable position in the auto-deleveraging queue,
+/// where the highest ranked positions are deleveraged first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AdlRankingIndicator {
+    /// The unrealized profit relative to the position margin, which is the profit ratio times the leverage.
+    #[default]
+    PnlTimesLeverage,
+    /// The unrealized profit relative to the total cost of the position, regardless of its leverage.
+    UnrealizedPnlRatio,
+}
+
+/// How the exchange auto-deleverages the profitable position of the user,
+/// once a bankrupt counterparty could not be liquidated above its bankruptcy price
+/// and the insurance fund could not absorb the loss.
+/// The bankrupt counterparties are simulated by `AutoDeleverage` market updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CopyGetters)]
+pub struct AdlModel<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// The indicator which ranks the position in the auto-deleveraging queue.
+    #[getset(get_copy = "pub")]
+    indicator: AdlRankingIndicator,
+
+    /// The position is only deleveraged if its ranking reaches `min_ranking`,
+    /// which stands in for the rankings of the simulated counterparties ahead of it in the queue.
+    #[getset(get_copy = "pub")]
+    min_ranking: Decimal<I, D>,
+}
+
+impl<I, const D: u8> AdlModel<I, D>
+where
+    I: Mon<D>,
+{
+    /// Create a new auto-deleveraging model.
+    ///
+    /// # Arguments:
+    /// `indicator`: The indicator which ranks the position in the auto-deleveraging queue.
+    /// `min_ranking`: The ranking the position must reach to be deleveraged, which must be >= 0.
+    pub fn new(
+        indicator: AdlRankingIndicator,
+        min_ranking: Decimal<I, D>,
+    ) -> Result<Self, ConfigError> {
+        if min_ranking < Decimal::zero() {
+            return Err(ConfigError::InvalidAdlModel);
+        }
+
+        Ok(Self {
+            indicator,
+            min_ranking,
+        })
+    }
+
+    /// The ranking of a position with `unrealized_pnl`, `total_cost` and `position_margin` according to the `indicator`.
+    pub(crate) fn ranking(
+        &self,
+        unrealized_pnl: Decimal<I, D>,
+        total_cost: Decimal<I, D>,
+        position_margin: Decimal<I, D>,
+    ) -> Decimal<I, D> {
+        let denominator = match self.indicator {
+            AdlRankingIndicator::PnlTimesLeverage => position_margin,
+            AdlRankingIndicator::UnrealizedPnlRatio => total_cost,
+        };
+        if denominator <= Decimal::zero() {
+            return Decimal::zero();
+        }
+        unrealized_pnl / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+
+    use super::*;
+
+    type Model = AdlModel<i64, 5>;
+
+    #[test]
+    fn adl_model_new() {
+        assert_eq!(
+            Model::new(
+                AdlRankingIndicator::PnlTimesLeverage,
+                Decimal::try_from_scaled(-1, 1).unwrap()
+            ),
+            Err(ConfigError::InvalidAdlModel)
+        );
+        let model = Model::new(AdlRankingIndicator::UnrealizedPnlRatio, Decimal::one()).unwrap();
+        assert_eq!(model.indicator(), AdlRankingIndicator::UnrealizedPnlRatio);
+        assert_eq!(model.min_ranking(), Decimal::one());
+    }
+
+    #[test]
+    fn adl_model_ranking() {
+        let pnl = Decimal::try_from_scaled(100, 0).unwrap();
+        let total_cost = Decimal::try_from_scaled(1000, 0).unwrap();
+        let position_margin = Decimal::try_from_scaled(200, 0).unwrap();
+
+        let model = Model::new(AdlRankingIndicator::PnlTimesLeverage, Decimal::zero()).unwrap();
+        assert_eq!(
+            model.ranking(pnl, total_cost, position_margin),
+            Decimal::try_from_scaled(5, 1).unwrap()
+        );
+        let model = Model::new(AdlRankingIndicator::UnrealizedPnlRatio, Decimal::zero()).unwrap();
+        assert_eq!(
+            model.ranking(pnl, total_cost, position_margin),
+            Decimal::try_from_scaled(1, 1).unwrap()
+        );
+        assert_eq!(
+            model.ranking(pnl, Decimal::zero(), position_margin),
+            Decimal::zero()
+        );
+    }
+}
@@ -5,6 +5,7 @@ use tracing::trace;
 use typed_builder::TypedBuilder;
 
 use super::{MarginCurrency, Mon};
+use crate::utils::min;
 
 /// Contains user balances including margin amounts.
 #[derive(Debug, Clone, Eq, PartialEq, TypedBuilder, CopyGetters)]
@@ -30,6 +31,21 @@ where
     #[getset(get_copy = "pub")]
     total_fees_paid: BaseOrQuote,
 
+    /// The total amount of maker rebates received, which is already netted in `total_fees_paid`.
+    #[getset(get_copy = "pub")]
+    #[builder(default)]
+    total_rebates_received: BaseOrQuote,
+
+    /// The total amount of funding paid or received (if negative).
+    #[getset(get_copy = "pub")]
+    #[builder(default)]
+    total_funding_paid: BaseOrQuote,
+
+    /// The total amount deposited minus the total amount withdrawn since the start.
+    #[getset(get_copy = "pub")]
+    #[builder(default)]
+    net_deposits: BaseOrQuote,
+
     /// A marker type.
     #[builder(default)]
     _i: PhantomData<I>,
@@ -61,6 +77,9 @@ where
             position_margin: BaseOrQuote::zero(),
             order_margin: BaseOrQuote::zero(),
             total_fees_paid: BaseOrQuote::zero(),
+            total_rebates_received: BaseOrQuote::zero(),
+            total_funding_paid: BaseOrQuote::zero(),
+            net_deposits: BaseOrQuote::zero(),
             _i: PhantomData,
         }
     }
@@ -77,16 +96,67 @@ where
         assert2::debug_assert!(self.order_margin >= BaseOrQuote::zero());
     }
 
-    /// If `fee` is negative then we receive balance.
+    /// If `fee` is negative then we receive balance, as is the case for maker rebates.
+    /// A fee the available balance can not cover is taken from the `order_margin` and then the `position_margin`,
+    /// as unrealized profits may back the margin with the cross margin mode.
     #[inline(always)]
     pub fn account_for_fee(&mut self, fee: BaseOrQuote) {
         trace!("account_for_fee: {fee}");
         self.debug_assert_state();
 
-        self.available -= fee;
-        assert2::debug_assert!(self.available >= BaseOrQuote::zero());
+        let from_available = min(fee, self.available);
+        self.available -= from_available;
+        let from_order_margin = min(fee - from_available, self.order_margin);
+        self.order_margin -= from_order_margin;
+        self.position_margin -= fee - from_available - from_order_margin;
+        self.debug_assert_state();
 
         self.total_fees_paid += fee;
+        if fee < BaseOrQuote::zero() {
+            self.total_rebates_received -= fee;
+        }
+    }
+
+    /// Settle a funding payment against the available balance.
+    /// If `payment` is negative then we receive balance.
+    /// A payment the available balance can not cover is taken from the `position_margin`,
+    /// which brings the position closer to its liquidation.
+    /// The part of a payment exceeding both is forgone, as the position is bankrupt by then.
+    #[inline(always)]
+    pub fn apply_funding(&mut self, payment: BaseOrQuote) {
+        trace!("apply_funding: {payment}, self: {self}");
+        self.debug_assert_state();
+
+        let from_available = min(payment, self.available);
+        self.available -= from_available;
+        let from_position_margin = min(payment - from_available, self.position_margin);
+        self.position_margin -= from_position_margin;
+        self.debug_assert_state();
+
+        self.total_funding_paid += from_available + from_position_margin;
+    }
+
+    /// Deposit `amount` into the available balance.
+    #[inline(always)]
+    pub fn deposit(&mut self, amount: BaseOrQuote) {
+        trace!("deposit: {amount}, self: {self}");
+        assert2::debug_assert!(amount > BaseOrQuote::zero());
+        self.debug_assert_state();
+
+        self.available += amount;
+        self.net_deposits += amount;
+    }
+
+    /// Withdraw `amount` from the available balance, which must cover it.
+    #[inline(always)]
+    pub fn withdraw(&mut self, amount: BaseOrQuote) {
+        trace!("withdraw: {amount}, self: {self}");
+        assert2::debug_assert!(amount > BaseOrQuote::zero());
+        self.debug_assert_state();
+
+        self.available -= amount;
+        assert2::debug_assert!(self.available >= BaseOrQuote::zero());
+        self.net_deposits -= amount;
     }
 
     /// Try to reserve some order margin from available balance.
@@ -174,6 +244,9 @@ mod test {
             position_margin: QuoteCurrency::new(200, 0),
             order_margin: QuoteCurrency::new(100, 0),
             total_fees_paid: QuoteCurrency::zero(),
+            total_rebates_received: QuoteCurrency::zero(),
+            total_funding_paid: QuoteCurrency::zero(),
+            net_deposits: QuoteCurrency::zero(),
             _i: PhantomData,
         };
         assert_eq!(balances.sum(), QuoteCurrency::new(1300, 0));
@@ -196,6 +269,82 @@ mod test {
         }
     }
 
+    #[test]
+    fn balances_account_for_rebate() {
+        let mut balances = Balances::new(QuoteCurrency::<i64, 5>::new(1000, 0));
+        balances.account_for_fee(QuoteCurrency::new(3, 0));
+        balances.account_for_fee(QuoteCurrency::new(-1, 0));
+        assert_eq!(balances.available(), QuoteCurrency::new(998, 0));
+        assert_eq!(balances.total_fees_paid(), QuoteCurrency::new(2, 0));
+        assert_eq!(balances.total_rebates_received(), QuoteCurrency::new(1, 0));
+    }
+
+    #[test]
+    fn balances_account_for_fee_exceeding_available() {
+        let mut balances = Balances::builder()
+            .available(QuoteCurrency::<i64, 5>::new(5, 0))
+            .position_margin(QuoteCurrency::new(100, 0))
+            .order_margin(QuoteCurrency::new(10, 0))
+            .total_fees_paid(Zero::zero())
+            .build();
+        balances.account_for_fee(QuoteCurrency::new(20, 0));
+        assert_eq!(
+            balances,
+            Balances::builder()
+                .available(Zero::zero())
+                .position_margin(QuoteCurrency::new(95, 0))
+                .order_margin(Zero::zero())
+                .total_fees_paid(QuoteCurrency::new(20, 0))
+                .build()
+        );
+    }
+
+    #[test]
+    fn balances_deposit_withdraw() {
+        let mut balances = Balances::new(QuoteCurrency::<i64, 5>::new(1000, 0));
+        balances.deposit(QuoteCurrency::new(500, 0));
+        balances.withdraw(QuoteCurrency::new(200, 0));
+        assert_eq!(balances.available(), QuoteCurrency::new(1300, 0));
+        assert_eq!(balances.net_deposits(), QuoteCurrency::new(300, 0));
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_balances_apply_funding(payment in -1000..1000_i64) {
+            let mut balances = Balances::new(QuoteCurrency::<i64, 5>::new(10_000, 0));
+            let payment = QuoteCurrency::new(payment, 0);
+            balances.apply_funding(payment);
+            balances.debug_assert_state();
+            assert_eq!(balances, Balances::builder()
+                .available(QuoteCurrency::new(10_000, 0) - payment)
+                .position_margin(Zero::zero())
+                .order_margin(Zero::zero())
+                .total_fees_paid(Zero::zero())
+                .total_funding_paid(payment)
+                .build()
+            );
+        }
+    }
+
+    #[test]
+    fn balances_apply_funding_exceeding_available() {
+        let mut balances = Balances::builder()
+            .available(QuoteCurrency::<i64, 5>::new(2, 0))
+            .position_margin(QuoteCurrency::new(100, 0))
+            .order_margin(Zero::zero())
+            .total_fees_paid(Zero::zero())
+            .build();
+        balances.apply_funding(QuoteCurrency::new(5, 0));
+        assert_eq!(balances.available(), QuoteCurrency::zero());
+        assert_eq!(balances.position_margin(), QuoteCurrency::new(97, 0));
+        assert_eq!(balances.total_funding_paid(), QuoteCurrency::new(5, 0));
+
+        // Beyond the position margin, the payment is forgone.
+        balances.apply_funding(QuoteCurrency::new(100, 0));
+        assert_eq!(balances.position_margin(), QuoteCurrency::zero());
+        assert_eq!(balances.total_funding_paid(), QuoteCurrency::new(102, 0));
+    }
+
     proptest! {
         #[test]
         fn proptest_balances_try_reserve_order_margin(margin in 1..1000_i64) {
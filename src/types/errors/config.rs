@@ -30,4 +30,32 @@ pub enum ConfigError {
 
     #[error("Invalid order limits")]
     InvalidOrderLimits,
+
+    #[error("The parameters of the slippage model are invalid")]
+    InvalidSlippageModel,
+
+    #[error("The parameters of the latency model are invalid")]
+    InvalidLatencyModel,
+
+    #[error(
+        "The risk limit tiers must have a positive, strictly ascending `max_notional` and non-decreasing margin requirements"
+    )]
+    InvalidRiskLimitTiers,
+
+    #[error(
+        "The fee tiers must have a non-negative, strictly ascending `min_volume`, a non-negative taker fee not exceeding the one of the contract specification and a maker rebate not exceeding it"
+    )]
+    InvalidFeeTiers,
+
+    #[error("The parameters of the liquidation model are invalid")]
+    InvalidLiquidationModel,
+
+    #[error("The parameters of the auto-deleveraging model are invalid")]
+    InvalidAdlModel,
+
+    #[error("The insurance fund must be >= 0")]
+    InvalidInsuranceFund,
+
+    #[error("The sample interval of the account tracker must be > 0")]
+    InvalidSampleInterval,
 }
@@ -54,4 +54,12 @@ pub enum Error {
 
     #[error("The provided prices for `Candle` don't make sense.")]
     InvalidCandlePrices,
+
+    #[error(
+        "The constituents of an `IndexPrice` must be non-empty, with positive prices and weights."
+    )]
+    InvalidIndexConstituents,
+
+    #[error("The amount of a deposit or withdrawal must be > 0")]
+    InvalidTransferAmount,
 }
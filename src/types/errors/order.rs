@@ -21,6 +21,34 @@ pub enum OrderError {
         away_market_quotation_price: String,
     },
 
+    #[error("The trigger price is less than or equal zero.")]
+    TriggerPriceLTEZero,
+
+    #[error(
+        "The stop order would trigger immediately as the trigger_price {trigger_price} is already reached by the reference price {reference_price}"
+    )]
+    StopOrderWouldTriggerImmediately {
+        trigger_price: String,
+        reference_price: String,
+    },
+
+    #[error(
+        "The trailing offset must be greater than zero and a fraction must also be less than one."
+    )]
+    InvalidTrailingOffset,
+
+    #[error("A trailing stop order can only be submitted once its reference price is known.")]
+    TrailingStopWithoutReferencePrice,
+
+    #[error("The expiry of the `GoodTilDate` order is not in the future.")]
+    GoodTilDateExpired,
+
+    #[error("There is no position to close.")]
+    NoPositionToClose,
+
+    #[error("The exit orders of a bracket must be on the opposite side of the entry order.")]
+    InvalidBracketExitSide,
+
     #[error("order size is less than or equal zero.")]
     OrderQuantityLTEZero,
 
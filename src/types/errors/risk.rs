@@ -5,6 +5,15 @@ pub enum RiskError {
     #[error("The `Trader` does not have enough balance.")]
     NotEnoughAvailableBalance,
 
+    #[error("The reduce-only order would not reduce the position.")]
+    ReduceOnlyOrderWouldIncreasePosition,
+
     #[error("The position will be liquidated!")]
     Liquidate,
+
+    #[error("The position would exceed the largest risk limit tier.")]
+    RiskLimitExceeded,
+
+    #[error("The withdrawal would breach the maintenance margin of the position.")]
+    WithdrawalBreachesMargin,
 }
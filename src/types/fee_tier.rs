@@ -0,0 +1,168 @@
+use std::collections::VecDeque;
+
+use const_decimal::Decimal;
+use getset::CopyGetters;
+use num_traits::Zero;
+
+use super::{ConfigError, Currency, Fee, Maker, Mon, NANOS_PER_SECOND, Taker, TimestampNs};
+
+/// The span of the rolling traded volume which selects the `FeeTier`, being 30 days.
+pub const FEE_TIER_VOLUME_WINDOW_NS: i64 = 30 * 24 * 60 * 60 * NANOS_PER_SECOND;
+
+/// A bracket of the fee schedule, which sets the fees once the traded volume of the last 30 days reaches `min_volume`.
+/// Higher tiers usually charge lower fees, and may pay a rebate to makers.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+pub struct FeeTier<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The traded notional value of the last 30 days, denoted in the margin currency, from which on this tier applies.
+    #[getset(get_copy = "pub")]
+    min_volume: BaseOrQuote,
+
+    /// The fee limit orders pay, which is a rebate if negative.
+    #[getset(get_copy = "pub")]
+    fee_maker: Fee<I, D, Maker>,
+
+    /// The fee market orders pay.
+    #[getset(get_copy = "pub")]
+    fee_taker: Fee<I, D, Taker>,
+}
+
+impl<I, const D: u8, BaseOrQuote> FeeTier<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new fee tier.
+    ///
+    /// # Arguments:
+    /// `min_volume`: The traded notional value of the last 30 days from which on this tier applies, which must be >= 0.
+    /// `fee_maker`: The fee a maker pays, which may be negative for a rebate not exceeding the `fee_taker`.
+    /// `fee_taker`: The fee a taker pays, which must be >= 0.
+    pub fn new(
+        min_volume: BaseOrQuote,
+        fee_maker: Fee<I, D, Maker>,
+        fee_taker: Fee<I, D, Taker>,
+    ) -> Result<Self, ConfigError> {
+        if min_volume < BaseOrQuote::zero()
+            || *fee_taker.as_ref() < Decimal::zero()
+            || *fee_maker.as_ref() + *fee_taker.as_ref() < Decimal::zero()
+        {
+            return Err(ConfigError::InvalidFeeTiers);
+        }
+
+        Ok(Self {
+            min_volume,
+            fee_maker,
+            fee_taker,
+        })
+    }
+}
+
+/// The traded notional value within the `FEE_TIER_VOLUME_WINDOW_NS` up to the latest fill.
+#[derive(Debug, Clone)]
+pub(crate) struct RollingVolume<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fills: VecDeque<(TimestampNs, BaseOrQuote)>,
+    volume: BaseOrQuote,
+    _i: std::marker::PhantomData<I>,
+}
+
+impl<I, const D: u8, BaseOrQuote> Default for RollingVolume<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    fn default() -> Self {
+        Self {
+            fills: VecDeque::new(),
+            volume: BaseOrQuote::zero(),
+            _i: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> RollingVolume<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The traded notional value within the window.
+    #[inline(always)]
+    pub(crate) fn volume(&self) -> BaseOrQuote {
+        self.volume
+    }
+
+    /// Add the `notional` value of a fill at `ts_ns`.
+    pub(crate) fn record(&mut self, ts_ns: TimestampNs, notional: BaseOrQuote) {
+        self.fills.push_back((ts_ns, notional));
+        self.volume += notional;
+        self.expire(ts_ns);
+    }
+
+    /// Drop the fills which fell out of the window as of `ts_ns`.
+    pub(crate) fn expire(&mut self, ts_ns: TimestampNs) {
+        while let Some((fill_ts_ns, notional)) = self.fills.front().copied()
+            && fill_ts_ns + TimestampNs::from(FEE_TIER_VOLUME_WINDOW_NS) <= ts_ns
+        {
+            self.volume -= notional;
+            self.fills.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::QuoteCurrency;
+
+    fn fee<MakerTaker>(bps: i64) -> Fee<i64, 5, MakerTaker> {
+        Fee::from(Decimal::try_from_scaled(bps, 4).unwrap())
+    }
+
+    #[test]
+    fn fee_tier() {
+        let tier =
+            FeeTier::<i64, 5, _>::new(QuoteCurrency::new(1_000_000, 0), fee(-1), fee(4)).unwrap();
+        assert_eq!(tier.min_volume(), QuoteCurrency::new(1_000_000, 0));
+        assert_eq!(tier.fee_maker(), fee(-1));
+        assert_eq!(tier.fee_taker(), fee(4));
+
+        assert_eq!(
+            FeeTier::<i64, 5, _>::new(QuoteCurrency::new(-1, 0), fee(1), fee(4)),
+            Err(ConfigError::InvalidFeeTiers)
+        );
+        assert_eq!(
+            FeeTier::<i64, 5, _>::new(QuoteCurrency::zero(), fee(1), fee(-1)),
+            Err(ConfigError::InvalidFeeTiers)
+        );
+        // The rebate must not exceed the taker fee.
+        assert_eq!(
+            FeeTier::<i64, 5, _>::new(QuoteCurrency::zero(), fee(-5), fee(4)),
+            Err(ConfigError::InvalidFeeTiers)
+        );
+    }
+
+    #[test]
+    fn rolling_volume() {
+        let mut volume = RollingVolume::<i64, 5, QuoteCurrency<i64, 5>>::default();
+        volume.record(0.into(), QuoteCurrency::new(100, 0));
+        volume.record(10.into(), QuoteCurrency::new(50, 0));
+        assert_eq!(volume.volume(), QuoteCurrency::new(150, 0));
+
+        volume.expire((FEE_TIER_VOLUME_WINDOW_NS - 1).into());
+        assert_eq!(volume.volume(), QuoteCurrency::new(150, 0));
+        volume.expire(FEE_TIER_VOLUME_WINDOW_NS.into());
+        assert_eq!(volume.volume(), QuoteCurrency::new(50, 0));
+        volume.record(
+            (FEE_TIER_VOLUME_WINDOW_NS + 10).into(),
+            QuoteCurrency::new(20, 0),
+        );
+        assert_eq!(volume.volume(), QuoteCurrency::new(20, 0));
+    }
+}
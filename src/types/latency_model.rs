@@ -0,0 +1,163 @@
+use super::{ConfigError, TimestampNs};
+
+/// How long a message takes to travel between the strategy and the exchange.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum LatencyModel {
+    /// Messages arrive instantly.
+    #[default]
+    NoLatency,
+    /// Every message takes the same time to arrive.
+    Fixed(TimestampNs),
+    /// The latency is drawn uniformly from `min..=max`, using a pseudo random number generator seeded with `seed`,
+    /// so a simulation is reproducible.
+    Uniform {
+        /// The smallest possible latency.
+        min: TimestampNs,
+        /// The largest possible latency.
+        max: TimestampNs,
+        /// The seed of the pseudo random number generator.
+        seed: u64,
+    },
+    /// The latencies are replayed from a recorded trace, starting over once it is exhausted.
+    Trace(Vec<TimestampNs>),
+}
+
+impl LatencyModel {
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        let zero = TimestampNs::default();
+        let valid = match self {
+            LatencyModel::NoLatency => true,
+            LatencyModel::Fixed(latency) => *latency >= zero,
+            LatencyModel::Uniform { min, max, .. } => *min >= zero && min <= max,
+            LatencyModel::Trace(trace) => {
+                !trace.is_empty() && trace.iter().all(|latency| *latency >= zero)
+            }
+        };
+        if !valid {
+            return Err(ConfigError::InvalidLatencyModel);
+        }
+
+        Ok(())
+    }
+}
+
+/// Draws the latencies of a `LatencyModel` one after another.
+#[derive(Debug, Clone)]
+pub(crate) struct LatencySampler {
+    model: LatencyModel,
+    rng_state: u64,
+    trace_idx: usize,
+}
+
+impl LatencySampler {
+    pub(crate) fn new(model: LatencyModel) -> Self {
+        let rng_state = match &model {
+            LatencyModel::Uniform { seed, .. } => *seed,
+            _ => 0,
+        };
+        Self {
+            model,
+            rng_state,
+            trace_idx: 0,
+        }
+    }
+
+    /// The latency of the next message.
+    pub(crate) fn sample(&mut self) -> TimestampNs {
+        match &self.model {
+            LatencyModel::NoLatency => TimestampNs::default(),
+            LatencyModel::Fixed(latency) => *latency,
+            LatencyModel::Uniform { min, max, .. } => {
+                let range = (max.get() - min.get()) as u64 + 1;
+                let offset = split_mix_64(&mut self.rng_state) % range;
+                *min + TimestampNs::from(offset as i64)
+            }
+            LatencyModel::Trace(trace) => {
+                let latency = trace[self.trace_idx];
+                self.trace_idx = (self.trace_idx + 1) % trace.len();
+                latency
+            }
+        }
+    }
+}
+
+/// The `SplitMix64` pseudo random number generator, which is plenty for latency jitter.
+fn split_mix_64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_model_validate() {
+        LatencyModel::NoLatency.validate().unwrap();
+        LatencyModel::Fixed(5.into()).validate().unwrap();
+        LatencyModel::Trace(vec![1.into(), 0.into()])
+            .validate()
+            .unwrap();
+        assert_eq!(
+            LatencyModel::Fixed((-1).into()).validate(),
+            Err(ConfigError::InvalidLatencyModel)
+        );
+        assert_eq!(
+            LatencyModel::Uniform {
+                min: 10.into(),
+                max: 5.into(),
+                seed: 0
+            }
+            .validate(),
+            Err(ConfigError::InvalidLatencyModel)
+        );
+        assert_eq!(
+            LatencyModel::Trace(Vec::new()).validate(),
+            Err(ConfigError::InvalidLatencyModel)
+        );
+    }
+
+    #[test]
+    fn latency_sampler_fixed() {
+        let mut sampler = LatencySampler::new(LatencyModel::Fixed(7.into()));
+        assert_eq!(sampler.sample(), 7.into());
+        assert_eq!(sampler.sample(), 7.into());
+        assert_eq!(
+            LatencySampler::new(LatencyModel::NoLatency).sample(),
+            0.into()
+        );
+    }
+
+    #[test]
+    fn latency_sampler_uniform() {
+        let model = LatencyModel::Uniform {
+            min: 10.into(),
+            max: 20.into(),
+            seed: 42,
+        };
+        let mut sampler = LatencySampler::new(model.clone());
+        let latencies: Vec<TimestampNs> = (0..1000).map(|_| sampler.sample()).collect();
+        assert!(
+            latencies
+                .iter()
+                .all(|latency| *latency >= 10.into() && *latency <= 20.into())
+        );
+        assert!(latencies.contains(&10.into()));
+        assert!(latencies.contains(&20.into()));
+
+        // The same seed reproduces the same latencies.
+        let mut sampler = LatencySampler::new(model);
+        assert!(latencies.iter().all(|latency| *latency == sampler.sample()));
+    }
+
+    #[test]
+    fn latency_sampler_trace() {
+        let mut sampler = LatencySampler::new(LatencyModel::Trace(vec![3.into(), 1.into()]));
+        assert_eq!(sampler.sample(), 3.into());
+        assert_eq!(sampler.sample(), 1.into());
+        assert_eq!(sampler.sample(), 3.into());
+    }
+}
@@ -5,10 +5,11 @@ use num_traits::Zero;
 
 use super::{
     Currency, Filled, FilledQuantity, LimitOrderFill, MarginCurrency, Mon, OrderId, Pending,
-    QuoteCurrency, RePricing, TimestampNs, UserOrderId, order_meta::ExchangeOrderMeta,
+    QuoteCurrency, RePricing, TimeInForce, TimestampNs, UserOrderId, order_meta::ExchangeOrderMeta,
     order_status::NewOrder,
 };
 use crate::{
+    prelude::Position,
     types::{OrderError, Side},
     utils::NoUserOrderId,
 };
@@ -77,6 +78,19 @@ where
     #[getset(get_copy = "pub", set = "pub")]
     re_pricing: RePricing,
 
+    /// Determines how long the order remains active.
+    #[getset(get_copy = "pub", set = "pub")]
+    time_in_force: TimeInForce,
+
+    /// If `true`, the order can only reduce the position and never increase or flip it.
+    #[getset(get_copy = "pub", set = "pub")]
+    reduce_only: bool,
+
+    /// The quantity resting ahead of the order at its price level, if its queue position is tracked.
+    /// Once it is consumed, trades at the limit price fill the order.
+    #[getset(get_copy = "pub", set = "pub(crate)")]
+    queue_ahead: Option<BaseOrQuote>,
+
     /// Depending on the status, different information is available.
     #[getset(get = "pub")]
     state: OrderStatus,
@@ -132,8 +146,35 @@ where
             remaining_quantity: quantity,
             side,
             re_pricing: RePricing::default(),
+            time_in_force: TimeInForce::default(),
+            reduce_only: false,
+            queue_ahead: None,
         })
     }
+
+    /// Create a new reduce-only limit order which closes the entire `position` at the `limit_price`,
+    /// sizing itself from `Position::quantity`.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn close_position(
+        position: &Position<I, D, BaseOrQuote>,
+        limit_price: QuoteCurrency<I, D>,
+    ) -> Result<Self, OrderError> {
+        let position_qty = position.quantity();
+        if position_qty.is_zero() {
+            return Err(OrderError::NoPositionToClose);
+        }
+        let side = if position_qty > BaseOrQuote::zero() {
+            Side::Sell
+        } else {
+            Side::Buy
+        };
+        let mut order = Self::new(side, limit_price, position_qty.abs())?;
+        order.reduce_only = true;
+
+        Ok(order)
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote, UserOrderIdT>
@@ -173,6 +214,9 @@ where
             remaining_quantity: quantity,
             side,
             re_pricing: RePricing::default(),
+            time_in_force: TimeInForce::default(),
+            reduce_only: false,
+            queue_ahead: None,
         })
     }
 
@@ -199,7 +243,10 @@ where
             limit_price: self.limit_price,
             remaining_quantity: self.remaining_quantity,
             state: Pending::new(meta),
-            re_pricing: RePricing::default(),
+            re_pricing: self.re_pricing,
+            time_in_force: self.time_in_force,
+            reduce_only: self.reduce_only,
+            queue_ahead: self.queue_ahead,
         }
     }
 
@@ -220,11 +267,24 @@ where
     UserOrderIdT: UserOrderId,
 {
     /// Used when an order gets some `quantity` filled at its limit price.
+    #[inline]
     pub(crate) fn fill(
         &mut self,
         filled_quantity: BaseOrQuote,
         fee: BaseOrQuote::PairedCurrency,
         ts_ns: TimestampNs,
+    ) -> LimitOrderFill<I, D, BaseOrQuote, UserOrderIdT> {
+        self.fill_at(self.limit_price(), filled_quantity, fee, ts_ns)
+    }
+
+    /// Used when an order gets some `quantity` filled at a `fill_price` at least as good as its limit price,
+    /// e.g. when a marketable order takes liquidity from multiple levels of the book.
+    pub(crate) fn fill_at(
+        &mut self,
+        fill_price: QuoteCurrency<I, D>,
+        filled_quantity: BaseOrQuote,
+        fee: BaseOrQuote::PairedCurrency,
+        ts_ns: TimestampNs,
     ) -> LimitOrderFill<I, D, BaseOrQuote, UserOrderIdT> {
         assert2::debug_assert!(
             filled_quantity <= self.remaining_quantity,
@@ -234,7 +294,10 @@ where
             filled_quantity > BaseOrQuote::zero(),
             "Filled quantity must be greater than zero."
         );
-        let fill_price = self.limit_price();
+        debug_assert!(match self.side {
+            Side::Buy => fill_price <= self.limit_price,
+            Side::Sell => fill_price >= self.limit_price,
+        });
 
         self.remaining_quantity -= filled_quantity;
         debug_assert!(
@@ -242,21 +305,29 @@ where
             "Quantity must be positive"
         );
 
-        let cumulative_qty = match &mut self.state.filled_quantity {
+        let (cumulative_qty, avg_fill_price) = match &mut self.state.filled_quantity {
             FilledQuantity::Unfilled => {
                 self.state.filled_quantity = FilledQuantity::Filled {
                     cumulative_qty: filled_quantity,
                     avg_price: fill_price,
                 };
 
-                filled_quantity
+                (filled_quantity, fill_price)
             }
             FilledQuantity::Filled {
                 cumulative_qty,
-                avg_price: _,
+                avg_price,
             } => {
+                if fill_price != *avg_price {
+                    *avg_price = QuoteCurrency::new_weighted_price(
+                        *avg_price,
+                        *cumulative_qty.as_ref(),
+                        fill_price,
+                        *filled_quantity.as_ref(),
+                    );
+                }
                 *cumulative_qty += filled_quantity;
-                *cumulative_qty
+                (*cumulative_qty, *avg_price)
             }
         };
 
@@ -266,13 +337,16 @@ where
                 state: Filled::new(
                     self.state.meta().clone(),
                     ts_ns,
-                    self.limit_price(),
+                    avg_fill_price,
                     cumulative_qty,
                 ),
                 limit_price: self.limit_price,
                 remaining_quantity: BaseOrQuote::zero(),
                 side: self.side,
                 re_pricing: self.re_pricing,
+                time_in_force: self.time_in_force,
+                reduce_only: self.reduce_only,
+                queue_ahead: self.queue_ahead,
             };
 
             LimitOrderFill::FullyFilled {
@@ -327,6 +401,20 @@ where
     pub fn notional(&self) -> BaseOrQuote::PairedCurrency {
         BaseOrQuote::PairedCurrency::convert_from(self.remaining_quantity, self.limit_price)
     }
+
+    /// Modify the `remaining_quantity` of an active order, e.g. when a linked order was filled.
+    /// The `new_qty` must be GT than zero.
+    pub(crate) fn set_remaining_quantity(&mut self, new_qty: BaseOrQuote) {
+        assert!(new_qty > BaseOrQuote::zero());
+        self.remaining_quantity = new_qty;
+    }
+
+    /// Modify the `limit_price` of an order which is not yet in the book, e.g. when its `RePricing` adjusts it.
+    /// The `limit_price` must be GT than zero.
+    pub(crate) fn set_limit_price(&mut self, limit_price: QuoteCurrency<I, D>) {
+        assert!(limit_price > QuoteCurrency::zero());
+        self.limit_price = limit_price;
+    }
 }
 
 impl<I, const D: u8, BaseOrQuote, UserOrderIdT>
@@ -360,7 +448,12 @@ mod tests {
     use test_case::test_matrix;
 
     use super::*;
-    use crate::{DECIMALS, prelude::QuoteCurrency, test_fee_maker, types::BaseCurrency};
+    use crate::{
+        DECIMALS,
+        prelude::{PositionInner, QuoteCurrency},
+        test_fee_maker,
+        types::BaseCurrency,
+    };
 
     #[test_matrix(
         [Side::Buy, Side::Sell],
@@ -426,6 +519,9 @@ mod tests {
                 limit_price,
                 remaining_quantity: qty,
                 re_pricing: RePricing::GoodTilCrossing,
+                time_in_force: TimeInForce::GoodTilCancelled,
+                reduce_only: false,
+                queue_ahead: None,
                 state: Pending::builder()
                     .meta(meta.clone())
                     .filled_quantity(FilledQuantity::Filled {
@@ -460,6 +556,9 @@ mod tests {
                     limit_price,
                     remaining_quantity: Zero::zero(),
                     re_pricing: RePricing::GoodTilCrossing,
+                    time_in_force: TimeInForce::GoodTilCancelled,
+                    reduce_only: false,
+                    queue_ahead: None,
                     state: Filled::new(meta, 1.into(), limit_price, quantity)
                 }
             }
@@ -473,15 +572,15 @@ mod tests {
         use std::mem::size_of;
         assert_eq!(
             size_of::<LimitOrder<i64, 5, BaseCurrency<i64, 5>, i64, NewOrder>>(),
-            32
+            48
         );
         assert_eq!(
             size_of::<LimitOrder<i32, 2, BaseCurrency<i32, 2>, i64, NewOrder>>(),
-            24
+            40
         );
         assert_eq!(
             size_of::<LimitOrder<i32, 2, BaseCurrency<i32, 2>, i32, NewOrder>>(),
-            16
+            32
         );
         assert_eq!(
             size_of::<
@@ -493,7 +592,7 @@ mod tests {
                     Pending<i32, 2, BaseCurrency<i32, 2>>,
                 >,
             >(),
-            56
+            72
         );
         assert_eq!(
             size_of::<
@@ -505,7 +604,7 @@ mod tests {
                     Pending<i64, 2, BaseCurrency<i64, 2>>,
                 >,
             >(),
-            72
+            88
         );
     }
 
@@ -521,6 +620,43 @@ mod tests {
         assert_eq!(order.user_order_id(), 1);
     }
 
+    #[test]
+    fn limit_order_into_pending_keeps_re_pricing() {
+        let mut order = LimitOrder::new(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap();
+        order.set_re_pricing(RePricing::HideNotSlide);
+        let mut order = order.into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        assert_eq!(order.re_pricing(), RePricing::HideNotSlide);
+
+        order.set_limit_price(QuoteCurrency::new(99, 0));
+        assert_eq!(order.limit_price(), QuoteCurrency::new(99, 0));
+        assert_eq!(order.notional(), QuoteCurrency::new(495, 0));
+    }
+
+    #[test]
+    fn limit_order_close_position() {
+        let qty = BaseCurrency::<i64, 5>::new(5, 0);
+        let limit_price = QuoteCurrency::new(110, 0);
+        let position = Position::Long(PositionInner::new(qty, QuoteCurrency::new(100, 0)));
+        let order = LimitOrder::close_position(&position, limit_price).unwrap();
+        assert_eq!(order.side(), Side::Sell);
+        assert_eq!(order.limit_price(), limit_price);
+        assert_eq!(order.remaining_quantity(), qty);
+        assert!(order.reduce_only());
+        let order = order.into_pending(ExchangeOrderMeta::new(0.into(), 0.into()));
+        assert!(order.reduce_only());
+
+        let neutral = Position::<i64, 5, BaseCurrency<i64, 5>>::Neutral;
+        assert_eq!(
+            LimitOrder::close_position(&neutral, limit_price),
+            Err(OrderError::NoPositionToClose)
+        );
+    }
+
     proptest! {
         #[test]
         fn limit_order_fill_proptest_buy_partial(
@@ -549,6 +685,9 @@ mod tests {
                         limit_price,
                         remaining_quantity: init_qty - filled_quantity,
                         re_pricing: RePricing::GoodTilCrossing,
+                        time_in_force: TimeInForce::GoodTilCancelled,
+                        reduce_only: false,
+                        queue_ahead: None,
                         state: Pending::builder()
                             .meta(meta)
                             .filled_quantity(FilledQuantity::Filled {
@@ -0,0 +1,132 @@
+use super::{
+    Currency, ExchangeOrderMeta, LimitOrder, MarginCurrency, Mon, NewOrder, OrderId, Pending, Side,
+    StopOrder, UserOrderId,
+};
+
+/// An order which can be linked to other orders, either in a one-cancels-other pair or as an exit of a bracket order.
+///
+/// Generics:
+/// - `I`: The numeric data type of currencies.
+/// - `D`: The constant decimal precision of the currencies.
+/// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
+/// - `UserOrderId`: The type of user order id to use. Set to `()` if you don't need one.
+/// - `OrderStatus`: The status of the order for each stage, contains different information based on the stage.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::From)]
+pub enum LinkedOrder<I, const D: u8, BaseOrQuote, UserOrderIdT, OrderStatus>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// A passive limit order.
+    Limit(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, OrderStatus>),
+    /// A conditional stop or take-profit order.
+    Stop(StopOrder<I, D, BaseOrQuote, UserOrderIdT, OrderStatus>),
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT, OrderStatus>
+    LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, OrderStatus>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Whether its a buy or sell order.
+    #[inline]
+    pub fn side(&self) -> Side {
+        match self {
+            LinkedOrder::Limit(order) => order.side(),
+            LinkedOrder::Stop(order) => order.side(),
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT>
+    LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Take in the order metadata provided by the exchange and coverts the order to the `Pending` state.
+    pub fn into_pending(
+        self,
+        meta: ExchangeOrderMeta,
+    ) -> LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>> {
+        match self {
+            LinkedOrder::Limit(order) => LinkedOrder::Limit(order.into_pending(meta)),
+            LinkedOrder::Stop(order) => LinkedOrder::Stop(order.into_pending(meta)),
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT>
+    LinkedOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Get the order id assigned by the exchange.
+    #[inline]
+    pub fn id(&self) -> OrderId {
+        match self {
+            LinkedOrder::Limit(order) => order.id(),
+            LinkedOrder::Stop(order) => order.id(),
+        }
+    }
+
+    /// The remaining quantity of the order.
+    #[inline]
+    pub fn quantity(&self) -> BaseOrQuote {
+        match self {
+            LinkedOrder::Limit(order) => order.remaining_quantity(),
+            LinkedOrder::Stop(order) => order.quantity(),
+        }
+    }
+
+    /// Modify the remaining quantity, which must be greater than zero.
+    pub(crate) fn set_quantity(&mut self, quantity: BaseOrQuote) {
+        match self {
+            LinkedOrder::Limit(order) => order.set_remaining_quantity(quantity),
+            LinkedOrder::Stop(order) => order.set_quantity(quantity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{BaseCurrency, QuoteCurrency};
+
+    #[test]
+    fn linked_order_set_quantity() {
+        let meta = ExchangeOrderMeta::new(0.into(), 0.into());
+        let mut order: LinkedOrder<_, 5, _, _, _> = LimitOrder::new(
+            Side::Sell,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone())
+        .into();
+        assert_eq!(order.side(), Side::Sell);
+        order.set_quantity(BaseCurrency::new(2, 0));
+        assert_eq!(order.quantity(), BaseCurrency::new(2, 0));
+
+        let order: LinkedOrder<_, 5, _, _, _> = StopOrder::new_stop_market(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into();
+        assert_eq!(order.side(), Side::Buy);
+        let mut order = order.into_pending(meta);
+        assert_eq!(order.id(), 0.into());
+        order.set_quantity(BaseCurrency::new(3, 0));
+        assert_eq!(order.quantity(), BaseCurrency::new(3, 0));
+    }
+}
@@ -0,0 +1,181 @@
+use const_decimal::Decimal;
+use getset::CopyGetters;
+use num_traits::{One, Zero};
+
+use super::{ConfigError, Currency, Mon};
+use crate::prelude::QuantityFilter;
+
+/// How much of the position a liquidation closes at once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidationMode<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// The whole position is closed at once.
+    #[default]
+    Full,
+    /// A fraction of the remaining position is closed per step, e.g. `0.25` for a quarter,
+    /// until the maintenance margin is met again or the position is closed.
+    /// The maintenance margin is met again once the position falls into a lower risk limit tier,
+    /// or in cross margin mode once the equity covers the maintenance margin of the remaining position.
+    Partial(Decimal<I, D>),
+}
+
+/// The price at which the liquidated quantity is settled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidationSettlement {
+    /// The liquidation order fills like a market order of the user and pays the taker fee.
+    #[default]
+    MarketPrice,
+    /// The exchange takes over the liquidated quantity at its bankruptcy price,
+    /// at which the losses exhaust the margin backing the position. No taker fee is paid.
+    BankruptcyPrice,
+}
+
+/// How the exchange liquidates a position which no longer meets its maintenance margin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CopyGetters)]
+pub struct LiquidationModel<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// How much of the position a liquidation closes at once.
+    #[getset(get_copy = "pub")]
+    mode: LiquidationMode<I, D>,
+
+    /// The liquidation fee as a fraction of the notional value of the liquidated quantity,
+    /// which is charged on top of any trading fee, but never exceeds the remaining available balance.
+    #[getset(get_copy = "pub")]
+    fee: Decimal<I, D>,
+
+    /// The price at which the liquidated quantity is settled.
+    #[getset(get_copy = "pub")]
+    settlement: LiquidationSettlement,
+}
+
+impl<I, const D: u8> Default for LiquidationModel<I, D>
+where
+    I: Mon<D>,
+{
+    fn default() -> Self {
+        Self {
+            mode: LiquidationMode::Full,
+            fee: Decimal::zero(),
+            settlement: LiquidationSettlement::MarketPrice,
+        }
+    }
+}
+
+impl<I, const D: u8> LiquidationModel<I, D>
+where
+    I: Mon<D>,
+{
+    /// Create a new liquidation model.
+    ///
+    /// # Arguments:
+    /// `mode`: How much of the position a liquidation closes at once.
+    /// A `Partial` step must be in range (0..1).
+    /// `fee`: The liquidation fee as a fraction of the liquidated notional value in range [0..1).
+    /// `settlement`: The price at which the liquidated quantity is settled.
+    pub fn new(
+        mode: LiquidationMode<I, D>,
+        fee: Decimal<I, D>,
+        settlement: LiquidationSettlement,
+    ) -> Result<Self, ConfigError> {
+        if let LiquidationMode::Partial(step) = mode
+            && (step <= Decimal::zero() || step >= Decimal::one())
+        {
+            return Err(ConfigError::InvalidLiquidationModel);
+        }
+        if fee < Decimal::zero() || fee >= Decimal::one() {
+            return Err(ConfigError::InvalidLiquidationModel);
+        }
+
+        Ok(Self {
+            mode,
+            fee,
+            settlement,
+        })
+    }
+
+    /// The quantity a single liquidation step closes of a position with `quantity`,
+    /// which is rounded down to the `tick_size` of the `quantity_filter`.
+    /// Closes the whole position if the step would fall below the minimum order quantity.
+    pub(crate) fn step_quantity<BaseOrQuote>(
+        &self,
+        quantity: BaseOrQuote,
+        quantity_filter: &QuantityFilter<I, D, BaseOrQuote>,
+    ) -> BaseOrQuote
+    where
+        BaseOrQuote: Currency<I, D>,
+    {
+        assert2::debug_assert!(quantity > BaseOrQuote::zero());
+        let LiquidationMode::Partial(step) = self.mode else {
+            return quantity;
+        };
+        let step_qty = quantity * step;
+        let step_qty = step_qty - step_qty % quantity_filter.tick_size();
+        let min_qty = quantity_filter
+            .min_quantity()
+            .unwrap_or_else(BaseOrQuote::zero);
+        if step_qty <= BaseOrQuote::zero() || step_qty < min_qty {
+            return quantity;
+        }
+        step_qty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    type Model = LiquidationModel<i64, 5>;
+
+    #[test]
+    fn liquidation_model_new() {
+        let quarter = Decimal::try_from_scaled(25, 2).unwrap();
+        Model::new(
+            LiquidationMode::Partial(quarter),
+            Decimal::try_from_scaled(5, 3).unwrap(),
+            LiquidationSettlement::BankruptcyPrice,
+        )
+        .unwrap();
+        assert_eq!(
+            Model::new(
+                LiquidationMode::Partial(Decimal::one()),
+                Decimal::zero(),
+                LiquidationSettlement::MarketPrice
+            ),
+            Err(ConfigError::InvalidLiquidationModel)
+        );
+        assert_eq!(
+            Model::new(
+                LiquidationMode::Full,
+                Decimal::try_from_scaled(-1, 3).unwrap(),
+                LiquidationSettlement::MarketPrice
+            ),
+            Err(ConfigError::InvalidLiquidationModel)
+        );
+    }
+
+    #[test]
+    fn liquidation_model_step_quantity() {
+        let filter = QuantityFilter::default();
+        let qty = BaseCurrency::<i64, 5>::new(10, 0);
+        assert_eq!(Model::default().step_quantity(qty, &filter), qty);
+
+        let model = Model::new(
+            LiquidationMode::Partial(Decimal::try_from_scaled(25, 2).unwrap()),
+            Decimal::zero(),
+            LiquidationSettlement::MarketPrice,
+        )
+        .unwrap();
+        // Rounded down to the tick size of one.
+        assert_eq!(model.step_quantity(qty, &filter), BaseCurrency::new(2, 0));
+        // The step would round down to zero, so the rest is closed at once.
+        assert_eq!(
+            model.step_quantity(BaseCurrency::new(3, 0), &filter),
+            BaseCurrency::new(3, 0)
+        );
+    }
+}
@@ -0,0 +1,12 @@
+/// How the margin of the account backs the position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MarginMode {
+    /// Only the margin reserved for the position backs it,
+    /// so it is liquidated once its losses exhaust the position margin.
+    #[default]
+    Isolated,
+    /// The total equity of the account, being its balances plus the unrealized profit and loss, backs the position,
+    /// so it is only liquidated once the equity falls below the maintenance margin.
+    /// The unrealized profit and loss also adds to the margin available for new orders.
+    Cross,
+}
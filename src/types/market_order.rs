@@ -1,9 +1,10 @@
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, Setters};
 
 use super::{
-    Currency, ExchangeOrderMeta, Filled, Mon, OrderError, Pending, QuoteCurrency, Side,
-    TimestampNs, UserOrderId, order_status::NewOrder,
+    Currency, ExchangeOrderMeta, Filled, MarginCurrency, Mon, OrderError, Pending, QuoteCurrency,
+    Side, TimestampNs, UserOrderId, order_status::NewOrder,
 };
+use crate::prelude::Position;
 
 /// Defines an market order aka taker order.
 /// Generics:
@@ -12,7 +13,7 @@ use super::{
 /// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
 /// - `UserOrderId`: The type of user order id to use. Set to `()` if you don't need one.
 /// - `OrderStatus`: The status of the order for each stage, contains different information based on the stage.
-#[derive(Debug, Clone, PartialEq, Eq, Getters, CopyGetters)]
+#[derive(Debug, Clone, PartialEq, Eq, Getters, CopyGetters, Setters)]
 pub struct MarketOrder<I, const D: u8, BaseOrQuote, UserOrderIdT, OrderStatus>
 where
     I: Mon<D>,
@@ -32,6 +33,10 @@ where
     #[getset(get_copy = "pub")]
     quantity: BaseOrQuote,
 
+    /// If `true`, the order can only reduce the position and never increase or flip it.
+    #[getset(get_copy = "pub", set = "pub")]
+    reduce_only: bool,
+
     /// Depending on the status, different information is available.
     #[getset(get = "pub")]
     state: OrderStatus,
@@ -80,6 +85,7 @@ where
             state: NewOrder,
             side,
             quantity,
+            reduce_only: false,
             _quote: std::marker::PhantomData,
         })
     }
@@ -106,10 +112,35 @@ where
             state: NewOrder,
             quantity,
             side,
+            reduce_only: false,
             _quote: std::marker::PhantomData,
         })
     }
 
+    /// Create a new reduce-only market order which closes the entire `position`,
+    /// sizing itself from `Position::quantity`.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`] if there is no position to close.
+    pub fn close_position(position: &Position<I, D, BaseOrQuote>) -> Result<Self, OrderError>
+    where
+        BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    {
+        let position_qty = position.quantity();
+        if position_qty.is_zero() {
+            return Err(OrderError::NoPositionToClose);
+        }
+        let side = if position_qty > BaseOrQuote::zero() {
+            Side::Sell
+        } else {
+            Side::Buy
+        };
+        let mut order = Self::new(side, position_qty.abs())?;
+        order.reduce_only = true;
+
+        Ok(order)
+    }
+
     /// Take in the order metadata provided by the exchange and coverts the order to the `Pending` state.
     pub fn into_pending(
         self,
@@ -119,6 +150,7 @@ where
             user_order_id: self.user_order_id,
             side: self.side,
             quantity: self.quantity,
+            reduce_only: self.reduce_only,
             state: Pending::new(meta),
             _quote: std::marker::PhantomData,
         }
@@ -132,6 +164,13 @@ where
     BaseOrQuote: Currency<I, D>,
     UserOrderIdT: UserOrderId,
 {
+    /// Reduce the `quantity` of a reduce-only order to the size of the position it closes.
+    /// The `quantity` must be GT than zero.
+    pub(crate) fn set_quantity(&mut self, quantity: BaseOrQuote) {
+        assert!(quantity > BaseOrQuote::zero());
+        self.quantity = quantity;
+    }
+
     /// Mark the order as filled, by modifying its state.
     pub(crate) fn into_filled(
         self,
@@ -149,6 +188,7 @@ where
             ),
             quantity: self.quantity,
             side: self.side,
+            reduce_only: self.reduce_only,
             _quote: std::marker::PhantomData,
         }
     }
@@ -157,7 +197,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{types::BaseCurrency, utils::NoUserOrderId};
+    use crate::{prelude::PositionInner, types::BaseCurrency, utils::NoUserOrderId};
 
     #[test_case::test_matrix([Side::Buy, Side::Sell])]
     fn market_order_new(side: Side) {
@@ -191,4 +231,32 @@ mod test {
             Err(OrderError::OrderQuantityLTEZero)
         );
     }
+
+    #[test]
+    fn market_order_close_position() {
+        let qty = BaseCurrency::<i64, 5>::new(5, 0);
+        let entry_price = QuoteCurrency::new(100, 0);
+
+        let order = MarketOrder::<_, 5, _, NoUserOrderId, _>::close_position(&Position::Long(
+            PositionInner::new(qty, entry_price),
+        ))
+        .unwrap();
+        assert_eq!(order.side(), Side::Sell);
+        assert_eq!(order.quantity(), qty);
+        assert!(order.reduce_only());
+
+        let order = MarketOrder::<_, 5, _, NoUserOrderId, _>::close_position(&Position::Short(
+            PositionInner::new(qty, entry_price),
+        ))
+        .unwrap();
+        assert_eq!(order.side(), Side::Buy);
+        assert_eq!(order.quantity(), qty);
+        assert!(order.reduce_only());
+
+        let neutral = Position::<i64, 5, BaseCurrency<i64, 5>>::Neutral;
+        assert_eq!(
+            MarketOrder::<_, 5, _, NoUserOrderId, _>::close_position(&neutral),
+            Err(OrderError::NoPositionToClose)
+        );
+    }
 }
@@ -1,35 +1,66 @@
+mod adl_model;
 mod balances;
 mod errors;
 mod fee;
+mod fee_tier;
+mod latency_model;
 mod leverage;
 mod limit_order;
 mod limits;
+mod linked_order;
+mod liquidation_model;
+mod margin_mode;
 mod market_order;
+mod order_book_depth;
+mod order_event;
 mod order_id;
 mod order_meta;
+mod order_request;
 mod order_status;
 mod order_update;
 mod re_pricing;
+mod risk_limit_tier;
 mod side;
+mod slippage_model;
 mod smol_currency;
+mod stop_order;
+mod time_in_force;
 mod timestamp_ns;
+mod trigger_by;
 
+pub use adl_model::{AdlModel, AdlRankingIndicator};
 pub use balances::Balances;
 pub use errors::*;
 pub use fee::{Fee, Maker, Taker};
+pub(crate) use fee_tier::RollingVolume;
+pub use fee_tier::{FEE_TIER_VOLUME_WINDOW_NS, FeeTier};
+pub use latency_model::LatencyModel;
+pub(crate) use latency_model::LatencySampler;
 pub use leverage::Leverage;
 pub use limit_order::{LimitOrder, price_time_priority_ordering};
 pub use limits::OrderRateLimits;
+pub use linked_order::LinkedOrder;
+pub use liquidation_model::{LiquidationMode, LiquidationModel, LiquidationSettlement};
+pub use margin_mode::MarginMode;
 pub use market_order::MarketOrder;
+pub(crate) use order_book_depth::take_liquidity;
+pub use order_book_depth::{OrderBookDepth, PriceLevel};
+pub use order_event::{CancelReason, OrderEvent, TriggeredOrder};
 pub use order_id::OrderId;
 pub use order_meta::ExchangeOrderMeta;
+pub use order_request::{OrderRequest, OrderResponse};
 pub use order_status::{Filled, FilledQuantity, NewOrder, Pending};
 pub use order_update::LimitOrderFill;
 pub use re_pricing::RePricing;
+pub use risk_limit_tier::RiskLimitTier;
 pub use side::Side;
+pub use slippage_model::SlippageModel;
 pub use smol_currency::{BaseCurrency, Currency, MarginCurrency, Mon, QuoteCurrency};
+pub use stop_order::{StopOrder, StopOrderType, TrailingOffset, TriggerDirection};
+pub use time_in_force::TimeInForce;
 pub(crate) use timestamp_ns::NANOS_PER_SECOND;
 pub use timestamp_ns::TimestampNs;
+pub use trigger_by::TriggerBy;
 
 /// Natural Logarithmic Returns newtype wrapping a borrowed slice of generic floats.
 pub struct LnReturns<'a, T: num_traits::Float>(pub &'a [T]);
@@ -0,0 +1,463 @@
+use getset::Getters;
+use num_traits::Zero;
+
+use super::{Currency, Mon, QuoteCurrency, Side};
+
+/// The visible quantity resting at a price level of the order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The price of the level.
+    pub price: QuoteCurrency<I, D>,
+    /// The total quantity resting at the level.
+    pub quantity: BaseOrQuote,
+}
+
+/// A snapshot of the visible order book depth.
+/// The `bids` are ordered from the highest to the lowest price and the `asks` from the lowest to the highest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Getters)]
+pub struct OrderBookDepth<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The bid levels, best first.
+    #[getset(get = "pub")]
+    bids: Vec<PriceLevel<I, D, BaseOrQuote>>,
+    /// The ask levels, best first.
+    #[getset(get = "pub")]
+    asks: Vec<PriceLevel<I, D, BaseOrQuote>>,
+}
+
+impl<I, const D: u8, BaseOrQuote> OrderBookDepth<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new depth snapshot from the `bids` and `asks` levels in any order.
+    /// Levels without any quantity are dropped.
+    pub fn new(
+        mut bids: Vec<PriceLevel<I, D, BaseOrQuote>>,
+        mut asks: Vec<PriceLevel<I, D, BaseOrQuote>>,
+    ) -> Self {
+        bids.retain(|level| level.quantity > BaseOrQuote::zero());
+        asks.retain(|level| level.quantity > BaseOrQuote::zero());
+        bids.sort_unstable_by(|a, b| b.price.cmp(&a.price));
+        asks.sort_unstable_by(|a, b| a.price.cmp(&b.price));
+        Self { bids, asks }
+    }
+
+    /// The highest bid price, if any.
+    #[inline]
+    pub fn best_bid(&self) -> Option<QuoteCurrency<I, D>> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    /// The lowest ask price, if any.
+    #[inline]
+    pub fn best_ask(&self) -> Option<QuoteCurrency<I, D>> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// The volume weighted average price at which a taker order of `quantity` on `side` fills,
+    /// by walking the levels of the away side of the book.
+    /// The quantity exceeding the visible depth is filled at the worst visible level.
+    /// Returns `None` if the away side is empty.
+    pub(crate) fn fill_price(
+        &self,
+        side: Side,
+        quantity: BaseOrQuote,
+    ) -> Option<QuoteCurrency<I, D>> {
+        assert2::debug_assert!(quantity > BaseOrQuote::zero());
+        let levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+        let worst_price = levels.last()?.price;
+
+        let mut avg_price = QuoteCurrency::zero();
+        let mut filled_qty = BaseOrQuote::zero();
+        for (price, level_qty) in levels
+            .iter()
+            .map(|level| (level.price, level.quantity))
+            .chain(std::iter::once((worst_price, quantity)))
+        {
+            let qty = level_qty.min(quantity - filled_qty);
+            avg_price = if filled_qty.is_zero() {
+                price
+            } else {
+                QuoteCurrency::new_weighted_price(
+                    avg_price,
+                    *filled_qty.as_ref(),
+                    price,
+                    *qty.as_ref(),
+                )
+            };
+            filled_qty += qty;
+            if filled_qty >= quantity {
+                break;
+            }
+        }
+        debug_assert_eq!(filled_qty, quantity);
+
+        Some(avg_price)
+    }
+
+    /// The quantity and volume weighted average price at which a marketable limit order
+    /// of `quantity` on `side` fills, by walking the levels of the away side of the book up to its `limit_price`.
+    /// Returns `None` if no level is within the `limit_price`.
+    pub(crate) fn marketable_fill(
+        &self,
+        side: Side,
+        limit_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Option<(BaseOrQuote, QuoteCurrency<I, D>)> {
+        assert2::debug_assert!(quantity > BaseOrQuote::zero());
+        let levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+
+        let mut avg_price = QuoteCurrency::zero();
+        let mut filled_qty = BaseOrQuote::zero();
+        for level in levels.iter().take_while(|level| match side {
+            Side::Buy => level.price <= limit_price,
+            Side::Sell => level.price >= limit_price,
+        }) {
+            let qty = level.quantity.min(quantity - filled_qty);
+            avg_price = if filled_qty.is_zero() {
+                level.price
+            } else {
+                QuoteCurrency::new_weighted_price(
+                    avg_price,
+                    *filled_qty.as_ref(),
+                    level.price,
+                    *qty.as_ref(),
+                )
+            };
+            filled_qty += qty;
+            if filled_qty >= quantity {
+                break;
+            }
+        }
+        if filled_qty.is_zero() {
+            return None;
+        }
+
+        Some((filled_qty, avg_price))
+    }
+
+    /// Take up to `quantity` of liquidity for a resting limit order on `side`
+    /// from the levels of the away side priced strictly better than its `limit_price`.
+    /// Emptied levels are removed. Returns the taken quantity.
+    pub(crate) fn take_liquidity(
+        &mut self,
+        side: Side,
+        limit_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> BaseOrQuote {
+        let levels = match side {
+            Side::Buy => &mut self.asks,
+            Side::Sell => &mut self.bids,
+        };
+        let taken_qty = take_liquidity(levels, side, limit_price, quantity);
+        levels.retain(|level| level.quantity > BaseOrQuote::zero());
+        taken_qty
+    }
+
+    /// Replace the quantity resting at the price of `level` on the `side` of the book,
+    /// where `Side::Buy` denotes the bids. A zero quantity removes the level.
+    pub(crate) fn update_level(&mut self, side: Side, level: PriceLevel<I, D, BaseOrQuote>) {
+        let (levels, idx) = match side {
+            Side::Buy => {
+                let idx = self
+                    .bids
+                    .binary_search_by(|probe| level.price.cmp(&probe.price));
+                (&mut self.bids, idx)
+            }
+            Side::Sell => {
+                let idx = self
+                    .asks
+                    .binary_search_by(|probe| probe.price.cmp(&level.price));
+                (&mut self.asks, idx)
+            }
+        };
+        match idx {
+            Ok(idx) if level.quantity.is_zero() => {
+                levels.remove(idx);
+            }
+            Ok(idx) => levels[idx].quantity = level.quantity,
+            Err(_) if level.quantity.is_zero() => {}
+            Err(idx) => levels.insert(idx, level),
+        }
+    }
+
+    /// Replace the best level on the `side` of the book, where `Side::Buy` denotes the bids.
+    /// Levels priced better than it are removed, while the levels behind it are kept.
+    pub(crate) fn update_best_level(&mut self, side: Side, level: PriceLevel<I, D, BaseOrQuote>) {
+        match side {
+            Side::Buy => self.bids.retain(|probe| probe.price <= level.price),
+            Side::Sell => self.asks.retain(|probe| probe.price >= level.price),
+        }
+        self.update_level(side, level);
+    }
+
+    /// The visible quantity resting at `price` on the `side` of the book, where `Side::Buy` denotes the bids.
+    /// A price without a level is empty, unless it lies beyond the worst visible level where nothing is known.
+    pub(crate) fn visible_quantity(
+        &self,
+        side: Side,
+        price: QuoteCurrency<I, D>,
+    ) -> Option<BaseOrQuote> {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        if let Some(level) = levels.iter().find(|level| level.price == price) {
+            return Some(level.quantity);
+        }
+        let worst_price = levels.last()?.price;
+        let beyond_worst = match side {
+            Side::Buy => price < worst_price,
+            Side::Sell => price > worst_price,
+        };
+        (!beyond_worst).then_some(BaseOrQuote::zero())
+    }
+}
+
+/// Take up to `quantity` of liquidity for a resting limit order on `side`
+/// from the `levels` priced strictly better than its `limit_price`, in any order.
+/// Because the limit order is assumed to have the worst possible queue position,
+/// liquidity at its `limit_price` does not fill it.
+/// Returns the taken quantity.
+pub(crate) fn take_liquidity<I, const D: u8, BaseOrQuote>(
+    levels: &mut [PriceLevel<I, D, BaseOrQuote>],
+    side: Side,
+    limit_price: QuoteCurrency<I, D>,
+    quantity: BaseOrQuote,
+) -> BaseOrQuote
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    let mut taken_qty = BaseOrQuote::zero();
+    for level in levels.iter_mut().filter(|level| match side {
+        Side::Buy => level.price < limit_price,
+        Side::Sell => level.price > limit_price,
+    }) {
+        let qty = level.quantity.min(quantity - taken_qty);
+        level.quantity -= qty;
+        taken_qty += qty;
+        if taken_qty >= quantity {
+            break;
+        }
+    }
+    taken_qty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    fn level(price: i64, quantity: i64) -> PriceLevel<i64, 5, BaseCurrency<i64, 5>> {
+        PriceLevel {
+            price: QuoteCurrency::new(price, 0),
+            quantity: BaseCurrency::new(quantity, 0),
+        }
+    }
+
+    #[test]
+    fn order_book_depth_new() {
+        let depth = OrderBookDepth::new(vec![level(98, 1), level(100, 2), level(99, 0)], vec![
+            level(102, 3),
+            level(101, 4),
+        ]);
+        assert_eq!(depth.bids(), &vec![level(100, 2), level(98, 1)]);
+        assert_eq!(depth.asks(), &vec![level(101, 4), level(102, 3)]);
+        assert_eq!(depth.best_bid(), Some(QuoteCurrency::new(100, 0)));
+        assert_eq!(depth.best_ask(), Some(QuoteCurrency::new(101, 0)));
+        assert_eq!(
+            OrderBookDepth::<i64, 5, BaseCurrency<i64, 5>>::default().best_bid(),
+            None
+        );
+    }
+
+    #[test]
+    fn order_book_depth_fill_price() {
+        let depth = OrderBookDepth::new(vec![level(100, 2), level(99, 3)], vec![
+            level(101, 2),
+            level(102, 3),
+        ]);
+        // Within the best level.
+        assert_eq!(
+            depth.fill_price(Side::Buy, BaseCurrency::new(1, 0)),
+            Some(QuoteCurrency::new(101, 0))
+        );
+        // Walks the first two levels.
+        assert_eq!(
+            depth.fill_price(Side::Buy, BaseCurrency::new(5, 0)),
+            Some(QuoteCurrency::new(1016, 1))
+        );
+        assert_eq!(
+            depth.fill_price(Side::Sell, BaseCurrency::new(5, 0)),
+            Some(QuoteCurrency::new(994, 1))
+        );
+        // Exceeds the visible depth, so the rest is filled at the worst level.
+        assert_eq!(
+            depth.fill_price(Side::Buy, BaseCurrency::new(10, 0)),
+            Some(QuoteCurrency::new(1018, 1))
+        );
+        assert_eq!(
+            OrderBookDepth::new(vec![level(100, 2)], Vec::new())
+                .fill_price(Side::Buy, BaseCurrency::new(1, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn order_book_depth_marketable_fill() {
+        let depth = OrderBookDepth::new(vec![level(100, 2), level(99, 3)], vec![
+            level(101, 2),
+            level(102, 3),
+            level(103, 5),
+        ]);
+        // Limited by the quantity.
+        assert_eq!(
+            depth.marketable_fill(
+                Side::Buy,
+                QuoteCurrency::new(103, 0),
+                BaseCurrency::new(1, 0)
+            ),
+            Some((BaseCurrency::new(1, 0), QuoteCurrency::new(101, 0)))
+        );
+        // Limited by the limit price.
+        assert_eq!(
+            depth.marketable_fill(
+                Side::Buy,
+                QuoteCurrency::new(102, 0),
+                BaseCurrency::new(10, 0)
+            ),
+            Some((BaseCurrency::new(5, 0), QuoteCurrency::new(1016, 1)))
+        );
+        assert_eq!(
+            depth.marketable_fill(
+                Side::Sell,
+                QuoteCurrency::new(100, 0),
+                BaseCurrency::new(10, 0)
+            ),
+            Some((BaseCurrency::new(2, 0), QuoteCurrency::new(100, 0)))
+        );
+        assert_eq!(
+            depth.marketable_fill(
+                Side::Sell,
+                QuoteCurrency::new(101, 0),
+                BaseCurrency::new(1, 0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn order_book_depth_take_liquidity() {
+        let mut depth = OrderBookDepth::new(vec![level(100, 2)], vec![
+            level(101, 2),
+            level(102, 3),
+            level(103, 5),
+        ]);
+        // Liquidity at the limit price is not taken.
+        assert_eq!(
+            depth.take_liquidity(
+                Side::Buy,
+                QuoteCurrency::new(103, 0),
+                BaseCurrency::new(3, 0)
+            ),
+            BaseCurrency::new(3, 0)
+        );
+        assert_eq!(depth.asks(), &vec![level(102, 2), level(103, 5)]);
+        assert_eq!(
+            depth.take_liquidity(
+                Side::Buy,
+                QuoteCurrency::new(103, 0),
+                BaseCurrency::new(3, 0)
+            ),
+            BaseCurrency::new(2, 0)
+        );
+        assert_eq!(depth.asks(), &vec![level(103, 5)]);
+        assert_eq!(
+            depth.take_liquidity(
+                Side::Sell,
+                QuoteCurrency::new(100, 0),
+                BaseCurrency::new(1, 0)
+            ),
+            BaseCurrency::zero()
+        );
+        assert_eq!(depth.bids(), &vec![level(100, 2)]);
+    }
+
+    #[test]
+    fn order_book_depth_update_level() {
+        let mut depth = OrderBookDepth::new(vec![level(100, 2), level(98, 1)], vec![level(101, 4)]);
+        depth.update_level(Side::Buy, level(99, 3));
+        assert_eq!(depth.bids(), &vec![
+            level(100, 2),
+            level(99, 3),
+            level(98, 1)
+        ]);
+        depth.update_level(Side::Buy, level(100, 1));
+        depth.update_level(Side::Buy, level(98, 0));
+        depth.update_level(Side::Buy, level(97, 0));
+        assert_eq!(depth.bids(), &vec![level(100, 1), level(99, 3)]);
+
+        depth.update_level(Side::Sell, level(103, 1));
+        depth.update_level(Side::Sell, level(102, 2));
+        depth.update_level(Side::Sell, level(101, 0));
+        assert_eq!(depth.asks(), &vec![level(102, 2), level(103, 1)]);
+        assert_eq!(depth.best_bid(), Some(QuoteCurrency::new(100, 0)));
+        assert_eq!(depth.best_ask(), Some(QuoteCurrency::new(102, 0)));
+    }
+
+    #[test]
+    fn order_book_depth_update_best_level() {
+        let mut depth = OrderBookDepth::new(vec![level(100, 2), level(99, 3)], vec![
+            level(101, 4),
+            level(102, 1),
+        ]);
+        depth.update_best_level(Side::Buy, level(99, 1));
+        depth.update_best_level(Side::Sell, level(100, 5));
+        assert_eq!(depth.bids(), &vec![level(99, 1)]);
+        assert_eq!(depth.asks(), &vec![
+            level(100, 5),
+            level(101, 4),
+            level(102, 1)
+        ]);
+    }
+
+    #[test]
+    fn order_book_depth_visible_quantity() {
+        let depth = OrderBookDepth::new(vec![level(100, 2), level(98, 3)], vec![level(101, 4)]);
+        let price = |p| QuoteCurrency::new(p, 0);
+        assert_eq!(
+            depth.visible_quantity(Side::Buy, price(100)),
+            Some(BaseCurrency::new(2, 0))
+        );
+        assert_eq!(
+            depth.visible_quantity(Side::Buy, price(99)),
+            Some(BaseCurrency::zero())
+        );
+        assert_eq!(
+            depth.visible_quantity(Side::Buy, price(101)),
+            Some(BaseCurrency::zero())
+        );
+        assert_eq!(depth.visible_quantity(Side::Buy, price(97)), None);
+        assert_eq!(depth.visible_quantity(Side::Sell, price(102)), None);
+        assert_eq!(
+            OrderBookDepth::<i64, 5, BaseCurrency<i64, 5>>::default()
+                .visible_quantity(Side::Sell, price(102)),
+            None
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use const_decimal::Decimal;
+
+use super::{
+    Currency, Error, Filled, LimitOrder, MarketOrder, Mon, OrderId, Pending, QuoteCurrency,
+    StopOrder, UserOrderId,
+};
+
+/// The reason why the exchange cancelled an order.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CancelReason {
+    /// An order linked to it was filled, triggered or cancelled.
+    LinkedOrder,
+    /// There was not enough available balance to keep the order active.
+    NotEnoughAvailableBalance,
+    /// The `TimeInForce` of the order did not allow the remaining quantity to rest in the book.
+    UnfilledRemainder,
+    /// The `GoodTilDate` time in force of the order expired.
+    Expired,
+    /// The position the reduce-only order was reducing has been closed or flipped.
+    ReduceOnly,
+}
+
+/// The order that was created when a `StopOrder` got triggered.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TriggeredOrder<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// A stop-market order was triggered and immediately filled.
+    Market(MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>),
+    /// A stop-limit order was triggered and its `LimitOrder` is now active.
+    Limit(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>),
+    /// The resulting order was rejected, e.g. due to insufficient margin.
+    Rejected(Error),
+}
+
+/// Events regarding orders, other than fills of resting limit orders which are reported as `LimitOrderFill`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderEvent<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// A `StopOrder` was triggered and is no longer active.
+    StopTriggered {
+        /// The stop order that was triggered.
+        stop_order: StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>,
+        /// The reference price which caused the trigger.
+        reference_price: QuoteCurrency<I, D>,
+        /// The order which was created as a result.
+        triggered_order: TriggeredOrder<I, D, BaseOrQuote, UserOrderIdT>,
+    },
+    /// An order was cancelled by the exchange.
+    Cancelled {
+        /// The id of the cancelled order.
+        order_id: OrderId,
+        /// Why the order was cancelled.
+        reason: CancelReason,
+    },
+    /// The quantity of an order was adjusted, because an order linked to it was filled
+    /// or because the position a reduce-only order reduces has shrunk.
+    QuantityAdjusted {
+        /// The id of the adjusted order.
+        order_id: OrderId,
+        /// The new remaining quantity of the order.
+        quantity: BaseOrQuote,
+    },
+    /// An exit order of a bracket became active, because its entry order was filled.
+    Activated {
+        /// The id of the activated order.
+        order_id: OrderId,
+        /// The quantity of the activated order.
+        quantity: BaseOrQuote,
+    },
+    /// The exchange liquidated the position, or a part of it when liquidating partially,
+    /// because it no longer met its maintenance margin.
+    Liquidated {
+        /// The liquidation order, filled at the settlement price of the `LiquidationModel`.
+        order: MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>,
+        /// The price at which the losses of the position exhausted the margin backing it,
+        /// or the market price if the margin covered its losses at any price.
+        bankruptcy_price: QuoteCurrency<I, D>,
+        /// The liquidation fee charged on top of any trading fee, which is paid into the insurance fund.
+        liquidation_fee: BaseOrQuote::PairedCurrency,
+    },
+    /// The exchange auto-deleveraged the profitable position, or a part of it,
+    /// against a bankrupt counterparty whose loss the insurance fund could not absorb.
+    AutoDeleveraged {
+        /// The order reducing the position, filled at the bankruptcy price of the counterparty without a fee.
+        order: MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>,
+        /// The ranking of the position in the auto-deleveraging queue according to the `AdlModel`.
+        ranking: Decimal<I, D>,
+    },
+}
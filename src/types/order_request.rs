@@ -0,0 +1,53 @@
+use super::{
+    Currency, Error, Filled, LimitOrder, MarketOrder, Mon, NewOrder, OrderId, Pending, UserOrderId,
+};
+use crate::exchange::CancelBy;
+
+/// A request sent to the exchange through `Exchange::send_request`,
+/// which takes effect once it reaches the exchange after the configured latency.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderRequest<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Submit a new `MarketOrder`.
+    SubmitMarketOrder(MarketOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>),
+    /// Submit a new `LimitOrder`.
+    SubmitLimitOrder(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>),
+    /// Amend an existing `LimitOrder`.
+    AmendLimitOrder {
+        /// The id of the order to amend.
+        existing_order_id: OrderId,
+        /// The order that replaces it.
+        new_order: LimitOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>,
+    },
+    /// Cancel an active `LimitOrder`.
+    CancelLimitOrder(CancelBy<UserOrderIdT>),
+}
+
+/// The outcome of an `OrderRequest`, evaluated against the market state at the time it reached the exchange.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderResponse<I, const D: u8, BaseOrQuote, UserOrderIdT>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// The `MarketOrder` was filled.
+    MarketOrderFilled(MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Filled<I, D, BaseOrQuote>>),
+    /// The `LimitOrder` was accepted, which includes any immediate fills of a marketable order.
+    LimitOrderSubmitted(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>),
+    /// The `LimitOrder` was amended and this is the order replacing it.
+    LimitOrderAmended(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>),
+    /// The `LimitOrder` was cancelled.
+    LimitOrderCancelled(LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>),
+    /// The request was rejected.
+    Rejected {
+        /// The rejected request.
+        request: OrderRequest<I, D, BaseOrQuote, UserOrderIdT>,
+        /// The reason for the rejection.
+        error: Error,
+    },
+}
@@ -0,0 +1,105 @@
+use const_decimal::Decimal;
+use getset::CopyGetters;
+use num_traits::{One, Zero};
+
+use super::{ConfigError, Currency, Leverage, Mon};
+
+/// A bracket of the risk limit tier table, which sets the margin requirements of positions up to a notional value.
+/// Larger positions fall into higher tiers with a lower maximum leverage and a higher maintenance margin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CopyGetters)]
+pub struct RiskLimitTier<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The largest position notional value, denoted in the margin currency, which falls into this tier.
+    #[getset(get_copy = "pub")]
+    max_notional: BaseOrQuote,
+
+    /// The highest leverage allowed within this tier, which sets its initial margin requirement.
+    #[getset(get_copy = "pub")]
+    max_leverage: Leverage<I, D>,
+
+    /// The minimum margin as a fraction of the position notional value,
+    /// that must be maintained to keep a position of this tier open.
+    #[getset(get_copy = "pub")]
+    maintenance_margin: Decimal<I, D>,
+}
+
+impl<I, const D: u8, BaseOrQuote> RiskLimitTier<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new risk limit tier.
+    ///
+    /// # Arguments:
+    /// `max_notional`: The largest position notional value, denoted in the margin currency, which falls into this tier.
+    /// `max_leverage`: The highest leverage allowed within this tier.
+    /// `maintenance_margin`: The maintenance margin requirement in range (0..1],
+    /// which must not exceed the initial margin requirement of the `max_leverage`.
+    pub fn new(
+        max_notional: BaseOrQuote,
+        max_leverage: Leverage<I, D>,
+        maintenance_margin: Decimal<I, D>,
+    ) -> Result<Self, ConfigError> {
+        if max_notional <= BaseOrQuote::zero() {
+            return Err(ConfigError::InvalidRiskLimitTiers);
+        }
+        if maintenance_margin <= Decimal::zero()
+            || maintenance_margin > Decimal::one()
+            || maintenance_margin > max_leverage.init_margin_req()
+        {
+            return Err(ConfigError::InvalidMaintenanceMarginFraction);
+        }
+
+        Ok(Self {
+            max_notional,
+            max_leverage,
+            maintenance_margin,
+        })
+    }
+
+    /// The initial margin requirement of this tier, implied by its `max_leverage`.
+    #[inline]
+    pub fn init_margin_req(&self) -> Decimal<I, D> {
+        self.max_leverage.init_margin_req()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{leverage, prelude::QuoteCurrency};
+
+    #[test]
+    fn risk_limit_tier() {
+        let tier = RiskLimitTier::<i64, 5, _>::new(
+            QuoteCurrency::new(10_000, 0),
+            leverage!(10),
+            Decimal::try_from_scaled(5, 2).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            tier.init_margin_req(),
+            Decimal::try_from_scaled(1, 1).unwrap()
+        );
+
+        assert_eq!(
+            RiskLimitTier::<i64, 5, _>::new(
+                QuoteCurrency::zero(),
+                leverage!(10),
+                Decimal::try_from_scaled(5, 2).unwrap(),
+            ),
+            Err(ConfigError::InvalidRiskLimitTiers)
+        );
+        assert_eq!(
+            RiskLimitTier::<i64, 5, _>::new(
+                QuoteCurrency::new(10_000, 0),
+                leverage!(10),
+                Decimal::try_from_scaled(2, 1).unwrap(),
+            ),
+            Err(ConfigError::InvalidMaintenanceMarginFraction)
+        );
+    }
+}
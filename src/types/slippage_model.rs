@@ -0,0 +1,167 @@
+use const_decimal::Decimal;
+use num_traits::{One, Zero};
+
+use super::{ConfigError, Currency, Mon, QuoteCurrency, Side};
+use crate::utils::decimal_from_f64;
+
+/// How far the fill price of a `MarketOrder` slips from the best bid or ask,
+/// when there is no order book depth for it to walk.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SlippageModel<I, const D: u8, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// The entire quantity is filled at the best bid or ask.
+    #[default]
+    NoSlippage,
+    /// The fill price is a fixed number of ticks worse than the best bid or ask.
+    FixedTicks(u32),
+    /// The fill price is worse than the best bid or ask by a fraction of it,
+    /// so the slippage costs this fraction of the notional value, e.g. `0.0005` for 5 basis points.
+    FractionOfNotional(Decimal<I, D>),
+    /// Square-root market impact, where the fill price is worse than the best bid or ask
+    /// by the fraction `coefficient * sqrt(quantity / reference_quantity)`.
+    SquareRootImpact {
+        /// Scales the impact, usually in the order of one.
+        coefficient: Decimal<I, D>,
+        /// The quantity relative to which the order quantity is measured, e.g. the daily traded volume.
+        reference_quantity: BaseOrQuote,
+    },
+}
+
+impl<I, const D: u8, BaseOrQuote> SlippageModel<I, D, BaseOrQuote>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        let valid = match self {
+            SlippageModel::NoSlippage | SlippageModel::FixedTicks(_) => true,
+            SlippageModel::FractionOfNotional(fraction) => {
+                *fraction >= Decimal::zero() && *fraction < Decimal::one()
+            }
+            SlippageModel::SquareRootImpact {
+                coefficient,
+                reference_quantity,
+            } => *coefficient >= Decimal::zero() && *reference_quantity > BaseOrQuote::zero(),
+        };
+        if !valid {
+            return Err(ConfigError::InvalidSlippageModel);
+        }
+
+        Ok(())
+    }
+
+    /// The price at which a market order of `quantity` on `side` fills,
+    /// given the `best_price` of the away side of the book.
+    /// A sell order never fills below one `tick_size`.
+    pub(crate) fn fill_price(
+        &self,
+        side: Side,
+        best_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+        tick_size: QuoteCurrency<I, D>,
+    ) -> QuoteCurrency<I, D> {
+        assert2::debug_assert!(best_price > QuoteCurrency::zero());
+        assert2::debug_assert!(quantity > BaseOrQuote::zero());
+        let slippage = match self {
+            SlippageModel::NoSlippage => return best_price,
+            SlippageModel::FixedTicks(ticks) => {
+                let ticks = Decimal::try_from_scaled(
+                    I::from(*ticks).expect("Can convert the number of ticks"),
+                    0,
+                )
+                .expect("Can create `Decimal`");
+                tick_size * ticks
+            }
+            SlippageModel::FractionOfNotional(fraction) => best_price * *fraction,
+            SlippageModel::SquareRootImpact {
+                coefficient,
+                reference_quantity,
+            } => {
+                let quantity: f64 = quantity.into();
+                let reference_quantity: f64 = (*reference_quantity).into();
+                let impact = decimal_from_f64((quantity / reference_quantity).sqrt())
+                    .expect("Can create `Decimal`");
+                best_price * (*coefficient * impact)
+            }
+        };
+        match side {
+            Side::Buy => best_price + slippage,
+            Side::Sell => (best_price - slippage).max(tick_size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BaseCurrency;
+
+    type Model = SlippageModel<i64, 5, BaseCurrency<i64, 5>>;
+
+    #[test]
+    fn slippage_model_validate() {
+        Model::NoSlippage.validate().unwrap();
+        Model::FixedTicks(5).validate().unwrap();
+        Model::FractionOfNotional(Decimal::try_from_scaled(5, 4).unwrap())
+            .validate()
+            .unwrap();
+        assert_eq!(
+            Model::FractionOfNotional(Decimal::one()).validate(),
+            Err(ConfigError::InvalidSlippageModel)
+        );
+        assert_eq!(
+            Model::FractionOfNotional(Decimal::try_from_scaled(-1, 4).unwrap()).validate(),
+            Err(ConfigError::InvalidSlippageModel)
+        );
+        assert_eq!(
+            Model::SquareRootImpact {
+                coefficient: Decimal::one(),
+                reference_quantity: BaseCurrency::zero(),
+            }
+            .validate(),
+            Err(ConfigError::InvalidSlippageModel)
+        );
+    }
+
+    #[test_case::test_case(Model::NoSlippage, 100, 100)]
+    #[test_case::test_case(Model::FixedTicks(2), 102, 98)]
+    #[test_case::test_case(Model::FractionOfNotional(Decimal::try_from_scaled(1, 2).unwrap()), 101, 99)]
+    #[test_case::test_case(
+        Model::SquareRootImpact {
+            coefficient: Decimal::try_from_scaled(4, 2).unwrap(),
+            reference_quantity: BaseCurrency::new(16, 0),
+        },
+        102,
+        98
+    )]
+    fn slippage_model_fill_price(model: Model, expected_buy: i64, expected_sell: i64) {
+        let tick_size = QuoteCurrency::new(1, 0);
+        let best_price = QuoteCurrency::new(100, 0);
+        let qty = BaseCurrency::new(4, 0);
+        assert_eq!(
+            model.fill_price(Side::Buy, best_price, qty, tick_size),
+            QuoteCurrency::new(expected_buy, 0)
+        );
+        assert_eq!(
+            model.fill_price(Side::Sell, best_price, qty, tick_size),
+            QuoteCurrency::new(expected_sell, 0)
+        );
+    }
+
+    #[test]
+    fn slippage_model_fill_price_floor() {
+        let tick_size = QuoteCurrency::new(1, 0);
+        assert_eq!(
+            Model::FixedTicks(10).fill_price(
+                Side::Sell,
+                QuoteCurrency::new(5, 0),
+                BaseCurrency::new(1, 0),
+                tick_size
+            ),
+            tick_size
+        );
+    }
+}
@@ -109,6 +109,24 @@ where
         BaseCurrency::convert_from(quantity, entry_price)
             - BaseCurrency::convert_from(quantity, exit_price)
     }
+
+    /// Solves `margin + quantity / entry_price - quantity / exit_price = maint_margin_req * |quantity| / exit_price`.
+    #[inline]
+    fn liquidation_price(
+        entry_price: QuoteCurrency<I, D>,
+        quantity: QuoteCurrency<I, D>,
+        margin: BaseCurrency<I, D>,
+        maint_margin_req: Decimal<I, D>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        assert2::debug_assert!(entry_price > Zero::zero());
+        let quantity = *quantity.as_ref();
+        let denominator = margin.0 + quantity / *entry_price.as_ref();
+        if denominator.is_zero() {
+            return None;
+        }
+        let price = (quantity + maint_margin_req * quantity.abs()) / denominator;
+        (price > Decimal::zero()).then_some(QuoteCurrency::from(price))
+    }
 }
 
 impl<I, const D: u8> Zero for BaseCurrency<I, D>
@@ -255,6 +273,25 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn base_currency_liquidation_price() {
+        let entry = QuoteCurrency::<i64, 5>::new(100, 0);
+        let qty = QuoteCurrency::new(1000, 0);
+        assert_eq!(
+            BaseCurrency::liquidation_price(entry, qty, BaseCurrency::new(25, 1), Decimal::zero()),
+            Some(QuoteCurrency::new(80, 0))
+        );
+        assert_eq!(
+            BaseCurrency::liquidation_price(entry, -qty, BaseCurrency::new(5, 0), Decimal::zero()),
+            Some(QuoteCurrency::new(200, 0))
+        );
+        // The margin covers the short up to any price.
+        assert_eq!(
+            BaseCurrency::liquidation_price(entry, -qty, BaseCurrency::new(12, 0), Decimal::zero()),
+            None
+        );
+    }
+
     #[test]
     fn base_currency() {
         let v = BaseCurrency::<i64, 5>::new(100, 0);
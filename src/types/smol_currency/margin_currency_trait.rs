@@ -1,3 +1,5 @@
+use const_decimal::Decimal;
+
 use super::{Currency, Mon, QuoteCurrency};
 
 /// Each Currency that is used as margin has to implement this trait.
@@ -29,4 +31,23 @@ where
         exit_price: QuoteCurrency<I, D>,
         quantity: Self::PairedCurrency,
     ) -> Self;
+
+    /// Compute the exit price at which the `margin` backing a position, plus its profit and loss,
+    /// falls to the `maint_margin_req` of its notional value at the exit price.
+    /// With a `maint_margin_req` of zero, this is the bankruptcy price of the position.
+    ///
+    /// # Arguments:
+    /// `entry_price`: The price at which the position was initiated.
+    /// `quantity`: The amount of contracts held, must be negative if short.
+    /// `margin`: The margin backing the position.
+    /// `maint_margin_req`: The maintenance margin requirement relative to the notional value at the exit price.
+    ///
+    /// # Returns:
+    /// The exit price or `None` if the position does not reach it at any positive price.
+    fn liquidation_price(
+        entry_price: QuoteCurrency<I, D>,
+        quantity: Self::PairedCurrency,
+        margin: Self,
+        maint_margin_req: Decimal<I, D>,
+    ) -> Option<QuoteCurrency<I, D>>;
 }
@@ -49,18 +49,6 @@ where
         Self(Decimal::try_from_scaled(integer, scale).expect("Make sure the inputs are correct."))
     }
 
-    #[inline]
-    pub(crate) fn liquidation_price_long(&self, maint_margin_req: Decimal<I, D>) -> Self {
-        assert2::debug_assert!(maint_margin_req <= Decimal::ONE);
-        Self(self.0 * (Decimal::one() - maint_margin_req))
-    }
-
-    #[inline]
-    pub(crate) fn liquidation_price_short(&self, maint_margin_req: Decimal<I, D>) -> Self {
-        assert2::debug_assert!(maint_margin_req <= Decimal::ONE);
-        Self(self.0 * (Decimal::one() + maint_margin_req))
-    }
-
     pub(crate) fn new_weighted_price(
         price_0: Self,
         weight_0: Decimal<I, D>,
@@ -136,6 +124,24 @@ where
         QuoteCurrency::convert_from(quantity, exit_price)
             - QuoteCurrency::convert_from(quantity, entry_price)
     }
+
+    /// Solves `margin + quantity * (exit_price - entry_price) = maint_margin_req * |quantity| * exit_price`.
+    #[inline]
+    fn liquidation_price(
+        entry_price: QuoteCurrency<I, D>,
+        quantity: BaseCurrency<I, D>,
+        margin: QuoteCurrency<I, D>,
+        maint_margin_req: Decimal<I, D>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        assert2::debug_assert!(entry_price > Zero::zero());
+        let quantity = *quantity.as_ref();
+        let denominator = quantity - maint_margin_req * quantity.abs();
+        if denominator.is_zero() {
+            return None;
+        }
+        let price = (quantity * entry_price.0 - margin.0) / denominator;
+        (price > Decimal::zero()).then_some(Self(price))
+    }
 }
 
 impl<I, const D: u8> Zero for QuoteCurrency<I, D>
@@ -294,6 +300,40 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn quote_currency_liquidation_price() {
+        let entry = QuoteCurrency::<i64, 5>::new(100, 0);
+        let qty = BaseCurrency::new(10, 0);
+        let margin = QuoteCurrency::new(200, 0);
+        assert_eq!(
+            QuoteCurrency::liquidation_price(entry, qty, margin, Decimal::zero()),
+            Some(QuoteCurrency::new(80, 0))
+        );
+        assert_eq!(
+            QuoteCurrency::liquidation_price(entry, -qty, margin, Decimal::zero()),
+            Some(QuoteCurrency::new(120, 0))
+        );
+        assert_eq!(
+            QuoteCurrency::liquidation_price(
+                entry,
+                BaseCurrency::new(40, 0),
+                QuoteCurrency::new(1120, 0),
+                Decimal::try_from_scaled(1, 1).unwrap()
+            ),
+            Some(QuoteCurrency::new(80, 0))
+        );
+        // The margin exceeds the notional value of the long.
+        assert_eq!(
+            QuoteCurrency::liquidation_price(
+                entry,
+                qty,
+                QuoteCurrency::new(1100, 0),
+                Decimal::zero()
+            ),
+            None
+        );
+    }
+
     #[test]
     fn quote_currency() {
         let v = QuoteCurrency::<i64, 5>::new(100, 0);
@@ -0,0 +1,694 @@
+use const_decimal::Decimal;
+use getset::{CopyGetters, Getters};
+use num_traits::Zero;
+
+use super::{
+    Currency, ExchangeOrderMeta, LimitOrder, MarginCurrency, MarketOrder, Mon, NewOrder,
+    OrderError, OrderId, Pending, QuoteCurrency, Side, TriggerBy, UserOrderId,
+};
+use crate::utils::NoUserOrderId;
+
+/// What kind of order a `StopOrder` turns into once it is triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOrderType<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// Becomes a `MarketOrder` when triggered.
+    Market,
+    /// Becomes a `LimitOrder` at `limit_price` when triggered.
+    Limit {
+        /// The limit price of the resulting `LimitOrder`.
+        limit_price: QuoteCurrency<I, D>,
+    },
+}
+
+/// Whether a `StopOrder` is triggered by the reference price rising or falling to the trigger price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerDirection {
+    /// Triggered once the reference price is at or above the trigger price.
+    Rising,
+    /// Triggered once the reference price is at or below the trigger price.
+    Falling,
+}
+
+/// How far a trailing `StopOrder` follows behind the best price seen since its activation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingOffset<I, const D: u8>
+where
+    I: Mon<D>,
+{
+    /// A fixed price distance.
+    Absolute(QuoteCurrency<I, D>),
+    /// A fraction of the best price, e.g. `0.01` for one percent.
+    Fraction(Decimal<I, D>),
+}
+
+impl<I, const D: u8> TrailingOffset<I, D>
+where
+    I: Mon<D>,
+{
+    fn validate(&self) -> Result<(), OrderError> {
+        let valid = match self {
+            TrailingOffset::Absolute(offset) => *offset > QuoteCurrency::zero(),
+            TrailingOffset::Fraction(fraction) => {
+                *fraction > Decimal::zero() && *fraction < Decimal::one()
+            }
+        };
+        if !valid {
+            return Err(OrderError::InvalidTrailingOffset);
+        }
+        Ok(())
+    }
+
+    /// The trigger price of a trailing order on `side`, given the `best_price` seen so far.
+    /// Sell orders trail below the highest price, buy orders trail above the lowest price.
+    pub fn trigger_price(
+        &self,
+        side: Side,
+        best_price: QuoteCurrency<I, D>,
+    ) -> QuoteCurrency<I, D> {
+        match (self, side) {
+            (TrailingOffset::Absolute(offset), Side::Buy) => best_price + *offset,
+            (TrailingOffset::Absolute(offset), Side::Sell) => best_price - *offset,
+            (TrailingOffset::Fraction(fraction), Side::Buy) => {
+                best_price * (Decimal::one() + *fraction)
+            }
+            (TrailingOffset::Fraction(fraction), Side::Sell) => {
+                best_price * (Decimal::one() - *fraction)
+            }
+        }
+    }
+}
+
+/// A conditional order which rests with the exchange until its `trigger_price` is reached,
+/// upon which it becomes either a `MarketOrder` or a `LimitOrder`.
+/// By default buy stops trigger when the reference price rises to or above the `trigger_price`,
+/// sell stops trigger when the reference price falls to or below the `trigger_price`.
+/// Take-profit orders trigger in the opposite direction.
+/// Trailing stop orders move their `trigger_price` along with the best price seen since their activation.
+///
+/// Generics:
+/// - `I`: The numeric data type of currencies.
+/// - `D`: The constant decimal precision of the currencies.
+/// - `BaseOrQuote`: Either `BaseCurrency` or `QuoteCurrency` depending on the futures type.
+/// - `UserOrderId`: The type of user order id to use. Set to `()` if you don't need one.
+/// - `OrderStatus`: The status of the order for each stage, contains different information based on the stage.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, CopyGetters)]
+pub struct StopOrder<I, const D: u8, BaseOrQuote, UserOrderIdT, OrderStatus>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Order Id provided by the user, can be any type really.
+    #[getset(get_copy = "pub")]
+    user_order_id: UserOrderIdT,
+
+    /// Whether its a buy or sell order.
+    #[getset(get_copy = "pub")]
+    side: Side,
+
+    /// The price at which the order is triggered.
+    /// For trailing stop orders its set by the exchange once the order is submitted.
+    #[getset(get_copy = "pub")]
+    trigger_price: QuoteCurrency<I, D>,
+
+    /// If set, the `trigger_price` trails the best price by this offset.
+    #[getset(get_copy = "pub")]
+    trailing_offset: Option<TrailingOffset<I, D>>,
+
+    /// The price source that is compared against the `trigger_price`.
+    #[getset(get_copy = "pub")]
+    trigger_by: TriggerBy,
+
+    /// Whether the order triggers on a rising or falling reference price.
+    #[getset(get_copy = "pub")]
+    trigger_direction: TriggerDirection,
+
+    /// What kind of order is created once triggered.
+    #[getset(get_copy = "pub")]
+    order_type: StopOrderType<I, D>,
+
+    /// The amount of contracts the order is for.
+    #[getset(get_copy = "pub")]
+    quantity: BaseOrQuote,
+
+    /// Depending on the status, different information is available.
+    #[getset(get = "pub")]
+    state: OrderStatus,
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT, OrderStatus> std::fmt::Display
+    for StopOrder<I, D, BaseOrQuote, UserOrderIdT, OrderStatus>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+    OrderStatus: Clone + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "user_id: {:?}, stop {:?} {} {} @ {} by {:?}, state: {:?}",
+            self.user_order_id,
+            self.order_type,
+            self.side,
+            self.quantity,
+            self.trigger_price,
+            self.trigger_by,
+            self.state
+        )
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote> StopOrder<I, D, BaseOrQuote, NoUserOrderId, NewOrder>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+{
+    /// Create a new stop order which becomes a `MarketOrder` once triggered.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trigger_price`: The price at which the order is triggered.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_stop_market(
+        side: Side,
+        trigger_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        Self::new(side, trigger_price, StopOrderType::Market, quantity)
+    }
+
+    /// Create a new stop order which becomes a `LimitOrder` at `limit_price` once triggered.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trigger_price`: The price at which the order is triggered.
+    /// - `limit_price`: The limit price of the `LimitOrder` that is created once triggered.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_stop_limit(
+        side: Side,
+        trigger_price: QuoteCurrency<I, D>,
+        limit_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        Self::new(
+            side,
+            trigger_price,
+            StopOrderType::Limit { limit_price },
+            quantity,
+        )
+    }
+
+    /// Create a new take-profit order which becomes a `MarketOrder` once triggered.
+    /// A sell take-profit triggers when the reference price rises to the `trigger_price`
+    /// and a buy take-profit when it falls to the `trigger_price`.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trigger_price`: The price at which the order is triggered.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_take_profit_market(
+        side: Side,
+        trigger_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        let mut order = Self::new(side, trigger_price, StopOrderType::Market, quantity)?;
+        order.set_trigger_direction(Self::take_profit_direction(side));
+        Ok(order)
+    }
+
+    /// Create a new take-profit order which becomes a `LimitOrder` at `limit_price` once triggered.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trigger_price`: The price at which the order is triggered.
+    /// - `limit_price`: The limit price of the `LimitOrder` that is created once triggered.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_take_profit_limit(
+        side: Side,
+        trigger_price: QuoteCurrency<I, D>,
+        limit_price: QuoteCurrency<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        let mut order = Self::new(
+            side,
+            trigger_price,
+            StopOrderType::Limit { limit_price },
+            quantity,
+        )?;
+        order.set_trigger_direction(Self::take_profit_direction(side));
+        Ok(order)
+    }
+
+    /// Create a new trailing stop order which becomes a `MarketOrder` once triggered.
+    /// A sell order trails below the highest price and a buy order above the lowest price seen since submission.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trailing_offset`: How far the trigger price trails the best price.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_trailing_stop(
+        side: Side,
+        trailing_offset: TrailingOffset<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        Self::new_trailing(side, trailing_offset, quantity)
+    }
+
+    fn take_profit_direction(side: Side) -> TriggerDirection {
+        match side {
+            Side::Buy => TriggerDirection::Falling,
+            Side::Sell => TriggerDirection::Rising,
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT> StopOrder<I, D, BaseOrQuote, UserOrderIdT, NewOrder>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Create a new stop order.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trigger_price`: The price at which the order is triggered.
+    /// - `order_type`: What kind of order is created once triggered.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new(
+        side: Side,
+        trigger_price: QuoteCurrency<I, D>,
+        order_type: StopOrderType<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        if trigger_price <= QuoteCurrency::zero() {
+            return Err(OrderError::TriggerPriceLTEZero);
+        }
+        if let StopOrderType::Limit { limit_price } = order_type
+            && limit_price <= QuoteCurrency::zero()
+        {
+            return Err(OrderError::LimitPriceLTEZero);
+        }
+        if quantity <= BaseOrQuote::zero() {
+            return Err(OrderError::OrderQuantityLTEZero);
+        }
+        let trigger_direction = match side {
+            Side::Buy => TriggerDirection::Rising,
+            Side::Sell => TriggerDirection::Falling,
+        };
+        Ok(Self {
+            user_order_id: UserOrderIdT::default(),
+            side,
+            trigger_price,
+            trailing_offset: None,
+            trigger_by: TriggerBy::default(),
+            trigger_direction,
+            order_type,
+            quantity,
+            state: NewOrder,
+        })
+    }
+
+    /// Create a new trailing stop order which becomes a `MarketOrder` once triggered.
+    /// The `trigger_price` is set by the exchange from the reference price once the order is submitted.
+    ///
+    /// # Arguments:
+    /// - `side`: either buy or sell
+    /// - `trailing_offset`: How far the trigger price trails the best price.
+    /// - `quantity`: A positive nonzero quantity of the amount of contracts this order is for.
+    ///
+    /// # Returns:
+    /// Either a successfully created order or an [`OrderError`]
+    pub fn new_trailing(
+        side: Side,
+        trailing_offset: TrailingOffset<I, D>,
+        quantity: BaseOrQuote,
+    ) -> Result<Self, OrderError> {
+        trailing_offset.validate()?;
+        if quantity <= BaseOrQuote::zero() {
+            return Err(OrderError::OrderQuantityLTEZero);
+        }
+        let trigger_direction = match side {
+            Side::Buy => TriggerDirection::Rising,
+            Side::Sell => TriggerDirection::Falling,
+        };
+        Ok(Self {
+            user_order_id: UserOrderIdT::default(),
+            side,
+            trigger_price: QuoteCurrency::zero(),
+            trailing_offset: Some(trailing_offset),
+            trigger_by: TriggerBy::default(),
+            trigger_direction,
+            order_type: StopOrderType::Market,
+            quantity,
+            state: NewOrder,
+        })
+    }
+
+    /// Set the `UserOrderId`.
+    #[inline]
+    pub fn set_user_order_id(&mut self, user_order_id: UserOrderIdT) {
+        self.user_order_id = user_order_id;
+    }
+
+    /// Set the price source that is compared against the `trigger_price`.
+    #[inline]
+    pub fn set_trigger_by(&mut self, trigger_by: TriggerBy) {
+        self.trigger_by = trigger_by;
+    }
+
+    /// Set whether the order triggers on a rising or falling reference price.
+    #[inline]
+    pub fn set_trigger_direction(&mut self, trigger_direction: TriggerDirection) {
+        self.trigger_direction = trigger_direction;
+    }
+
+    /// Take in the order metadata provided by the exchange and coverts the order to the `Pending` state.
+    pub fn into_pending(
+        self,
+        meta: ExchangeOrderMeta,
+    ) -> StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>> {
+        StopOrder {
+            user_order_id: self.user_order_id,
+            side: self.side,
+            trigger_price: self.trigger_price,
+            trailing_offset: self.trailing_offset,
+            trigger_by: self.trigger_by,
+            trigger_direction: self.trigger_direction,
+            order_type: self.order_type,
+            quantity: self.quantity,
+            state: Pending::new(meta),
+        }
+    }
+}
+
+impl<I, const D: u8, BaseOrQuote, UserOrderIdT>
+    StopOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>
+where
+    I: Mon<D>,
+    BaseOrQuote: Currency<I, D>,
+    BaseOrQuote::PairedCurrency: MarginCurrency<I, D>,
+    UserOrderIdT: UserOrderId,
+{
+    /// Get the order id assigned by the exchange.
+    #[inline]
+    pub fn id(&self) -> OrderId {
+        self.state().meta().id()
+    }
+
+    /// The price the order is valued at, being the `limit_price` for stop-limit orders
+    /// and the `trigger_price` for stop-market orders.
+    #[inline]
+    pub fn notional_price(&self) -> QuoteCurrency<I, D> {
+        match self.order_type {
+            StopOrderType::Market => self.trigger_price,
+            StopOrderType::Limit { limit_price } => limit_price,
+        }
+    }
+
+    /// The notional value of the order at its `notional_price`.
+    #[inline]
+    pub fn notional(&self) -> BaseOrQuote::PairedCurrency {
+        BaseOrQuote::PairedCurrency::convert_from(self.quantity, self.notional_price())
+    }
+
+    /// Whether the order is triggered at the `reference_price`.
+    #[inline]
+    pub fn is_triggered(&self, reference_price: QuoteCurrency<I, D>) -> bool {
+        match self.trigger_direction {
+            TriggerDirection::Rising => reference_price >= self.trigger_price,
+            TriggerDirection::Falling => reference_price <= self.trigger_price,
+        }
+    }
+
+    /// The trigger price of a trailing stop order after seeing the `best_price`.
+    /// Returns `None` if its not a trailing stop order or the trigger price would not improve.
+    pub(crate) fn trailed_trigger_price(
+        &self,
+        best_price: QuoteCurrency<I, D>,
+    ) -> Option<QuoteCurrency<I, D>> {
+        let trigger_price = self.trailing_offset?.trigger_price(self.side, best_price);
+        // A trailing stop without a trigger price has not been activated yet.
+        let improves = self.trigger_price.is_zero()
+            || match self.side {
+                Side::Buy => trigger_price < self.trigger_price,
+                Side::Sell => trigger_price > self.trigger_price,
+            };
+        improves.then_some(trigger_price)
+    }
+
+    /// Modify the `trigger_price`, which must be greater than zero.
+    pub(crate) fn set_trigger_price(&mut self, trigger_price: QuoteCurrency<I, D>) {
+        assert!(trigger_price > QuoteCurrency::zero());
+        self.trigger_price = trigger_price;
+    }
+
+    /// Modify the `quantity`, which must be greater than zero.
+    pub(crate) fn set_quantity(&mut self, quantity: BaseOrQuote) {
+        assert!(quantity > BaseOrQuote::zero());
+        self.quantity = quantity;
+    }
+
+    /// Convert the triggered order into a `MarketOrder`, keeping the `OrderId` of the stop order.
+    /// Returns `None` if the stop order is a stop-limit order.
+    pub(crate) fn to_market_order(
+        &self,
+        meta: ExchangeOrderMeta,
+    ) -> Option<MarketOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        debug_assert_eq!(meta.id(), self.id());
+        match self.order_type {
+            StopOrderType::Market => Some(
+                MarketOrder::new_with_user_order_id(self.side, self.quantity, self.user_order_id)
+                    .expect("Stop order was validated upon creation")
+                    .into_pending(meta),
+            ),
+            StopOrderType::Limit { .. } => None,
+        }
+    }
+
+    /// Convert the triggered order into a `LimitOrder`, keeping the `OrderId` of the stop order.
+    /// Returns `None` if the stop order is a stop-market order.
+    pub(crate) fn to_limit_order(
+        &self,
+        meta: ExchangeOrderMeta,
+    ) -> Option<LimitOrder<I, D, BaseOrQuote, UserOrderIdT, Pending<I, D, BaseOrQuote>>> {
+        debug_assert_eq!(meta.id(), self.id());
+        match self.order_type {
+            StopOrderType::Market => None,
+            StopOrderType::Limit { limit_price } => Some(
+                LimitOrder::new_with_user_order_id(
+                    self.side,
+                    limit_price,
+                    self.quantity,
+                    self.user_order_id,
+                )
+                .expect("Stop order was validated upon creation")
+                .into_pending(meta),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::BaseCurrency;
+
+    #[test_case::test_matrix([Side::Buy, Side::Sell])]
+    fn stop_order_new(side: Side) {
+        let order = StopOrder::new_stop_market(
+            side,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap();
+        assert_eq!(order.order_type(), StopOrderType::Market);
+        assert_eq!(order.trigger_by(), TriggerBy::LastTrade);
+
+        assert_eq!(
+            StopOrder::new_stop_market(
+                side,
+                QuoteCurrency::<i64, 5>::new(0, 0),
+                BaseCurrency::new(5, 0)
+            ),
+            Err(OrderError::TriggerPriceLTEZero)
+        );
+        assert_eq!(
+            StopOrder::new_stop_limit(
+                side,
+                QuoteCurrency::<i64, 5>::new(100, 0),
+                QuoteCurrency::new(0, 0),
+                BaseCurrency::new(5, 0)
+            ),
+            Err(OrderError::LimitPriceLTEZero)
+        );
+        assert_eq!(
+            StopOrder::new_stop_market(
+                side,
+                QuoteCurrency::<i64, 5>::new(100, 0),
+                BaseCurrency::new(0, 0)
+            ),
+            Err(OrderError::OrderQuantityLTEZero)
+        );
+    }
+
+    #[test]
+    fn stop_order_is_triggered() {
+        let meta = ExchangeOrderMeta::new(0.into(), 0.into());
+        let buy = StopOrder::new_stop_market(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone());
+        assert!(!buy.is_triggered(QuoteCurrency::new(99, 0)));
+        assert!(buy.is_triggered(QuoteCurrency::new(100, 0)));
+        assert!(buy.is_triggered(QuoteCurrency::new(101, 0)));
+
+        let sell = StopOrder::new_stop_market(
+            Side::Sell,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta);
+        assert!(sell.is_triggered(QuoteCurrency::new(99, 0)));
+        assert!(sell.is_triggered(QuoteCurrency::new(100, 0)));
+        assert!(!sell.is_triggered(QuoteCurrency::new(101, 0)));
+    }
+
+    #[test]
+    fn stop_order_take_profit_is_triggered() {
+        let meta = ExchangeOrderMeta::new(0.into(), 0.into());
+        let sell = StopOrder::new_take_profit_market(
+            Side::Sell,
+            QuoteCurrency::<i64, 5>::new(110, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone());
+        assert_eq!(sell.trigger_direction(), TriggerDirection::Rising);
+        assert!(!sell.is_triggered(QuoteCurrency::new(109, 0)));
+        assert!(sell.is_triggered(QuoteCurrency::new(110, 0)));
+
+        let buy = StopOrder::new_take_profit_limit(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(90, 0),
+            QuoteCurrency::new(89, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta);
+        assert_eq!(buy.trigger_direction(), TriggerDirection::Falling);
+        assert!(!buy.is_triggered(QuoteCurrency::new(91, 0)));
+        assert!(buy.is_triggered(QuoteCurrency::new(90, 0)));
+    }
+
+    #[test]
+    fn stop_order_trailing() {
+        assert_eq!(
+            StopOrder::new_trailing_stop(
+                Side::Sell,
+                TrailingOffset::Absolute(QuoteCurrency::<i64, 5>::new(0, 0)),
+                BaseCurrency::new(5, 0),
+            ),
+            Err(OrderError::InvalidTrailingOffset)
+        );
+        assert_eq!(
+            StopOrder::new_trailing_stop(
+                Side::Sell,
+                TrailingOffset::Fraction(Decimal::<i64, 5>::one()),
+                BaseCurrency::new(5, 0),
+            ),
+            Err(OrderError::InvalidTrailingOffset)
+        );
+
+        let meta = ExchangeOrderMeta::new(0.into(), 0.into());
+        let mut sell = StopOrder::new_trailing_stop(
+            Side::Sell,
+            TrailingOffset::Absolute(QuoteCurrency::<i64, 5>::new(5, 0)),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone());
+        assert_eq!(sell.order_type(), StopOrderType::Market);
+        let trigger_price = sell.trailed_trigger_price(QuoteCurrency::new(100, 0));
+        assert_eq!(trigger_price, Some(QuoteCurrency::new(95, 0)));
+        sell.set_trigger_price(trigger_price.unwrap());
+        assert_eq!(sell.trailed_trigger_price(QuoteCurrency::new(99, 0)), None);
+        assert_eq!(
+            sell.trailed_trigger_price(QuoteCurrency::new(102, 0)),
+            Some(QuoteCurrency::new(97, 0))
+        );
+
+        let mut buy = StopOrder::new_trailing_stop(
+            Side::Buy,
+            TrailingOffset::Fraction(Decimal::<i64, 5>::try_from_scaled(1, 1).unwrap()),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta);
+        buy.set_trigger_price(
+            buy.trailed_trigger_price(QuoteCurrency::new(100, 0))
+                .unwrap(),
+        );
+        assert_eq!(buy.trigger_price(), QuoteCurrency::new(110, 0));
+        assert_eq!(buy.trailed_trigger_price(QuoteCurrency::new(101, 0)), None);
+        assert_eq!(
+            buy.trailed_trigger_price(QuoteCurrency::new(90, 0)),
+            Some(QuoteCurrency::new(99, 0))
+        );
+        assert!(buy.is_triggered(QuoteCurrency::new(110, 0)));
+    }
+
+    #[test]
+    fn stop_order_notional() {
+        let meta = ExchangeOrderMeta::new(0.into(), 0.into());
+        let order = StopOrder::new_stop_market(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone());
+        assert_eq!(order.notional(), QuoteCurrency::new(500, 0));
+
+        let order = StopOrder::new_stop_limit(
+            Side::Buy,
+            QuoteCurrency::<i64, 5>::new(100, 0),
+            QuoteCurrency::new(101, 0),
+            BaseCurrency::new(5, 0),
+        )
+        .unwrap()
+        .into_pending(meta.clone());
+        assert_eq!(order.notional(), QuoteCurrency::new(505, 0));
+        assert!(order.to_market_order(meta.clone()).is_none());
+        let limit_order = order.to_limit_order(meta).unwrap();
+        assert_eq!(limit_order.limit_price(), QuoteCurrency::new(101, 0));
+        assert_eq!(limit_order.remaining_quantity(), BaseCurrency::new(5, 0));
+        assert_eq!(limit_order.id(), order.id());
+    }
+}
@@ -0,0 +1,61 @@
+use super::TimestampNs;
+
+/// How long a limit order remains active before it is cancelled by the exchange.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// The order remains active until it is filled or cancelled.
+    #[default]
+    GoodTilCancelled,
+    /// The order takes whatever liquidity it can upon entry, regardless of its `RePricing`.
+    /// The unfilled remainder is cancelled immediately.
+    ImmediateOrCancel,
+    /// The order must be filled entirely upon entry, regardless of its `RePricing`,
+    /// otherwise it is cancelled without any fills.
+    /// Without a current order book depth, the away market quotation is assumed to fill it entirely.
+    FillOrKill,
+    /// The order remains active until it is filled, cancelled or the exchange time reaches the given timestamp.
+    GoodTilDate(TimestampNs),
+}
+
+impl TimeInForce {
+    /// Whether the order must be executed immediately upon entry.
+    #[inline]
+    pub fn is_immediate(&self) -> bool {
+        matches!(
+            self,
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill
+        )
+    }
+
+    /// Whether an order with this time in force has expired at `ts_ns`.
+    #[inline]
+    pub fn is_expired(&self, ts_ns: TimestampNs) -> bool {
+        match self {
+            TimeInForce::GoodTilDate(expiry_ts_ns) => ts_ns >= *expiry_ts_ns,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_in_force_is_expired() {
+        assert!(!TimeInForce::GoodTilCancelled.is_expired(i64::MAX.into()));
+        assert!(!TimeInForce::ImmediateOrCancel.is_expired(i64::MAX.into()));
+        let gtd = TimeInForce::GoodTilDate(10.into());
+        assert!(!gtd.is_expired(9.into()));
+        assert!(gtd.is_expired(10.into()));
+        assert!(gtd.is_expired(11.into()));
+    }
+
+    #[test]
+    fn time_in_force_is_immediate() {
+        assert!(!TimeInForce::GoodTilCancelled.is_immediate());
+        assert!(TimeInForce::ImmediateOrCancel.is_immediate());
+        assert!(TimeInForce::FillOrKill.is_immediate());
+        assert!(!TimeInForce::GoodTilDate(10.into()).is_immediate());
+    }
+}
@@ -0,0 +1,78 @@
+use num_traits::Zero;
+
+use super::{Mon, QuoteCurrency, Side};
+use crate::market_state::MarketState;
+
+/// The price source that is compared against the trigger price of a `StopOrder`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerBy {
+    /// Trigger on the last traded price, as provided by `Trade` updates.
+    #[default]
+    LastTrade,
+    /// Trigger on the mark price of the contract, according to its `MarkMethod`.
+    MarkPrice,
+    /// Trigger on the best bid and ask.
+    /// Buy stops are compared against the ask and sell stops against the bid.
+    BidAsk,
+}
+
+impl TriggerBy {
+    /// Get the reference price from the `MarketState` for an order of the given `side`.
+    /// Returns `None` if the price is not yet known.
+    pub fn reference_price<I, const D: u8>(
+        &self,
+        market_state: &MarketState<I, D>,
+        side: Side,
+    ) -> Option<QuoteCurrency<I, D>>
+    where
+        I: Mon<D>,
+    {
+        let price = match self {
+            TriggerBy::LastTrade => market_state.last_trade_price(),
+            TriggerBy::MarkPrice => market_state.mark_price(),
+            TriggerBy::BidAsk => match side {
+                Side::Buy => market_state.ask(),
+                Side::Sell => market_state.bid(),
+            },
+        };
+        (price > QuoteCurrency::zero()).then_some(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_by_reference_price() {
+        let state = MarketState::<i64, 5>::from_components(
+            QuoteCurrency::new(100, 0),
+            QuoteCurrency::new(102, 0),
+            QuoteCurrency::new(99, 0),
+            0.into(),
+            0,
+        );
+        assert_eq!(
+            TriggerBy::LastTrade.reference_price(&state, Side::Buy),
+            Some(QuoteCurrency::new(99, 0))
+        );
+        assert_eq!(
+            TriggerBy::MarkPrice.reference_price(&state, Side::Sell),
+            Some(QuoteCurrency::new(101, 0))
+        );
+        assert_eq!(
+            TriggerBy::BidAsk.reference_price(&state, Side::Buy),
+            Some(QuoteCurrency::new(102, 0))
+        );
+        assert_eq!(
+            TriggerBy::BidAsk.reference_price(&state, Side::Sell),
+            Some(QuoteCurrency::new(100, 0))
+        );
+
+        let state = MarketState::<i64, 5>::default();
+        assert_eq!(
+            TriggerBy::LastTrade.reference_price(&state, Side::Buy),
+            None
+        );
+    }
+}